@@ -0,0 +1,206 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use crate::services::import::{self, ImportOptions};
+use crate::services::index;
+use crate::services::pdf::{self, TaskFilter};
+use crate::services::site::{self, SiteOptions};
+
+const USAGE: &str = "\
+Usage:
+  ironpad                           Run the server
+  ironpad export site [<dir>] [--project <id>] [--include note,project,daily] [--title <title>]
+  ironpad export pdf note <id> [<file>] [--project <id>]
+  ironpad export pdf project <id> [<file>]
+  ironpad export pdf tasks [<file>] [--project <id>] [--section <name>] [--status open|completed]
+                          [--tag <tag>] [--priority <priority>] [--due-before <YYYY-MM-DD>]
+  ironpad import <vault> [--format obsidian|logseq] [--folder <folder>] [--tasks-project <id>]
+                         [--message <message>] [--apply]";
+
+/// Flags that take no value
+const SWITCHES: [&str; 1] = ["apply"];
+
+/// Run the command in `args` (without the program name) instead of the server.
+/// Returns the exit code, or None when there is no command.
+pub fn run(args: &[String]) -> Option<i32> {
+    let (command, rest) = args.split_first()?;
+
+    let result = match command.as_str() {
+        "export" => export(rest),
+        "import" => import_vault(rest),
+        "help" | "--help" | "-h" => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+        other => Err(format!("Unknown command: {}", other)),
+    };
+
+    match result {
+        Ok(()) => Some(0),
+        Err(err) => {
+            eprintln!("{}\n\n{}", err, USAGE);
+            Some(1)
+        }
+    }
+}
+
+fn export(args: &[String]) -> Result<(), String> {
+    let (kind, rest) = args
+        .split_first()
+        .ok_or_else(|| "Missing export kind".to_string())?;
+    let (positional, mut flags) = parse_flags(rest)?;
+
+    match kind.as_str() {
+        "site" => {
+            let options = SiteOptions {
+                project: flags.remove("project"),
+                include: flags
+                    .remove("include")
+                    .map(|include| include.split(',').map(String::from).collect()),
+                title: flags.remove("title"),
+            };
+            check_no_flags(&flags)?;
+
+            let out = match positional.as_slice() {
+                [] => site::default_out_dir(&options),
+                [dir] => PathBuf::from(dir),
+                _ => return Err("Too many arguments".to_string()),
+            };
+
+            index::build();
+            let export = site::export(&options, &out)?;
+            println!(
+                "Exported {} pages and {} assets to {}",
+                export.pages, export.assets, export.out
+            );
+            for path in &export.unpublished {
+                println!("  skipped (publish: false): {}", path);
+            }
+            Ok(())
+        }
+        "pdf" => {
+            let (what, rest) = positional
+                .split_first()
+                .ok_or_else(|| "Missing PDF kind (note, project or tasks)".to_string())?;
+
+            index::build();
+            let (bytes, name, file) = match (what.as_str(), rest) {
+                ("note", [id, file @ ..]) => {
+                    let project = flags.remove("project");
+                    check_no_flags(&flags)?;
+                    (pdf::note_pdf(id, project.as_deref())?, id.clone(), file)
+                }
+                ("project", [id, file @ ..]) => {
+                    check_no_flags(&flags)?;
+                    (pdf::project_pdf(id)?, id.clone(), file)
+                }
+                ("tasks", file) => {
+                    let filter = TaskFilter {
+                        project: flags.remove("project"),
+                        section: flags.remove("section"),
+                        status: flags.remove("status"),
+                        tag: flags.remove("tag"),
+                        priority: flags.remove("priority"),
+                        due_before: flags.remove("due-before"),
+                    };
+                    check_no_flags(&flags)?;
+                    (pdf::tasks_pdf(&filter)?, "tasks".to_string(), file)
+                }
+                ("note" | "project", []) => return Err(format!("Missing {} id", what)),
+                (other, _) => return Err(format!("Unknown PDF kind: {}", other)),
+            };
+
+            let out = match file {
+                [] => PathBuf::from(format!("{}.pdf", name)),
+                [file] => PathBuf::from(file),
+                _ => return Err("Too many arguments".to_string()),
+            };
+            std::fs::write(&out, &bytes)
+                .map_err(|e| format!("Failed to write {}: {}", out.display(), e))?;
+            println!("Wrote {} ({} bytes)", out.display(), bytes.len());
+            Ok(())
+        }
+        other => Err(format!("Unknown export kind: {}", other)),
+    }
+}
+
+/// Import a vault: report what would be written, or write it with `--apply`.
+fn import_vault(args: &[String]) -> Result<(), String> {
+    let (positional, mut flags) = parse_flags(args)?;
+    let options = ImportOptions {
+        format: flags.remove("format"),
+        folder: flags.remove("folder"),
+        tasks_project: flags.remove("tasks-project"),
+        apply: flags.remove("apply").is_some(),
+        message: flags.remove("message"),
+    };
+    check_no_flags(&flags)?;
+    let source = match positional.as_slice() {
+        [source] => PathBuf::from(source),
+        [] => return Err("Missing vault folder".to_string()),
+        _ => return Err("Too many arguments".to_string()),
+    };
+
+    index::build();
+    let report = import::import(&source, &options)?;
+    let verb = if report.applied {
+        "Imported"
+    } else {
+        "Would import"
+    };
+    println!("{} from {} ({}):", verb, report.source, report.format);
+    for file in report.notes.iter().chain(&report.assets) {
+        println!("  {} -> {}", file.from, file.to);
+    }
+    for task in &report.tasks {
+        println!("  task from {}: {} -> {}", task.from, task.title, task.to);
+    }
+    for file in &report.skipped {
+        println!("  skipped {}: {}", file.path, file.reason);
+    }
+    for link in &report.unresolved {
+        println!("  unresolved link in {}: {}", link.file, link.target);
+    }
+    println!(
+        "{} notes, {} assets, {} tasks, {} links rewritten",
+        report.notes.len(),
+        report.assets.len(),
+        report.tasks.len(),
+        report.links_rewritten
+    );
+    if !report.applied {
+        println!("Dry run: nothing was written. Run again with --apply to import.");
+    }
+    Ok(())
+}
+
+/// Split `--name value` flags from positional arguments.
+fn parse_flags(args: &[String]) -> Result<(Vec<String>, HashMap<String, String>), String> {
+    let mut positional = Vec::new();
+    let mut flags = HashMap::new();
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        match arg.strip_prefix("--") {
+            Some(name) if SWITCHES.contains(&name) => {
+                flags.insert(name.to_string(), String::new());
+            }
+            Some(name) => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| format!("Missing value for --{}", name))?;
+                flags.insert(name.to_string(), value.clone());
+            }
+            None => positional.push(arg.clone()),
+        }
+    }
+
+    Ok((positional, flags))
+}
+
+fn check_no_flags(flags: &HashMap<String, String>) -> Result<(), String> {
+    match flags.keys().next() {
+        Some(name) => Err(format!("Unknown option: --{}", name)),
+        None => Ok(()),
+    }
+}
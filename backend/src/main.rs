@@ -7,6 +7,7 @@ use tower_http::cors::CorsLayer;
 use tower_http::services::ServeDir;
 use tracing::{info, warn};
 
+mod cli;
 pub mod config;
 mod models;
 mod routes;
@@ -34,6 +35,15 @@ async fn main() {
     // Resolve data directory (production vs development mode)
     config::init_data_dir();
 
+    // Run a command-line command (e.g. `ironpad export site`) instead of the server
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let Some(code) = cli::run(&args) {
+        std::process::exit(code);
+    }
+
+    // Build the in-memory search index (kept current by the watcher and our own writes)
+    services::index::build();
+
     // Find port and bind (listener kept alive to avoid race condition)
     let (listener, port) = find_available_port().await;
 
@@ -65,16 +75,38 @@ async fn main() {
             get(routes::notes::list_notes).post(routes::notes::create_note),
         )
         .nest("/notes", routes::notes::router())
+        // Note folders
+        .nest("/folders", routes::folders::router())
         // Tasks
         .nest("/tasks", routes::tasks::router())
         // Search
         .nest("/search", routes::search::router())
+        // Quick switcher
+        .nest("/switcher", routes::switcher::router())
+        // Link graph
+        .nest("/graph", routes::graph::router())
         // Git
         .nest("/git", routes::git::router())
         // Projects
         .nest("/projects", routes::projects::router())
         // Daily notes
         .nest("/daily", routes::daily::router())
+        // Rendered markdown
+        .nest("/render", routes::render::router())
+        // Static site export
+        .nest("/export", routes::export::router())
+        // Obsidian / Logseq vault import
+        .nest("/import", routes::import::router())
+        // Saved views
+        .nest("/views", routes::views::router())
+        // Frontmatter schemas
+        .nest("/schemas", routes::schemas::router())
+        // Tags
+        .nest("/tags", routes::tags::router())
+        // Templates
+        .nest("/templates", routes::templates::router())
+        // Archive
+        .nest("/archive", routes::archive::router())
         // Assets
         .nest("/assets", routes::assets::router());
 
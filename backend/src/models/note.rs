@@ -1,4 +1,4 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 /// Lightweight note representation for list views.
 /// Read-only, derived from filesystem + frontmatter.
@@ -20,4 +20,36 @@ pub struct Note {
     pub note_type: String,
     pub frontmatter: serde_yaml::Mapping,
     pub content: String,
+    /// Where a new note's template put `{{cursor}}` (character offset into `content`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<usize>,
+    /// ETag of the file as read or written (sent as a header, see `etag::of`)
+    #[serde(skip)]
+    pub etag: String,
+}
+
+/// Options for a new standalone note.
+/// Body of POST /api/notes (every field optional)
+#[derive(Debug, Default, Deserialize)]
+pub struct NewNote {
+    /// Also names the file (as a slug); timestamp filename if omitted
+    pub title: Option<String>,
+    /// Folder under `notes/`, created if missing
+    pub folder: Option<String>,
+    /// Initial body (replaces the template body)
+    pub content: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Name of a template in `data/templates/` (defaults to the vault's note template)
+    pub template: Option<String>,
+}
+
+/// Frontmatter changes for PUT /api/notes/:id/meta
+#[derive(Debug, Default, Deserialize)]
+pub struct NoteMetaUpdate {
+    pub title: Option<String>,
+    pub tags: Option<Vec<String>>,
+    /// Rename the file after the new title (the id stays the same)
+    #[serde(default)]
+    pub rename_file: bool,
 }
@@ -0,0 +1,76 @@
+use axum::{
+    extract::{Path, Query},
+    http::StatusCode,
+    response::IntoResponse,
+    routing::{get, post},
+    Json, Router,
+};
+use serde::Deserialize;
+
+use crate::services::archive;
+
+#[derive(Debug, Deserialize)]
+pub struct ArchiveQuery {
+    q: Option<String>,
+    /// Only items of this type (note, task, view, ...)
+    #[serde(rename = "type")]
+    item_type: Option<String>,
+}
+
+pub fn router() -> Router {
+    Router::new()
+        .route("/", get(list_archive))
+        .route("/{name}", get(get_archived).delete(purge_archived))
+        .route("/{name}/restore", post(restore_archived))
+}
+
+async fn list_archive(Query(params): Query<ArchiveQuery>) -> impl IntoResponse {
+    match archive::list(params.q.as_deref(), params.item_type.as_deref()) {
+        Ok(items) => Json(items).into_response(),
+        Err(err) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to list archive: {}", err),
+        )
+            .into_response(),
+    }
+}
+
+async fn get_archived(Path(name): Path<String>) -> impl IntoResponse {
+    match archive::get(&name) {
+        Ok(item) => Json(item).into_response(),
+        Err(err) if err.contains("not found") => (StatusCode::NOT_FOUND, err).into_response(),
+        Err(err) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to read archived item: {}", err),
+        )
+            .into_response(),
+    }
+}
+
+/// Move an item back to the path it was archived from.
+async fn restore_archived(Path(name): Path<String>) -> impl IntoResponse {
+    match archive::restore(&name) {
+        Ok(item) => Json(item).into_response(),
+        Err(err) if err.contains("not found") => (StatusCode::NOT_FOUND, err).into_response(),
+        Err(err) if err.contains("already exists") => (StatusCode::CONFLICT, err).into_response(),
+        Err(err) if err.starts_with("Invalid") => (StatusCode::BAD_REQUEST, err).into_response(),
+        Err(err) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to restore item: {}", err),
+        )
+            .into_response(),
+    }
+}
+
+/// Delete an archived item for good.
+async fn purge_archived(Path(name): Path<String>) -> impl IntoResponse {
+    match archive::purge(&name) {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(err) if err.contains("not found") => (StatusCode::NOT_FOUND, err).into_response(),
+        Err(err) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to purge item: {}", err),
+        )
+            .into_response(),
+    }
+}
@@ -1,14 +1,23 @@
 use axum::{
-    body::Bytes, extract::Path, http::StatusCode, response::IntoResponse, routing::get, Json,
-    Router,
+    body::Bytes,
+    extract::Path,
+    http::{HeaderMap, StatusCode},
+    response::IntoResponse,
+    routing::get,
+    Json, Router,
 };
 use chrono::{NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 use std::fs;
 
 use crate::config;
+use crate::routes::{conflict, if_match, invalid_frontmatter, with_etag};
+use crate::services::etag;
 use crate::services::filesystem;
 use crate::services::frontmatter;
+use crate::services::recent;
+use crate::services::schemas::{self, WriteError};
+use crate::services::templates::{self, TemplateContext};
 
 #[derive(Debug, Serialize)]
 pub struct DailyNote {
@@ -17,6 +26,12 @@ pub struct DailyNote {
     pub path: String,
     pub content: String,
     pub frontmatter: serde_yaml::Mapping,
+    /// Where a new note's template put `{{cursor}}` (character offset into `content`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<usize>,
+    /// ETag of the file as read or written (sent as a header, see `etag::of`)
+    #[serde(skip)]
+    pub etag: String,
 }
 
 #[derive(Debug, Serialize)]
@@ -81,7 +96,7 @@ fn list_daily_notes_impl() -> Result<Vec<DailyNoteSummary>, String> {
         let (fm, _, _) = frontmatter::parse_frontmatter(&content);
 
         let title = fm
-            .get(&serde_yaml::Value::from("title"))
+            .get(serde_yaml::Value::from("title"))
             .and_then(|v| v.as_str())
             .map(String::from)
             .unwrap_or_else(|| filename.to_string());
@@ -105,11 +120,11 @@ async fn get_or_create_today() -> impl IntoResponse {
     let today = Utc::now().format("%Y-%m-%d").to_string();
 
     match get_daily_note_impl(&today) {
-        Ok(note) => Json(note).into_response(),
+        Ok(note) => with_etag(StatusCode::OK, &note.etag, &note),
         Err(_) => {
-            // Note doesn't exist, create it with default template
-            match create_daily_note_impl(&today, None) {
-                Ok(note) => (StatusCode::CREATED, Json(note)).into_response(),
+            // Note doesn't exist, create it with the vault's daily template
+            match create_daily_note_impl(&today, None, None) {
+                Ok(note) => with_etag(StatusCode::CREATED, &note.etag, &note),
                 Err(err) => (
                     StatusCode::INTERNAL_SERVER_ERROR,
                     format!("Failed to create today's note: {}", err),
@@ -132,7 +147,10 @@ async fn get_daily_note(Path(date): Path<String>) -> impl IntoResponse {
     }
 
     match get_daily_note_impl(&date) {
-        Ok(note) => Json(note).into_response(),
+        Ok(note) => {
+            recent::record_access(&note.path);
+            with_etag(StatusCode::OK, &note.etag, &note)
+        }
         Err(err) if err.contains("not found") => (StatusCode::NOT_FOUND, err).into_response(),
         Err(err) => (
             StatusCode::INTERNAL_SERVER_ERROR,
@@ -159,12 +177,16 @@ fn get_daily_note_impl(date: &str) -> Result<DailyNote, String> {
         path: format!("daily/{}.md", date),
         content: body,
         frontmatter: fm,
+        cursor: None,
+        etag: etag::of(content.as_bytes()),
     })
 }
 
 #[derive(Debug, Deserialize)]
 pub struct CreateDailyNoteRequest {
     pub content: Option<String>,
+    /// Template name in `data/templates/` (defaults to the vault's daily template)
+    pub template: Option<String>,
 }
 
 /// Create a daily note (optionally with content)
@@ -181,11 +203,19 @@ async fn create_daily_note(
             .into_response();
     }
 
-    let content = body.and_then(|b| b.content.clone());
+    let request = body.map(|Json(request)| request);
+    let content = request.as_ref().and_then(|r| r.content.as_deref());
+    let template = request.as_ref().and_then(|r| r.template.as_deref());
 
-    match create_daily_note_impl(&date, content.as_deref()) {
-        Ok(note) => (StatusCode::CREATED, Json(note)).into_response(),
-        Err(err) if err.contains("already exists") => (StatusCode::CONFLICT, err).into_response(),
+    match create_daily_note_impl(&date, content, template) {
+        Ok(note) => with_etag(StatusCode::CREATED, &note.etag, &note),
+        Err(WriteError::Invalid(report)) => invalid_frontmatter(report),
+        Err(WriteError::Other(err)) if err.starts_with("Invalid") => {
+            (StatusCode::BAD_REQUEST, err).into_response()
+        }
+        Err(WriteError::Other(err)) if err.contains("already exists") => {
+            (StatusCode::CONFLICT, err).into_response()
+        }
         Err(err) => (
             StatusCode::INTERNAL_SERVER_ERROR,
             format!("Failed to create daily note: {}", err),
@@ -194,7 +224,11 @@ async fn create_daily_note(
     }
 }
 
-fn create_daily_note_impl(date: &str, initial_content: Option<&str>) -> Result<DailyNote, String> {
+fn create_daily_note_impl(
+    date: &str,
+    initial_content: Option<&str>,
+    template: Option<&str>,
+) -> Result<DailyNote, WriteError> {
     let daily_dir = config::data_dir().join("daily");
 
     // Create directory if it doesn't exist
@@ -205,7 +239,7 @@ fn create_daily_note_impl(date: &str, initial_content: Option<&str>) -> Result<D
     let note_path = daily_dir.join(format!("{}.md", date));
 
     if note_path.exists() {
-        return Err(format!("Daily note already exists: {}", date));
+        return Err(format!("Daily note already exists: {}", date).into());
     }
 
     let now = Utc::now().to_rfc3339();
@@ -214,6 +248,13 @@ fn create_daily_note_impl(date: &str, initial_content: Option<&str>) -> Result<D
     let parsed_date = NaiveDate::parse_from_str(date, "%Y-%m-%d").map_err(|e| e.to_string())?;
     let display_date = parsed_date.format("%A, %B %d, %Y").to_string();
 
+    let ctx = TemplateContext {
+        title: display_date.clone(),
+        date: parsed_date,
+        project: None,
+    };
+    let rendered = templates::for_new("daily", template, &ctx)?;
+
     // Create frontmatter
     let mut fm = serde_yaml::Mapping::new();
     fm.insert(
@@ -232,6 +273,13 @@ fn create_daily_note_impl(date: &str, initial_content: Option<&str>) -> Result<D
         serde_yaml::Value::from("date"),
         serde_yaml::Value::from(date),
     );
+    if let Some(rendered) = &rendered {
+        for (key, value) in &rendered.frontmatter {
+            if key.as_str() != Some("date") {
+                fm.insert(key.clone(), value.clone());
+            }
+        }
+    }
     fm.insert(
         serde_yaml::Value::from("created"),
         serde_yaml::Value::from(now.clone()),
@@ -241,14 +289,20 @@ fn create_daily_note_impl(date: &str, initial_content: Option<&str>) -> Result<D
         serde_yaml::Value::from(now),
     );
 
-    // Use provided content or default template
-    let body = initial_content.map(|c| c.to_string()).unwrap_or_else(|| {
-        format!(
-            "# {}\n\n## Today's Focus\n\n- \n\n## Notes\n\n\n\n## Tasks\n\n- [ ] \n",
-            display_date
-        )
-    });
-
+    // Use provided content, the template, or the built-in layout
+    let (body, cursor) = match (initial_content, rendered) {
+        (Some(content), _) => (content.to_string(), None),
+        (None, Some(rendered)) => (rendered.body, rendered.cursor),
+        (None, None) => (
+            format!(
+                "# {}\n\n## Today's Focus\n\n- \n\n## Notes\n\n\n\n## Tasks\n\n- [ ] \n",
+                display_date
+            ),
+            None,
+        ),
+    };
+
+    schemas::check_new(&note_path, &mut fm)?;
     let content = frontmatter::serialize_frontmatter(&fm, &body)?;
 
     filesystem::atomic_write(&note_path, content.as_bytes())?;
@@ -259,11 +313,17 @@ fn create_daily_note_impl(date: &str, initial_content: Option<&str>) -> Result<D
         path: format!("daily/{}.md", date),
         content: body,
         frontmatter: fm,
+        cursor,
+        etag: etag::of(content.as_bytes()),
     })
 }
 
-/// Update a daily note's content
-async fn update_daily_note(Path(date): Path<String>, body: Bytes) -> impl IntoResponse {
+/// Update a daily note's content. With `If-Match`, a stale version gets 409 and the current note.
+async fn update_daily_note(
+    Path(date): Path<String>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> impl IntoResponse {
     // Validate date format
     if NaiveDate::parse_from_str(&date, "%Y-%m-%d").is_err() {
         return (
@@ -275,9 +335,11 @@ async fn update_daily_note(Path(date): Path<String>, body: Bytes) -> impl IntoRe
 
     let content = String::from_utf8_lossy(&body).to_string();
 
-    match update_daily_note_impl(&date, &content) {
-        Ok(note) => Json(note).into_response(),
+    match update_daily_note_impl(&date, &content, if_match(&headers)) {
+        Ok(note) => with_etag(StatusCode::OK, &note.etag, &note),
         Err(err) if err.contains("not found") => (StatusCode::NOT_FOUND, err).into_response(),
+        Err(err) if err.starts_with("Conflict") => conflict(get_daily_note(Path(date)).await),
+        Err(err) if err.starts_with("Invalid") => (StatusCode::BAD_REQUEST, err).into_response(),
         Err(err) => (
             StatusCode::INTERNAL_SERVER_ERROR,
             format!("Failed to update daily note: {}", err),
@@ -286,7 +348,11 @@ async fn update_daily_note(Path(date): Path<String>, body: Bytes) -> impl IntoRe
     }
 }
 
-fn update_daily_note_impl(date: &str, new_content: &str) -> Result<DailyNote, String> {
+fn update_daily_note_impl(
+    date: &str,
+    new_content: &str,
+    if_match: Option<&str>,
+) -> Result<DailyNote, String> {
     let daily_dir = config::data_dir().join("daily");
     let note_path = daily_dir.join(format!("{}.md", date));
 
@@ -296,6 +362,7 @@ fn update_daily_note_impl(date: &str, new_content: &str) -> Result<DailyNote, St
 
     // Read existing file to preserve frontmatter
     let existing_content = fs::read_to_string(&note_path).map_err(|e| e.to_string())?;
+    etag::check(if_match, existing_content.as_bytes())?;
     let (mut fm, _, _) = frontmatter::parse_frontmatter(&existing_content);
 
     // Update the 'updated' timestamp
@@ -306,7 +373,7 @@ fn update_daily_note_impl(date: &str, new_content: &str) -> Result<DailyNote, St
     );
 
     // Serialize with updated frontmatter and new content (atomic write)
-    let file_content = frontmatter::serialize_frontmatter(&fm, new_content)?;
+    let file_content = frontmatter::rewrite(&existing_content, &fm, new_content)?;
 
     filesystem::atomic_write(&note_path, file_content.as_bytes())?;
 
@@ -316,5 +383,7 @@ fn update_daily_note_impl(date: &str, new_content: &str) -> Result<DailyNote, St
         path: format!("daily/{}.md", date),
         content: new_content.to_string(),
         frontmatter: fm,
+        cursor: None,
+        etag: etag::of(file_content.as_bytes()),
     })
 }
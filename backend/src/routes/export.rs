@@ -0,0 +1,86 @@
+use axum::{
+    extract::{Path, Query},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use serde::Deserialize;
+
+use crate::services::filesystem;
+use crate::services::pdf::{self, TaskFilter};
+use crate::services::site::{self, SiteOptions};
+
+#[derive(Debug, Deserialize)]
+pub struct PdfNoteQuery {
+    /// Project of a project note (ids are only unique per folder)
+    project: Option<String>,
+}
+
+pub fn router() -> Router {
+    Router::new()
+        .route("/site", post(export_site))
+        .route("/pdf/note/{id}", get(export_note_pdf))
+        .route("/pdf/project/{id}", get(export_project_pdf))
+        .route("/pdf/tasks", get(export_tasks_pdf))
+}
+
+/// Write a static HTML site of the vault or a single project to `exports/`.
+/// The output folder is fixed here; only the CLI can choose another one.
+async fn export_site(payload: Option<Json<SiteOptions>>) -> impl IntoResponse {
+    let options = payload.map(|Json(options)| options).unwrap_or_default();
+    let out = site::default_out_dir(&options);
+
+    match site::export(&options, &out) {
+        Ok(export) => (StatusCode::CREATED, Json(export)).into_response(),
+        Err(err) if err.starts_with("Invalid") => (StatusCode::BAD_REQUEST, err).into_response(),
+        Err(err) if err.contains("not found") => (StatusCode::NOT_FOUND, err).into_response(),
+        Err(err) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to export site: {}", err),
+        )
+            .into_response(),
+    }
+}
+
+/// A note as a PDF download.
+async fn export_note_pdf(Path(id): Path<String>, Query(params): Query<PdfNoteQuery>) -> Response {
+    pdf_response(pdf::note_pdf(&id, params.project.as_deref()), &id)
+}
+
+/// A project (index, notes and tasks by section) as a PDF download.
+async fn export_project_pdf(Path(id): Path<String>) -> Response {
+    pdf_response(pdf::project_pdf(&id), &id)
+}
+
+/// Tasks matching the query as a PDF table.
+async fn export_tasks_pdf(Query(filter): Query<TaskFilter>) -> Response {
+    let name = match &filter.project {
+        Some(project) => format!("{}-tasks", project),
+        None => "tasks".to_string(),
+    };
+    pdf_response(pdf::tasks_pdf(&filter), &name)
+}
+
+fn pdf_response(result: Result<Vec<u8>, String>, name: &str) -> Response {
+    match result {
+        Ok(bytes) => (
+            [
+                (header::CONTENT_TYPE, "application/pdf".to_string()),
+                (
+                    header::CONTENT_DISPOSITION,
+                    format!("attachment; filename=\"{}.pdf\"", filesystem::slugify(name)),
+                ),
+            ],
+            bytes,
+        )
+            .into_response(),
+        Err(err) if err.starts_with("Invalid") => (StatusCode::BAD_REQUEST, err).into_response(),
+        Err(err) if err.contains("not found") => (StatusCode::NOT_FOUND, err).into_response(),
+        Err(err) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to export PDF: {}", err),
+        )
+            .into_response(),
+    }
+}
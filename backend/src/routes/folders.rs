@@ -0,0 +1,107 @@
+use axum::{
+    extract::Query,
+    http::StatusCode,
+    response::IntoResponse,
+    routing::{post, put},
+    Json, Router,
+};
+use serde::Deserialize;
+
+use crate::services::folders;
+
+#[derive(Debug, Deserialize)]
+pub struct CreateFolderRequest {
+    /// Folder path relative to `notes/`, e.g. `guides/databases`
+    pub path: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DeleteFolderQuery {
+    path: String,
+    /// Archive the notes inside instead of refusing a non-empty folder
+    #[serde(default)]
+    recursive: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MoveFolderRequest {
+    /// Folder paths relative to `notes/`
+    pub from: String,
+    pub to: String,
+    /// Commit message (defaults to "Move {from} to {to}")
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FolderOrderRequest {
+    /// Folder path relative to `notes/` (empty for `notes/` itself)
+    #[serde(default)]
+    pub path: String,
+    /// Entry names (sub-folders and note files) in display order
+    pub order: Vec<String>,
+}
+
+pub fn router() -> Router {
+    Router::new()
+        .route("/", post(create_folder).delete(delete_folder))
+        .route("/move", post(move_folder))
+        .route("/order", put(set_order))
+}
+
+async fn create_folder(Json(payload): Json<CreateFolderRequest>) -> impl IntoResponse {
+    match folders::create_folder(&payload.path) {
+        Ok(folder) => (StatusCode::CREATED, Json(folder)).into_response(),
+        Err(err) if err.starts_with("Invalid") => (StatusCode::BAD_REQUEST, err).into_response(),
+        Err(err) if err.contains("already exists") => (StatusCode::CONFLICT, err).into_response(),
+        Err(err) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to create folder: {}", err),
+        )
+            .into_response(),
+    }
+}
+
+/// Delete a folder; notes inside go to the archive.
+async fn delete_folder(Query(params): Query<DeleteFolderQuery>) -> impl IntoResponse {
+    match folders::delete_folder(&params.path, params.recursive) {
+        Ok(deleted) => Json(deleted).into_response(),
+        Err(err) if err.starts_with("Invalid") => (StatusCode::BAD_REQUEST, err).into_response(),
+        Err(err) if err.contains("not found") => (StatusCode::NOT_FOUND, err).into_response(),
+        Err(err) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to delete folder: {}", err),
+        )
+            .into_response(),
+    }
+}
+
+/// Move or rename a folder, rewriting links to the notes inside.
+async fn move_folder(Json(payload): Json<MoveFolderRequest>) -> impl IntoResponse {
+    let result = folders::move_folder(&payload.from, &payload.to, payload.message.as_deref());
+
+    match result {
+        Ok(result) => Json(result).into_response(),
+        Err(err) if err.starts_with("Invalid") => (StatusCode::BAD_REQUEST, err).into_response(),
+        Err(err) if err.contains("not found") => (StatusCode::NOT_FOUND, err).into_response(),
+        Err(err) if err.contains("already exists") => (StatusCode::CONFLICT, err).into_response(),
+        Err(err) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to move folder: {}", err),
+        )
+            .into_response(),
+    }
+}
+
+/// Store the manual order of a folder's entries.
+async fn set_order(Json(payload): Json<FolderOrderRequest>) -> impl IntoResponse {
+    match folders::set_order(&payload.path, &payload.order) {
+        Ok(folder) => Json(folder).into_response(),
+        Err(err) if err.starts_with("Invalid") => (StatusCode::BAD_REQUEST, err).into_response(),
+        Err(err) if err.contains("not found") => (StatusCode::NOT_FOUND, err).into_response(),
+        Err(err) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to save folder order: {}", err),
+        )
+            .into_response(),
+    }
+}
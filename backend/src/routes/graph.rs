@@ -0,0 +1,51 @@
+use axum::{extract::Query, http::StatusCode, response::IntoResponse, routing::get, Json, Router};
+use serde::Deserialize;
+
+use crate::services::graph::{self, GraphFilter};
+
+/// Default and maximum hops from the focus node
+const DEFAULT_DEPTH: usize = 1;
+const MAX_DEPTH: usize = 5;
+
+#[derive(Debug, Deserialize)]
+pub struct GraphQuery {
+    project: Option<String>,
+    /// Comma-separated node kinds: note, task, project, daily, tag
+    #[serde(rename = "type")]
+    kinds: Option<String>,
+    tag: Option<String>,
+    focus: Option<String>,
+    depth: Option<usize>,
+}
+
+pub fn router() -> Router {
+    Router::new().route("/", get(get_graph))
+}
+
+/// Nodes and edges of the vault for the graph view
+async fn get_graph(Query(params): Query<GraphQuery>) -> impl IntoResponse {
+    let filter = GraphFilter {
+        project: params.project,
+        kinds: params.kinds.map(|kinds| {
+            kinds
+                .split(',')
+                .map(|k| k.trim().to_lowercase())
+                .filter(|k| !k.is_empty())
+                .collect()
+        }),
+        tag: params.tag,
+        focus: params.focus,
+        depth: params.depth.unwrap_or(DEFAULT_DEPTH).min(MAX_DEPTH),
+    };
+
+    match graph::graph(&filter) {
+        Ok(graph) => Json(graph).into_response(),
+        Err(err) if err.starts_with("Invalid") => (StatusCode::BAD_REQUEST, err).into_response(),
+        Err(err) if err.contains("not found") => (StatusCode::NOT_FOUND, err).into_response(),
+        Err(err) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to build graph: {}", err),
+        )
+            .into_response(),
+    }
+}
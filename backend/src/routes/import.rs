@@ -0,0 +1,33 @@
+use std::path::Path;
+
+use axum::{http::StatusCode, response::IntoResponse, routing::post, Json, Router};
+use serde::Deserialize;
+
+use crate::services::import::{self, ImportOptions};
+
+#[derive(Debug, Deserialize)]
+pub struct ImportRequest {
+    /// Folder of the Obsidian or Logseq vault
+    pub source: String,
+    #[serde(flatten)]
+    pub options: ImportOptions,
+}
+
+pub fn router() -> Router {
+    Router::new().route("/", post(import_vault))
+}
+
+/// Report what importing a vault would write, or write it with `apply`.
+async fn import_vault(Json(request): Json<ImportRequest>) -> impl IntoResponse {
+    match import::import(Path::new(&request.source), &request.options) {
+        Ok(report) if report.applied => (StatusCode::CREATED, Json(report)).into_response(),
+        Ok(report) => Json(report).into_response(),
+        Err(err) if err.starts_with("Invalid") => (StatusCode::BAD_REQUEST, err).into_response(),
+        Err(err) if err.contains("not found") => (StatusCode::NOT_FOUND, err).into_response(),
+        Err(err) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to import vault: {}", err),
+        )
+            .into_response(),
+    }
+}
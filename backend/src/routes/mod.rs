@@ -1,7 +1,69 @@
+pub mod archive;
 pub mod assets;
 pub mod daily;
+pub mod export;
+pub mod folders;
 pub mod git;
+pub mod graph;
+pub mod import;
 pub mod notes;
 pub mod projects;
+pub mod render;
+pub mod schemas;
 pub mod search;
+pub mod switcher;
+pub mod tags;
 pub mod tasks;
+pub mod templates;
+pub mod views;
+
+use axum::{
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Serialize;
+
+use crate::services::schemas::DocViolations;
+
+/// The `If-Match` header of a write, checked with `etag::check`.
+pub fn if_match(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(header::IF_MATCH)
+        .and_then(|value| value.to_str().ok())
+}
+
+/// A JSON document with the ETag of the bytes it was built from (see `etag::of`).
+pub fn with_etag<T: Serialize>(status: StatusCode, etag: &str, body: &T) -> Response {
+    (status, [(header::ETAG, etag.to_string())], Json(body)).into_response()
+}
+
+/// The answer to a write with a stale `If-Match`: 409 with the current
+/// version of the document (from its GET handler), so the client can merge.
+pub fn conflict(current: impl IntoResponse) -> Response {
+    let mut response = current.into_response();
+    if response.status().is_success() {
+        *response.status_mut() = StatusCode::CONFLICT;
+    }
+    response
+}
+
+#[derive(Serialize)]
+struct SchemaError {
+    error: &'static str,
+    #[serde(flatten)]
+    report: DocViolations,
+}
+
+/// 400 for a write whose frontmatter breaks the vault's schema. The
+/// violations come back as JSON so the client can point at the offending fields.
+pub fn invalid_frontmatter(report: DocViolations) -> Response {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(SchemaError {
+            error: "Invalid frontmatter",
+            report,
+        }),
+    )
+        .into_response()
+}
@@ -1,13 +1,70 @@
-use axum::{extract::Path, http::StatusCode, response::IntoResponse, routing::get, Json, Router};
+use axum::{
+    extract::{Path, Query},
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    routing::{get, post, put},
+    Json, Router,
+};
+use serde::Deserialize;
 
-use crate::models::note::{Note, NoteSummary};
+use crate::config;
+use crate::models::note::{NewNote, NoteMetaUpdate, NoteSummary};
+use crate::routes::{conflict, if_match, invalid_frontmatter, with_etag};
 use crate::services::filesystem;
+use crate::services::folders;
+use crate::services::links;
+use crate::services::recent;
+use crate::services::related;
+use crate::services::rename;
+use crate::services::schemas::WriteError;
+
+/// Default and maximum number of related notes
+const DEFAULT_RELATED_LIMIT: usize = 10;
+const MAX_RELATED_LIMIT: usize = 50;
+
+#[derive(Debug, Deserialize)]
+pub struct ListNotesQuery {
+    #[serde(default)]
+    tree: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RelatedQuery {
+    limit: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MoveNoteRequest {
+    /// Data-relative destination, e.g. `notes/guides/postgres.md`
+    pub to: String,
+    /// New id; the current one is kept if omitted
+    pub id: Option<String>,
+    /// Commit message (defaults to "Move {from} to {to}")
+    pub message: Option<String>,
+}
 
 pub fn router() -> Router {
-    Router::new().route("/{id}", get(get_note).put(update_note).delete(delete_note))
+    Router::new()
+        .route("/{id}", get(get_note).put(update_note).delete(delete_note))
+        .route("/{id}/meta", put(update_note_meta))
+        .route("/{id}/related", get(get_related_notes))
+        .route("/{id}/backlinks", get(get_note_backlinks))
+        .route("/{id}/move", post(move_note))
 }
 
-pub async fn list_notes() -> impl IntoResponse {
+/// Flat list of notes, or the `notes/` folder tree with `?tree=true`
+pub async fn list_notes(Query(params): Query<ListNotesQuery>) -> impl IntoResponse {
+    if params.tree {
+        return match folders::tree() {
+            Ok(tree) => Json(tree).into_response(),
+            Err(err) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to list notes: {}", err),
+            )
+                .into_response(),
+        };
+    }
+
     match filesystem::list_notes() {
         Ok(notes) => Json::<Vec<NoteSummary>>(notes).into_response(),
         Err(err) => (
@@ -20,7 +77,10 @@ pub async fn list_notes() -> impl IntoResponse {
 
 async fn get_note(Path(id): Path<String>) -> impl IntoResponse {
     match filesystem::read_note_by_id(&id) {
-        Ok(note) => Json::<Note>(note).into_response(),
+        Ok(note) => {
+            recent::record_access(&note.path);
+            with_etag(StatusCode::OK, &note.etag, &note)
+        }
         Err(err) if err.starts_with("Note not found") => {
             (StatusCode::NOT_FOUND, err).into_response()
         }
@@ -32,9 +92,16 @@ async fn get_note(Path(id): Path<String>) -> impl IntoResponse {
     }
 }
 
-pub async fn create_note() -> impl IntoResponse {
-    match filesystem::create_note() {
-        Ok(note) => (StatusCode::CREATED, Json::<Note>(note)).into_response(),
+/// Create a note; the body (title, folder, content, tags, template) is optional.
+pub async fn create_note(payload: Option<Json<NewNote>>) -> impl IntoResponse {
+    let new = payload.map(|Json(new)| new).unwrap_or_default();
+
+    match filesystem::create_note(&new) {
+        Ok(note) => with_etag(StatusCode::CREATED, &note.etag, &note),
+        Err(WriteError::Invalid(report)) => invalid_frontmatter(report),
+        Err(WriteError::Other(err)) if err.starts_with("Invalid") => {
+            (StatusCode::BAD_REQUEST, err).into_response()
+        }
         Err(err) => (
             StatusCode::INTERNAL_SERVER_ERROR,
             format!("Failed to create note: {}", err),
@@ -43,12 +110,19 @@ pub async fn create_note() -> impl IntoResponse {
     }
 }
 
-async fn update_note(Path(id): Path<String>, body: String) -> impl IntoResponse {
-    match filesystem::update_note(&id, &body) {
-        Ok(note) => Json::<Note>(note).into_response(),
+/// Replace a note's body. With `If-Match`, a stale version gets 409 and the current note.
+async fn update_note(
+    Path(id): Path<String>,
+    headers: HeaderMap,
+    body: String,
+) -> impl IntoResponse {
+    match filesystem::update_note(&id, &body, if_match(&headers)) {
+        Ok(note) => with_etag(StatusCode::OK, &note.etag, &note),
         Err(err) if err.starts_with("Note not found") => {
             (StatusCode::NOT_FOUND, err).into_response()
         }
+        Err(err) if err.starts_with("Conflict") => conflict(get_note(Path(id)).await),
+        Err(err) if err.starts_with("Invalid") => (StatusCode::BAD_REQUEST, err).into_response(),
         Err(err) => (
             StatusCode::INTERNAL_SERVER_ERROR,
             format!("Failed to update note: {}", err),
@@ -57,6 +131,32 @@ async fn update_note(Path(id): Path<String>, body: String) -> impl IntoResponse
     }
 }
 
+/// Update title and tags, optionally renaming the file after the title.
+async fn update_note_meta(
+    Path(id): Path<String>,
+    headers: HeaderMap,
+    Json(meta): Json<NoteMetaUpdate>,
+) -> impl IntoResponse {
+    match filesystem::update_note_meta(&id, &meta, if_match(&headers)) {
+        Ok(note) => with_etag(StatusCode::OK, &note.etag, &note),
+        Err(WriteError::Invalid(report)) => invalid_frontmatter(report),
+        Err(WriteError::Other(err)) if err.starts_with("Note not found") => {
+            (StatusCode::NOT_FOUND, err).into_response()
+        }
+        Err(WriteError::Other(err)) if err.starts_with("Conflict") => {
+            conflict(get_note(Path(id)).await)
+        }
+        Err(WriteError::Other(err)) if err.starts_with("Invalid") => {
+            (StatusCode::BAD_REQUEST, err).into_response()
+        }
+        Err(err) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to update note metadata: {}", err),
+        )
+            .into_response(),
+    }
+}
+
 async fn delete_note(Path(id): Path<String>) -> impl IntoResponse {
     match filesystem::archive_note(&id) {
         Ok(_) => StatusCode::NO_CONTENT.into_response(),
@@ -70,3 +170,83 @@ async fn delete_note(Path(id): Path<String>) -> impl IntoResponse {
             .into_response(),
     }
 }
+
+/// Notes and tasks about similar topics (TF-IDF similarity, computed locally).
+async fn get_related_notes(
+    Path(id): Path<String>,
+    Query(params): Query<RelatedQuery>,
+) -> impl IntoResponse {
+    let limit = params
+        .limit
+        .unwrap_or(DEFAULT_RELATED_LIMIT)
+        .clamp(1, MAX_RELATED_LIMIT);
+
+    let result =
+        filesystem::read_note_by_id(&id).and_then(|note| related::related_notes(&note.path, limit));
+
+    match result {
+        Ok(notes) => Json(notes).into_response(),
+        Err(err) if err.starts_with("Note not found") => {
+            (StatusCode::NOT_FOUND, err).into_response()
+        }
+        Err(err) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to find related notes: {}", err),
+        )
+            .into_response(),
+    }
+}
+
+/// Notes, tasks and daily notes that link to this note.
+async fn get_note_backlinks(Path(id): Path<String>) -> impl IntoResponse {
+    let result = filesystem::read_note_by_id(&id).and_then(|note| links::backlinks(&note.path));
+
+    match result {
+        Ok(backlinks) => Json(backlinks).into_response(),
+        Err(err) if err.starts_with("Note not found") => {
+            (StatusCode::NOT_FOUND, err).into_response()
+        }
+        Err(err) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to find backlinks: {}", err),
+        )
+            .into_response(),
+    }
+}
+
+/// Move or rename a note, rewriting links to it across the vault.
+async fn move_note(
+    Path(id): Path<String>,
+    Json(payload): Json<MoveNoteRequest>,
+) -> impl IntoResponse {
+    match filesystem::read_note_by_id(&id) {
+        Ok(note) => move_note_response(&config::data_dir().join(&note.path), &payload),
+        Err(err) if err.starts_with("Note not found") => {
+            (StatusCode::NOT_FOUND, err).into_response()
+        }
+        Err(err) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to move note: {}", err),
+        )
+            .into_response(),
+    }
+}
+
+/// Move the note at `path`; shared with project notes.
+pub fn move_note_response(path: &std::path::Path, payload: &MoveNoteRequest) -> Response {
+    match rename::move_note(
+        path,
+        &payload.to,
+        payload.id.as_deref(),
+        payload.message.as_deref(),
+    ) {
+        Ok(result) => Json(result).into_response(),
+        Err(err) if err.starts_with("Invalid") => (StatusCode::BAD_REQUEST, err).into_response(),
+        Err(err) if err.contains("already exists") => (StatusCode::CONFLICT, err).into_response(),
+        Err(err) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to move note: {}", err),
+        )
+            .into_response(),
+    }
+}
@@ -1,21 +1,29 @@
 use axum::{
     extract::Path,
-    http::StatusCode,
+    http::{HeaderMap, StatusCode},
     response::IntoResponse,
-    routing::{get, put},
+    routing::{get, post, put},
     Json, Router,
 };
 use serde::{Deserialize, Serialize};
 use std::fs;
 
 use crate::config;
+use crate::routes::notes::{move_note_response, MoveNoteRequest};
 use crate::routes::tasks::{
     create_task_handler, delete_task_handler, get_task_handler, list_project_tasks_handler,
-    toggle_task_handler, update_task_content_handler, update_task_meta_handler, CreateTaskRequest,
-    UpdateTaskMetaRequest,
+    task_backlinks_handler, toggle_task_handler, update_task_content_handler,
+    update_task_meta_handler, CreateTaskRequest, UpdateTaskMetaRequest,
 };
+use crate::routes::{conflict, if_match, invalid_frontmatter, with_etag};
+use crate::services::archive;
+use crate::services::etag;
 use crate::services::filesystem;
 use crate::services::frontmatter;
+use crate::services::index;
+use crate::services::recent;
+use crate::services::schemas::{self, WriteError};
+use crate::services::templates::{self, TemplateContext};
 
 #[derive(Debug, Serialize)]
 pub struct Project {
@@ -32,16 +40,16 @@ pub struct ProjectWithContent {
     pub path: String,
     pub created: String,
     pub content: String,
-}
-
-#[derive(Debug, Deserialize)]
-pub struct UpdateProjectContentRequest {
-    pub content: String,
+    /// ETag of the file as read or written (sent as a header, see `etag::of`)
+    #[serde(skip)]
+    pub etag: String,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct CreateProjectRequest {
     pub name: String,
+    /// Template name in `data/templates/` (defaults to the vault's project template)
+    pub template: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -63,11 +71,19 @@ pub struct ProjectNoteWithContent {
     pub created: String,
     pub updated: String,
     pub content: String,
+    /// Where a new note's template put `{{cursor}}` (character offset into `content`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<usize>,
+    /// ETag of the file as read or written (sent as a header, see `etag::of`)
+    #[serde(skip)]
+    pub etag: String,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct CreateNoteRequest {
     pub title: Option<String>,
+    /// Template name in `data/templates/` (defaults to the vault's note template)
+    pub template: Option<String>,
 }
 
 pub fn router() -> Router {
@@ -91,6 +107,10 @@ pub fn router() -> Router {
         )
         .route("/{id}/tasks/{task_id}/toggle", put(toggle_project_task))
         .route("/{id}/tasks/{task_id}/meta", put(update_project_task_meta))
+        .route(
+            "/{id}/tasks/{task_id}/backlinks",
+            get(get_project_task_backlinks),
+        )
         // Note routes
         .route(
             "/{id}/notes",
@@ -102,6 +122,7 @@ pub fn router() -> Router {
                 .put(update_project_note)
                 .delete(delete_project_note),
         )
+        .route("/{id}/notes/{note_id}/move", post(move_project_note))
 }
 
 // ============ Task Handlers ============
@@ -121,22 +142,33 @@ async fn get_project_task(Path((id, task_id)): Path<(String, String)>) -> impl I
     get_task_handler(id, task_id).await
 }
 
+async fn get_project_task_backlinks(
+    Path((id, task_id)): Path<(String, String)>,
+) -> impl IntoResponse {
+    task_backlinks_handler(id, task_id).await
+}
+
 async fn update_project_task(
     Path((id, task_id)): Path<(String, String)>,
+    headers: HeaderMap,
     body: String,
 ) -> impl IntoResponse {
-    update_task_content_handler(id, task_id, body).await
+    update_task_content_handler(id, task_id, if_match(&headers), body).await
 }
 
-async fn toggle_project_task(Path((id, task_id)): Path<(String, String)>) -> impl IntoResponse {
-    toggle_task_handler(id, task_id).await
+async fn toggle_project_task(
+    Path((id, task_id)): Path<(String, String)>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    toggle_task_handler(id, task_id, if_match(&headers)).await
 }
 
 async fn update_project_task_meta(
     Path((id, task_id)): Path<(String, String)>,
+    headers: HeaderMap,
     Json(payload): Json<UpdateTaskMetaRequest>,
 ) -> impl IntoResponse {
-    update_task_meta_handler(id, task_id, payload).await
+    update_task_meta_handler(id, task_id, if_match(&headers), payload).await
 }
 
 async fn delete_project_task(Path((id, task_id)): Path<(String, String)>) -> impl IntoResponse {
@@ -186,13 +218,13 @@ fn list_projects_impl() -> Result<Vec<Project>, String> {
             .to_string();
 
         let name = fm
-            .get(&serde_yaml::Value::from("title"))
+            .get(serde_yaml::Value::from("title"))
             .and_then(|v| v.as_str())
             .map(String::from)
             .unwrap_or_else(|| id.clone());
 
         let created = fm
-            .get(&serde_yaml::Value::from("created"))
+            .get(serde_yaml::Value::from("created"))
             .and_then(|v| v.as_str())
             .map(String::from)
             .unwrap_or_default();
@@ -221,17 +253,19 @@ async fn get_project(Path(id): Path<String>) -> impl IntoResponse {
             let (fm, _, _) = frontmatter::parse_frontmatter(&content);
 
             let name = fm
-                .get(&serde_yaml::Value::from("title"))
+                .get(serde_yaml::Value::from("title"))
                 .and_then(|v| v.as_str())
                 .map(String::from)
                 .unwrap_or_else(|| id.clone());
 
             let created = fm
-                .get(&serde_yaml::Value::from("created"))
+                .get(serde_yaml::Value::from("created"))
                 .and_then(|v| v.as_str())
                 .map(String::from)
                 .unwrap_or_default();
 
+            recent::record_access(&format!("projects/{}", id));
+
             Json(Project {
                 id: id.clone(),
                 name,
@@ -249,8 +283,12 @@ async fn get_project(Path(id): Path<String>) -> impl IntoResponse {
 }
 
 async fn create_project(Json(payload): Json<CreateProjectRequest>) -> impl IntoResponse {
-    match create_project_impl(&payload.name) {
+    match create_project_impl(&payload.name, payload.template.as_deref()) {
         Ok(project) => (StatusCode::CREATED, Json(project)).into_response(),
+        Err(WriteError::Invalid(report)) => invalid_frontmatter(report),
+        Err(WriteError::Other(err)) if err.starts_with("Invalid") => {
+            (StatusCode::BAD_REQUEST, err).into_response()
+        }
         Err(err) => (
             StatusCode::INTERNAL_SERVER_ERROR,
             format!("Failed to create project: {}", err),
@@ -259,7 +297,7 @@ async fn create_project(Json(payload): Json<CreateProjectRequest>) -> impl IntoR
     }
 }
 
-fn create_project_impl(name: &str) -> Result<Project, String> {
+fn create_project_impl(name: &str, template: Option<&str>) -> Result<Project, WriteError> {
     use chrono::Utc;
 
     // Create slug from name
@@ -272,19 +310,22 @@ fn create_project_impl(name: &str) -> Result<Project, String> {
         .to_string();
 
     if slug.is_empty() {
-        return Err("Invalid project name".to_string());
+        return Err("Invalid project name".into());
     }
 
     let projects_dir = config::data_dir().join("projects");
     let project_dir = projects_dir.join(&slug);
 
     if project_dir.exists() {
-        return Err("Project already exists".to_string());
+        return Err("Project already exists".into());
     }
 
-    // Create directories
-    fs::create_dir_all(&project_dir).map_err(|e| e.to_string())?;
-    fs::create_dir_all(project_dir.join("assets")).map_err(|e| e.to_string())?;
+    let ctx = TemplateContext {
+        title: name.to_string(),
+        date: Utc::now().date_naive(),
+        project: Some(name.to_string()),
+    };
+    let rendered = templates::for_new("project", template, &ctx)?;
 
     // Create index.md
     let index_path = project_dir.join("index.md");
@@ -303,6 +344,11 @@ fn create_project_impl(name: &str) -> Result<Project, String> {
         serde_yaml::Value::from("title"),
         serde_yaml::Value::from(name),
     );
+    if let Some(rendered) = &rendered {
+        for (key, value) in &rendered.frontmatter {
+            fm.entry(key.clone()).or_insert_with(|| value.clone());
+        }
+    }
     fm.insert(
         serde_yaml::Value::from("created"),
         serde_yaml::Value::from(now.clone()),
@@ -312,7 +358,16 @@ fn create_project_impl(name: &str) -> Result<Project, String> {
         serde_yaml::Value::from(now.clone()),
     );
 
-    let content = frontmatter::serialize_frontmatter(&fm, &format!("# {}\n\n", name))?;
+    let body = match rendered {
+        Some(rendered) => rendered.body,
+        None => format!("# {}\n\n", name),
+    };
+    schemas::check_new(&index_path, &mut fm)?;
+    let content = frontmatter::serialize_frontmatter(&fm, &body)?;
+
+    // Create directories
+    fs::create_dir_all(&project_dir).map_err(|e| e.to_string())?;
+    fs::create_dir_all(project_dir.join("assets")).map_err(|e| e.to_string())?;
 
     filesystem::atomic_write(&index_path, content.as_bytes())?;
 
@@ -345,25 +400,26 @@ async fn get_project_content(Path(id): Path<String>) -> impl IntoResponse {
             let (fm, body, _) = frontmatter::parse_frontmatter(&content);
 
             let name = fm
-                .get(&serde_yaml::Value::from("title"))
+                .get(serde_yaml::Value::from("title"))
                 .and_then(|v| v.as_str())
                 .map(String::from)
                 .unwrap_or_else(|| id.clone());
 
             let created = fm
-                .get(&serde_yaml::Value::from("created"))
+                .get(serde_yaml::Value::from("created"))
                 .and_then(|v| v.as_str())
                 .map(String::from)
                 .unwrap_or_default();
 
-            Json(ProjectWithContent {
+            let project = ProjectWithContent {
                 id: id.clone(),
                 name,
                 path: format!("projects/{}", id),
                 created,
                 content: body,
-            })
-            .into_response()
+                etag: etag::of(content.as_bytes()),
+            };
+            with_etag(StatusCode::OK, &project.etag, &project)
         }
         Err(err) => (
             StatusCode::INTERNAL_SERVER_ERROR,
@@ -373,7 +429,12 @@ async fn get_project_content(Path(id): Path<String>) -> impl IntoResponse {
     }
 }
 
-async fn update_project_content(Path(id): Path<String>, body: String) -> impl IntoResponse {
+/// Replace the project page. With `If-Match`, a stale version gets 409 and the current page.
+async fn update_project_content(
+    Path(id): Path<String>,
+    headers: HeaderMap,
+    body: String,
+) -> impl IntoResponse {
     let index_path = config::data_dir()
         .join("projects")
         .join(&id)
@@ -395,6 +456,10 @@ async fn update_project_content(Path(id): Path<String>, body: String) -> impl In
         }
     };
 
+    if etag::check(if_match(&headers), existing.as_bytes()).is_err() {
+        return conflict(get_project_content(Path(id)).await);
+    }
+
     let (mut fm, _, _) = frontmatter::parse_frontmatter(&existing);
 
     // Update the timestamp
@@ -405,8 +470,11 @@ async fn update_project_content(Path(id): Path<String>, body: String) -> impl In
     );
 
     // Serialize with new content
-    let new_content = match frontmatter::serialize_frontmatter(&fm, &body) {
+    let new_content = match frontmatter::rewrite(&existing, &fm, &body) {
         Ok(c) => c,
+        Err(err) if err.starts_with("Invalid") => {
+            return (StatusCode::BAD_REQUEST, err).into_response()
+        }
         Err(err) => {
             return (
                 StatusCode::INTERNAL_SERVER_ERROR,
@@ -426,25 +494,26 @@ async fn update_project_content(Path(id): Path<String>, body: String) -> impl In
     }
 
     let name = fm
-        .get(&serde_yaml::Value::from("title"))
+        .get(serde_yaml::Value::from("title"))
         .and_then(|v| v.as_str())
         .map(String::from)
         .unwrap_or_else(|| id.clone());
 
     let created = fm
-        .get(&serde_yaml::Value::from("created"))
+        .get(serde_yaml::Value::from("created"))
         .and_then(|v| v.as_str())
         .map(String::from)
         .unwrap_or_default();
 
-    Json(ProjectWithContent {
+    let project = ProjectWithContent {
         id: id.clone(),
         name,
         path: format!("projects/{}", id),
         created,
         content: body,
-    })
-    .into_response()
+        etag: etag::of(new_content.as_bytes()),
+    };
+    with_etag(StatusCode::OK, &project.etag, &project)
 }
 
 // ============ Project Notes Handlers ============
@@ -504,25 +573,25 @@ async fn list_project_notes(Path(project_id): Path<String>) -> impl IntoResponse
             .to_string();
 
         let id = fm
-            .get(&serde_yaml::Value::from("id"))
+            .get(serde_yaml::Value::from("id"))
             .and_then(|v| v.as_str())
             .map(String::from)
             .unwrap_or_else(|| filename.clone());
 
         let title = fm
-            .get(&serde_yaml::Value::from("title"))
+            .get(serde_yaml::Value::from("title"))
             .and_then(|v| v.as_str())
             .map(String::from)
             .unwrap_or_else(|| filename.clone());
 
         let created = fm
-            .get(&serde_yaml::Value::from("created"))
+            .get(serde_yaml::Value::from("created"))
             .and_then(|v| v.as_str())
             .map(String::from)
             .unwrap_or_default();
 
         let updated = fm
-            .get(&serde_yaml::Value::from("updated"))
+            .get(serde_yaml::Value::from("updated"))
             .and_then(|v| v.as_str())
             .map(String::from)
             .unwrap_or_default();
@@ -572,6 +641,25 @@ async fn create_project_note(
     let title = payload.title.unwrap_or_else(|| "Untitled".to_string());
     let now_str = now.to_rfc3339();
 
+    let ctx = TemplateContext {
+        title: title.clone(),
+        date: now.date_naive(),
+        project: Some(templates::project_name(&project_id)),
+    };
+    let rendered = match templates::for_new("note", payload.template.as_deref(), &ctx) {
+        Ok(rendered) => rendered,
+        Err(err) if err.starts_with("Invalid") => {
+            return (StatusCode::BAD_REQUEST, err).into_response();
+        }
+        Err(err) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to load template: {}", err),
+            )
+                .into_response();
+        }
+    };
+
     let mut fm = serde_yaml::Mapping::new();
     fm.insert(
         serde_yaml::Value::from("id"),
@@ -585,6 +673,11 @@ async fn create_project_note(
         serde_yaml::Value::from("title"),
         serde_yaml::Value::from(title.clone()),
     );
+    if let Some(rendered) = &rendered {
+        for (key, value) in &rendered.frontmatter {
+            fm.entry(key.clone()).or_insert_with(|| value.clone());
+        }
+    }
     fm.insert(
         serde_yaml::Value::from("project_id"),
         serde_yaml::Value::from(project_id.clone()),
@@ -598,7 +691,13 @@ async fn create_project_note(
         serde_yaml::Value::from(now_str.clone()),
     );
 
-    let body = format!("# {}\n\n", title);
+    let (body, cursor) = match rendered {
+        Some(rendered) => (rendered.body, rendered.cursor),
+        None => (format!("# {}\n\n", title), None),
+    };
+    if let Err(report) = schemas::check_new(&note_path, &mut fm) {
+        return invalid_frontmatter(report);
+    }
     let content = match frontmatter::serialize_frontmatter(&fm, &body) {
         Ok(c) => c,
         Err(err) => {
@@ -618,19 +717,18 @@ async fn create_project_note(
             .into_response();
     }
 
-    (
-        StatusCode::CREATED,
-        Json(ProjectNoteWithContent {
-            id: format!("{}-{}", project_id, filename),
-            title,
-            path: format!("projects/{}/notes/{}.md", project_id, filename),
-            project_id,
-            created: now_str.clone(),
-            updated: now_str,
-            content: body,
-        }),
-    )
-        .into_response()
+    let note = ProjectNoteWithContent {
+        id: format!("{}-{}", project_id, filename),
+        title,
+        path: format!("projects/{}/notes/{}.md", project_id, filename),
+        project_id,
+        created: now_str.clone(),
+        updated: now_str,
+        content: body,
+        cursor,
+        etag: etag::of(content.as_bytes()),
+    };
+    with_etag(StatusCode::CREATED, &note.etag, &note)
 }
 
 async fn get_project_note(
@@ -642,66 +740,14 @@ async fn get_project_note(
         .join("notes");
 
     // Try to find the note by ID (which might be the filename)
-    let note_path = notes_dir.join(format!("{}.md", note_id));
+    let mut note_path = notes_dir.join(format!("{}.md", note_id));
 
     if !note_path.exists() {
-        // Try to find by searching all notes for matching ID
-        if let Ok(entries) = fs::read_dir(&notes_dir) {
-            for entry in entries.flatten() {
-                let path = entry.path();
-                if path.extension().and_then(|s| s.to_str()) != Some("md") {
-                    continue;
-                }
-
-                if let Ok(content) = fs::read_to_string(&path) {
-                    let (fm, body, _) = frontmatter::parse_frontmatter(&content);
-
-                    let file_id = fm
-                        .get(&serde_yaml::Value::from("id"))
-                        .and_then(|v| v.as_str())
-                        .map(String::from);
-
-                    if file_id.as_deref() == Some(&note_id) {
-                        let title = fm
-                            .get(&serde_yaml::Value::from("title"))
-                            .and_then(|v| v.as_str())
-                            .map(String::from)
-                            .unwrap_or_default();
-
-                        let created = fm
-                            .get(&serde_yaml::Value::from("created"))
-                            .and_then(|v| v.as_str())
-                            .map(String::from)
-                            .unwrap_or_default();
-
-                        let updated = fm
-                            .get(&serde_yaml::Value::from("updated"))
-                            .and_then(|v| v.as_str())
-                            .map(String::from)
-                            .unwrap_or_default();
-
-                        let filename = path
-                            .file_stem()
-                            .and_then(|s| s.to_str())
-                            .unwrap_or("")
-                            .to_string();
-
-                        return Json(ProjectNoteWithContent {
-                            id: note_id,
-                            title,
-                            path: format!("projects/{}/notes/{}.md", project_id, filename),
-                            project_id,
-                            created,
-                            updated,
-                            content: body,
-                        })
-                        .into_response();
-                    }
-                }
-            }
+        // Otherwise look up the ID in frontmatter via the index registry
+        match index::find_path(&note_id, |path| path.starts_with(&notes_dir)) {
+            Some(path) => note_path = path,
+            None => return (StatusCode::NOT_FOUND, "Note not found").into_response(),
         }
-
-        return (StatusCode::NOT_FOUND, "Note not found").into_response();
     }
 
     let content = match fs::read_to_string(&note_path) {
@@ -718,43 +764,47 @@ async fn get_project_note(
     let (fm, body, _) = frontmatter::parse_frontmatter(&content);
 
     let id = fm
-        .get(&serde_yaml::Value::from("id"))
+        .get(serde_yaml::Value::from("id"))
         .and_then(|v| v.as_str())
         .map(String::from)
         .unwrap_or_else(|| note_id.clone());
 
     let title = fm
-        .get(&serde_yaml::Value::from("title"))
+        .get(serde_yaml::Value::from("title"))
         .and_then(|v| v.as_str())
         .map(String::from)
         .unwrap_or_default();
 
     let created = fm
-        .get(&serde_yaml::Value::from("created"))
+        .get(serde_yaml::Value::from("created"))
         .and_then(|v| v.as_str())
         .map(String::from)
         .unwrap_or_default();
 
     let updated = fm
-        .get(&serde_yaml::Value::from("updated"))
+        .get(serde_yaml::Value::from("updated"))
         .and_then(|v| v.as_str())
         .map(String::from)
         .unwrap_or_default();
 
-    Json(ProjectNoteWithContent {
+    let note = ProjectNoteWithContent {
         id,
         title,
-        path: format!("projects/{}/notes/{}.md", project_id, note_id),
+        path: filesystem::normalize_path(&note_path),
         project_id,
         created,
         updated,
         content: body,
-    })
-    .into_response()
+        cursor: None,
+        etag: etag::of(content.as_bytes()),
+    };
+    with_etag(StatusCode::OK, &note.etag, &note)
 }
 
+/// Replace a project note's body. With `If-Match`, a stale version gets 409 and the current note.
 async fn update_project_note(
     Path((project_id, note_id)): Path<(String, String)>,
+    headers: HeaderMap,
     body: String,
 ) -> impl IntoResponse {
     let notes_dir = config::data_dir()
@@ -780,6 +830,10 @@ async fn update_project_note(
         }
     };
 
+    if etag::check(if_match(&headers), existing.as_bytes()).is_err() {
+        return conflict(get_project_note(Path((project_id, note_id))).await);
+    }
+
     let (mut fm, _, _) = frontmatter::parse_frontmatter(&existing);
 
     // Update timestamp
@@ -790,8 +844,11 @@ async fn update_project_note(
     );
 
     // Serialize with new content
-    let new_content = match frontmatter::serialize_frontmatter(&fm, &body) {
+    let new_content = match frontmatter::rewrite(&existing, &fm, &body) {
         Ok(c) => c,
+        Err(err) if err.starts_with("Invalid") => {
+            return (StatusCode::BAD_REQUEST, err).into_response()
+        }
         Err(err) => {
             return (
                 StatusCode::INTERNAL_SERVER_ERROR,
@@ -810,24 +867,24 @@ async fn update_project_note(
     }
 
     let id = fm
-        .get(&serde_yaml::Value::from("id"))
+        .get(serde_yaml::Value::from("id"))
         .and_then(|v| v.as_str())
         .map(String::from)
         .unwrap_or_else(|| note_id.clone());
 
     let title = fm
-        .get(&serde_yaml::Value::from("title"))
+        .get(serde_yaml::Value::from("title"))
         .and_then(|v| v.as_str())
         .map(String::from)
         .unwrap_or_default();
 
     let created = fm
-        .get(&serde_yaml::Value::from("created"))
+        .get(serde_yaml::Value::from("created"))
         .and_then(|v| v.as_str())
         .map(String::from)
         .unwrap_or_default();
 
-    Json(ProjectNoteWithContent {
+    let note = ProjectNoteWithContent {
         id,
         title,
         path: format!("projects/{}/notes/{}.md", project_id, note_id),
@@ -835,8 +892,10 @@ async fn update_project_note(
         created,
         updated: now,
         content: body,
-    })
-    .into_response()
+        cursor: None,
+        etag: etag::of(new_content.as_bytes()),
+    };
+    with_etag(StatusCode::OK, &note.etag, &note)
 }
 
 async fn delete_project_note(
@@ -854,24 +913,35 @@ async fn delete_project_note(
     }
 
     // Move to archive instead of deleting
-    let archive_dir = config::data_dir().join("archive");
-    if let Err(e) = fs::create_dir_all(&archive_dir) {
+    if let Err(err) = archive::archive_file(&note_path, &format!("{}-{}.md", project_id, note_id)) {
         return (
             StatusCode::INTERNAL_SERVER_ERROR,
-            format!("Failed to create archive directory: {}", e),
+            format!("Failed to archive note: {}", err),
         )
             .into_response();
     }
 
-    let archive_path = archive_dir.join(format!("{}-{}.md", project_id, note_id));
+    StatusCode::NO_CONTENT.into_response()
+}
 
-    if let Err(err) = fs::rename(&note_path, &archive_path) {
-        return (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            format!("Failed to archive note: {}", err),
-        )
-            .into_response();
+/// Move or rename a project note (possibly into another project or `notes/`).
+async fn move_project_note(
+    Path((project_id, note_id)): Path<(String, String)>,
+    Json(payload): Json<MoveNoteRequest>,
+) -> impl IntoResponse {
+    let notes_dir = config::data_dir()
+        .join("projects")
+        .join(&project_id)
+        .join("notes");
+
+    let mut note_path = notes_dir.join(format!("{}.md", note_id));
+
+    if !note_path.exists() {
+        match index::find_path(&note_id, |path| path.starts_with(&notes_dir)) {
+            Some(path) => note_path = path,
+            None => return (StatusCode::NOT_FOUND, "Note not found").into_response(),
+        }
     }
 
-    StatusCode::NO_CONTENT.into_response()
+    move_note_response(&note_path, &payload)
 }
@@ -0,0 +1,37 @@
+use axum::{
+    extract::{Path, Query},
+    http::StatusCode,
+    response::IntoResponse,
+    routing::get,
+    Json, Router,
+};
+use serde::Deserialize;
+
+use crate::services::markdown;
+
+#[derive(Debug, Deserialize)]
+pub struct RenderQuery {
+    /// Project of a task or project note (ids are only unique per folder)
+    project: Option<String>,
+}
+
+pub fn router() -> Router {
+    Router::new().route("/{type}/{id}", get(render_doc))
+}
+
+/// A note, task, project or daily note as sanitised HTML
+async fn render_doc(
+    Path((doc_type, id)): Path<(String, String)>,
+    Query(params): Query<RenderQuery>,
+) -> impl IntoResponse {
+    match markdown::render_doc(&doc_type, &id, params.project.as_deref()) {
+        Ok(rendered) => Json(rendered).into_response(),
+        Err(err) if err.starts_with("Invalid") => (StatusCode::BAD_REQUEST, err).into_response(),
+        Err(err) if err.contains("not found") => (StatusCode::NOT_FOUND, err).into_response(),
+        Err(err) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to render document: {}", err),
+        )
+            .into_response(),
+    }
+}
@@ -0,0 +1,25 @@
+use axum::{http::StatusCode, response::IntoResponse, routing::get, Json, Router};
+
+use crate::services::schemas;
+
+pub fn router() -> Router {
+    Router::new()
+        .route("/", get(list_schemas))
+        .route("/report", get(report))
+}
+
+async fn list_schemas() -> impl IntoResponse {
+    match schemas::list() {
+        Ok(list) => Json(list).into_response(),
+        Err(err) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to list schemas: {}", err),
+        )
+            .into_response(),
+    }
+}
+
+/// Every existing document that breaks its type's schema.
+async fn report() -> impl IntoResponse {
+    Json(schemas::report())
+}
@@ -1,20 +1,104 @@
-use axum::{extract::Query, http::StatusCode, response::IntoResponse, routing::get, Json, Router};
+use axum::{
+    extract::Query,
+    http::{HeaderName, StatusCode},
+    response::IntoResponse,
+    routing::{get, post},
+    Json, Router,
+};
 use serde::Deserialize;
 
-use crate::services::search;
+use crate::services::matcher::MatchOptions;
+use crate::services::replace::{self, Replacement};
+use crate::services::search::{self, SearchMode, SearchOptions};
+
+/// Default and maximum page size for search results
+const DEFAULT_LIMIT: usize = 50;
+const MAX_LIMIT: usize = 500;
+
+/// Upper bound for `max_matches` (matching lines per file)
+const MAX_MATCHES_PER_FILE: u64 = 100;
 
 #[derive(Debug, Deserialize)]
 pub struct SearchQuery {
     q: String,
+    limit: Option<usize>,
+    offset: Option<usize>,
+    /// query (default), literal or regex
+    #[serde(default)]
+    mode: SearchMode,
+    #[serde(default)]
+    case_sensitive: bool,
+    #[serde(default)]
+    whole_word: bool,
+    #[serde(default)]
+    fold_diacritics: bool,
+    max_matches: Option<u64>,
 }
 
 pub fn router() -> Router {
-    Router::new().route("/", get(search_notes))
+    Router::new()
+        .route("/", get(search_notes))
+        .route("/replace", post(apply_replace))
+        .route("/replace/preview", post(preview_replace))
 }
 
+#[derive(Debug, Deserialize)]
+pub struct ReplaceRequest {
+    q: String,
+    replacement: String,
+    #[serde(default)]
+    mode: SearchMode,
+    #[serde(flatten)]
+    matching: MatchOptions,
+    /// Restrict the replacement to these files (e.g. those confirmed in the preview)
+    paths: Option<Vec<String>>,
+    /// Commit message (defaults to `Replace "q" with "replacement"`)
+    message: Option<String>,
+}
+
+impl ReplaceRequest {
+    fn replacement(&self) -> Replacement<'_> {
+        Replacement {
+            query: &self.q,
+            replacement: &self.replacement,
+            options: SearchOptions {
+                mode: self.mode,
+                matching: self.matching,
+                ..Default::default()
+            },
+            paths: self.paths.as_deref(),
+        }
+    }
+}
+
+/// Ranked search. The total number of matches (before pagination)
+/// is returned in the `X-Total-Count` header.
 async fn search_notes(Query(params): Query<SearchQuery>) -> impl IntoResponse {
-    match search::search_notes(&params.q) {
-        Ok(results) => Json(results).into_response(),
+    let limit = params.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+    let offset = params.offset.unwrap_or(0);
+    let options = SearchOptions {
+        mode: params.mode,
+        matching: MatchOptions {
+            case_sensitive: params.case_sensitive,
+            whole_word: params.whole_word,
+            fold_diacritics: params.fold_diacritics,
+        },
+        max_matches_per_file: params
+            .max_matches
+            .unwrap_or(search::DEFAULT_MATCHES_PER_FILE)
+            .clamp(1, MAX_MATCHES_PER_FILE),
+    };
+
+    match search::search_notes(&params.q, &options, limit, offset) {
+        Ok(page) => (
+            [(
+                HeaderName::from_static("x-total-count"),
+                page.total.to_string(),
+            )],
+            Json(page.results),
+        )
+            .into_response(),
+        Err(err) if err.starts_with("Invalid") => (StatusCode::BAD_REQUEST, err).into_response(),
         Err(err) => (
             StatusCode::INTERNAL_SERVER_ERROR,
             format!("Search failed: {}", err),
@@ -22,3 +106,29 @@ async fn search_notes(Query(params): Query<SearchQuery>) -> impl IntoResponse {
             .into_response(),
     }
 }
+
+/// Preview a vault-wide replacement as a diff per file (nothing is written).
+async fn preview_replace(Json(payload): Json<ReplaceRequest>) -> impl IntoResponse {
+    match replace::preview(&payload.replacement()) {
+        Ok(diff) => Json(diff).into_response(),
+        Err(err) if err.starts_with("Invalid") => (StatusCode::BAD_REQUEST, err).into_response(),
+        Err(err) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to preview replacement: {}", err),
+        )
+            .into_response(),
+    }
+}
+
+/// Apply a vault-wide replacement and record it as a single commit.
+async fn apply_replace(Json(payload): Json<ReplaceRequest>) -> impl IntoResponse {
+    match replace::apply(&payload.replacement(), payload.message.as_deref()) {
+        Ok(result) => Json(result).into_response(),
+        Err(err) if err.starts_with("Invalid") => (StatusCode::BAD_REQUEST, err).into_response(),
+        Err(err) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to apply replacement: {}", err),
+        )
+            .into_response(),
+    }
+}
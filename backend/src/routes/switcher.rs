@@ -0,0 +1,219 @@
+use std::cmp::Ordering;
+use std::sync::{Arc, RwLock};
+
+use axum::{extract::Query, response::IntoResponse, routing::get, Json, Router};
+use chrono::{NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::services::frontmatter;
+use crate::services::fuzzy;
+use crate::services::index::{self, IndexedDoc};
+use crate::services::recent;
+
+/// Default and maximum number of results
+const DEFAULT_LIMIT: usize = 20;
+const MAX_LIMIT: usize = 100;
+
+/// Matches on ids and paths rank below matches on titles and aliases
+const ID_PENALTY: i64 = 4;
+const PATH_PENALTY: i64 = 8;
+
+/// Bonus for an item opened just now; halves every `RECENT_HALF_LIFE_HOURS`
+const RECENT_BOOST: f64 = 24.0;
+const RECENT_HALF_LIFE_HOURS: f64 = 24.0;
+
+#[derive(Debug, Deserialize)]
+pub struct SwitcherQuery {
+    #[serde(default)]
+    q: String,
+    /// Only return one kind: note, task, project or daily
+    kind: Option<String>,
+    limit: Option<usize>,
+}
+
+/// Something the switcher can jump to
+#[derive(Debug, Clone)]
+struct Entry {
+    kind: &'static str,
+    id: String,
+    title: String,
+    path: String,
+    project_id: Option<String>,
+    aliases: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SwitcherItem {
+    pub kind: &'static str,
+    pub id: String,
+    pub title: String,
+    pub path: String,
+    pub project_id: Option<String>,
+    pub score: i64,
+    /// Which field matched: title, alias, id or path
+    pub matched_field: &'static str,
+    /// The matched text (the title, or the alias/id/path that matched)
+    pub matched_text: String,
+    /// Char indices in `matched_text` that matched the query
+    pub positions: Vec<usize>,
+}
+
+lazy_static::lazy_static! {
+    /// Entries and the index generation they were built from.
+    /// Rebuilt lazily after any file change so queries stay in-memory.
+    static ref ENTRIES: RwLock<(Option<u64>, Arc<Vec<Entry>>)> =
+        RwLock::new((None, Arc::new(Vec::new())));
+}
+
+pub fn router() -> Router {
+    Router::new().route("/", get(switcher))
+}
+
+/// Fuzzy-match titles, aliases, ids and paths of notes, tasks, projects and
+/// daily notes. Ranked by match quality, with recently opened items boosted.
+/// An empty query returns the most recently opened items.
+async fn switcher(Query(params): Query<SwitcherQuery>) -> impl IntoResponse {
+    let limit = params.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+
+    let entries = entries();
+
+    let last_access = recent::last_access();
+    let now = Utc::now();
+    let recency = |path: &str| -> f64 {
+        last_access.get(path).map_or(0.0, |at| {
+            let hours = (now - *at).num_seconds().max(0) as f64 / 3600.0;
+            RECENT_BOOST * 0.5_f64.powf(hours / RECENT_HALF_LIFE_HOURS)
+        })
+    };
+
+    let candidates = entries
+        .iter()
+        .filter(|e| params.kind.as_deref().is_none_or(|k| k == e.kind));
+
+    let mut items: Vec<(SwitcherItem, f64)> = if params.q.trim().is_empty() {
+        candidates
+            .filter(|e| last_access.contains_key(&e.path))
+            .map(|e| {
+                let boost = recency(&e.path);
+                (to_item(e, 0, "title", e.title.clone(), Vec::new()), boost)
+            })
+            .collect()
+    } else {
+        candidates
+            .filter_map(|e| {
+                let (field, text, m) = best_match(&params.q, e)?;
+                let rank = m.score as f64 + recency(&e.path);
+                Some((to_item(e, m.score, field, text, m.positions), rank))
+            })
+            .collect()
+    };
+
+    items.sort_by(|(a, a_rank), (b, b_rank)| {
+        b_rank
+            .partial_cmp(a_rank)
+            .unwrap_or(Ordering::Equal)
+            .then_with(|| a.title.len().cmp(&b.title.len()))
+            .then_with(|| a.title.cmp(&b.title))
+    });
+
+    let items: Vec<SwitcherItem> = items
+        .into_iter()
+        .take(limit)
+        .map(|(item, _)| item)
+        .collect();
+
+    Json(items).into_response()
+}
+
+fn to_item(
+    entry: &Entry,
+    score: i64,
+    matched_field: &'static str,
+    matched_text: String,
+    positions: Vec<usize>,
+) -> SwitcherItem {
+    SwitcherItem {
+        kind: entry.kind,
+        id: entry.id.clone(),
+        title: entry.title.clone(),
+        path: entry.path.clone(),
+        project_id: entry.project_id.clone(),
+        score,
+        matched_field,
+        matched_text,
+        positions,
+    }
+}
+
+/// Best-scoring field of an entry for the query.
+fn best_match(query: &str, entry: &Entry) -> Option<(&'static str, String, fuzzy::FuzzyMatch)> {
+    let fields = std::iter::once(("title", &entry.title, 0))
+        .chain(entry.aliases.iter().map(|a| ("alias", a, 0)))
+        .chain(std::iter::once(("id", &entry.id, ID_PENALTY)))
+        .chain(std::iter::once(("path", &entry.path, PATH_PENALTY)));
+
+    fields
+        .filter_map(|(field, text, penalty)| {
+            fuzzy::fuzzy_match(query, text).map(|mut m| {
+                m.score -= penalty;
+                (field, text.clone(), m)
+            })
+        })
+        .max_by_key(|(_, _, m)| m.score)
+}
+
+/// Current entries, rebuilt from the index if anything changed since they
+/// were collected.
+fn entries() -> Arc<Vec<Entry>> {
+    let generation = index::read(|idx| idx.generation());
+
+    if let Ok(cache) = ENTRIES.read() {
+        if cache.0 == Some(generation) {
+            return cache.1.clone();
+        }
+    }
+
+    let (generation, entries) = index::read(|idx| {
+        let entries: Vec<Entry> = idx.docs().filter_map(entry_for).collect();
+        (idx.generation(), Arc::new(entries))
+    });
+    if let Ok(mut cache) = ENTRIES.write() {
+        *cache = (Some(generation), entries.clone());
+    }
+    entries
+}
+
+/// The switcher entry for a document, classified by location like the list
+/// endpoints (project notes are left out; a project is its `index.md`).
+fn entry_for(doc: &IndexedDoc) -> Option<Entry> {
+    let parts: Vec<&str> = doc.path.split('/').collect();
+    let stem = parts.last()?.trim_end_matches(".md");
+    let (kind, id, path, project_id) = match parts.as_slice() {
+        ["projects", project, "index.md"] => (
+            "project",
+            project.to_string(),
+            format!("projects/{}", project),
+            Some(project.to_string()),
+        ),
+        ["projects", project, "tasks", _] => (
+            "task",
+            frontmatter::get_str_or(&doc.frontmatter, "id", stem),
+            doc.path.clone(),
+            Some(project.to_string()),
+        ),
+        ["daily", _] if NaiveDate::parse_from_str(stem, "%Y-%m-%d").is_ok() => {
+            ("daily", format!("daily-{}", stem), doc.path.clone(), None)
+        }
+        ["notes", _, ..] | [_] => ("note", doc.id.clone(), doc.path.clone(), None),
+        _ => return None,
+    };
+
+    Some(Entry {
+        kind,
+        id,
+        title: doc.title.clone(),
+        path,
+        project_id,
+        aliases: frontmatter::get_string_seq(&doc.frontmatter, "aliases"),
+    })
+}
@@ -0,0 +1,100 @@
+use axum::{
+    extract::{Path, Query},
+    http::StatusCode,
+    response::IntoResponse,
+    routing::{get, post},
+    Json, Router,
+};
+use serde::Deserialize;
+
+use crate::services::tags;
+
+#[derive(Debug, Deserialize)]
+pub struct TagsQuery {
+    /// Only count documents in this project
+    pub project: Option<String>,
+    /// Only count documents of this type (note, task, project, daily, ...)
+    #[serde(rename = "type")]
+    pub doc_type: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TaggedQuery {
+    /// Leave out documents that only carry sub-tags
+    #[serde(default)]
+    pub exact: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RenameTagRequest {
+    pub from: String,
+    pub to: String,
+    /// Commit message (defaults to "Rename tag #{from} to #{to}")
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MergeTagsRequest {
+    pub from: Vec<String>,
+    pub into: String,
+    /// Commit message (defaults to "Merge #{from} into #{into}")
+    pub message: Option<String>,
+}
+
+pub fn router() -> Router {
+    Router::new()
+        .route("/", get(list_tags))
+        .route("/rename", post(rename_tag))
+        .route("/merge", post(merge_tags))
+        .route("/{*tag}", get(tagged))
+}
+
+async fn list_tags(Query(params): Query<TagsQuery>) -> impl IntoResponse {
+    Json(tags::summary(
+        params.project.as_deref(),
+        params.doc_type.as_deref(),
+    ))
+}
+
+/// Documents carrying a tag or one of its sub-tags.
+async fn tagged(Path(tag): Path<String>, Query(params): Query<TaggedQuery>) -> impl IntoResponse {
+    match tags::tagged(&tag, params.exact) {
+        Ok(docs) => Json(docs).into_response(),
+        Err(err) if err.starts_with("Invalid") => (StatusCode::BAD_REQUEST, err).into_response(),
+        Err(err) if err.contains("not found") => (StatusCode::NOT_FOUND, err).into_response(),
+        Err(err) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to list tagged documents: {}", err),
+        )
+            .into_response(),
+    }
+}
+
+/// Rename a tag (and its sub-tags) in every file, committing once.
+async fn rename_tag(Json(payload): Json<RenameTagRequest>) -> impl IntoResponse {
+    match tags::rename(&payload.from, &payload.to, payload.message.as_deref()) {
+        Ok(change) => Json(change).into_response(),
+        Err(err) if err.starts_with("Invalid") => (StatusCode::BAD_REQUEST, err).into_response(),
+        Err(err) if err.contains("not found") => (StatusCode::NOT_FOUND, err).into_response(),
+        Err(err) if err.contains("already exists") => (StatusCode::CONFLICT, err).into_response(),
+        Err(err) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to rename tag: {}", err),
+        )
+            .into_response(),
+    }
+}
+
+/// Merge tags into another, committing once.
+async fn merge_tags(Json(payload): Json<MergeTagsRequest>) -> impl IntoResponse {
+    match tags::merge(&payload.from, &payload.into, payload.message.as_deref()) {
+        Ok(change) => Json(change).into_response(),
+        Err(err) if err.starts_with("Invalid") => (StatusCode::BAD_REQUEST, err).into_response(),
+        Err(err) if err.contains("not found") => (StatusCode::NOT_FOUND, err).into_response(),
+        Err(err) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to merge tags: {}", err),
+        )
+            .into_response(),
+    }
+}
@@ -4,8 +4,16 @@ use std::fs;
 use std::path::Path as StdPath;
 
 use crate::config;
+use crate::routes::{conflict, invalid_frontmatter, with_etag};
+use crate::services::archive;
+use crate::services::etag;
 use crate::services::filesystem;
 use crate::services::frontmatter;
+use crate::services::index;
+use crate::services::links;
+use crate::services::recent;
+use crate::services::schemas::{self, WriteError};
+use crate::services::templates::{self, TemplateContext};
 
 /// Task summary for list views
 #[derive(Debug, Clone, Serialize)]
@@ -25,6 +33,9 @@ pub struct Task {
     pub path: String,
     pub created: String,
     pub updated: String,
+    /// ETag of the file as read or written (sent as a header, see `etag::of`)
+    #[serde(skip)]
+    pub etag: String,
 }
 
 /// Task with full content for detail view
@@ -46,6 +57,12 @@ pub struct TaskWithContent {
     pub created: String,
     pub updated: String,
     pub content: String,
+    /// Where a new task's template put `{{cursor}}` (character offset into `content`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<usize>,
+    /// ETag of the file as read or written (sent as a header, see `etag::of`)
+    #[serde(skip)]
+    pub etag: String,
 }
 
 #[derive(Debug, Deserialize)]
@@ -53,6 +70,8 @@ pub struct CreateTaskRequest {
     pub title: String,
     pub section: Option<String>,
     pub parent_id: Option<String>,
+    /// Template name in `data/templates/` (defaults to the vault's task template)
+    pub template: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -95,8 +114,13 @@ pub async fn create_task_handler(
         &payload.title,
         payload.section.as_deref(),
         payload.parent_id.as_deref(),
+        payload.template.as_deref(),
     ) {
-        Ok(task) => (StatusCode::CREATED, Json(task)).into_response(),
+        Ok(task) => with_etag(StatusCode::CREATED, &task.etag, &task),
+        Err(WriteError::Invalid(report)) => invalid_frontmatter(report),
+        Err(WriteError::Other(err)) if err.starts_with("Invalid") => {
+            (StatusCode::BAD_REQUEST, err).into_response()
+        }
         Err(err) => (
             StatusCode::INTERNAL_SERVER_ERROR,
             format!("Failed to create task: {}", err),
@@ -108,7 +132,10 @@ pub async fn create_task_handler(
 /// Get a task with content
 pub async fn get_task_handler(project_id: String, task_id: String) -> impl IntoResponse {
     match get_task_impl(&project_id, &task_id) {
-        Ok(task) => Json(task).into_response(),
+        Ok(task) => {
+            recent::record_access(&task.path);
+            with_etag(StatusCode::OK, &task.etag, &task)
+        }
         Err(err) if err.contains("not found") => (StatusCode::NOT_FOUND, err).into_response(),
         Err(err) => (
             StatusCode::INTERNAL_SERVER_ERROR,
@@ -118,15 +145,36 @@ pub async fn get_task_handler(project_id: String, task_id: String) -> impl IntoR
     }
 }
 
+/// Notes, tasks and daily notes that link to a task
+pub async fn task_backlinks_handler(project_id: String, task_id: String) -> impl IntoResponse {
+    let result = find_task_path(&project_id, &task_id)
+        .and_then(|path| links::backlinks(&filesystem::normalize_path(&path)));
+
+    match result {
+        Ok(backlinks) => Json(backlinks).into_response(),
+        Err(err) if err.contains("not found") => (StatusCode::NOT_FOUND, err).into_response(),
+        Err(err) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to find backlinks: {}", err),
+        )
+            .into_response(),
+    }
+}
+
 /// Update task content (markdown body)
 pub async fn update_task_content_handler(
     project_id: String,
     task_id: String,
+    if_match: Option<&str>,
     body: String,
 ) -> impl IntoResponse {
-    match update_task_content_impl(&project_id, &task_id, &body) {
-        Ok(task) => Json(task).into_response(),
+    match update_task_content_impl(&project_id, &task_id, &body, if_match) {
+        Ok(task) => with_etag(StatusCode::OK, &task.etag, &task),
         Err(err) if err.contains("not found") => (StatusCode::NOT_FOUND, err).into_response(),
+        Err(err) if err.starts_with("Conflict") => {
+            conflict(get_task_handler(project_id, task_id).await)
+        }
+        Err(err) if err.starts_with("Invalid") => (StatusCode::BAD_REQUEST, err).into_response(),
         Err(err) => (
             StatusCode::INTERNAL_SERVER_ERROR,
             format!("Failed to update task: {}", err),
@@ -136,10 +184,18 @@ pub async fn update_task_content_handler(
 }
 
 /// Toggle task completion
-pub async fn toggle_task_handler(project_id: String, task_id: String) -> impl IntoResponse {
-    match toggle_task_impl(&project_id, &task_id) {
-        Ok(task) => Json(task).into_response(),
+pub async fn toggle_task_handler(
+    project_id: String,
+    task_id: String,
+    if_match: Option<&str>,
+) -> impl IntoResponse {
+    match toggle_task_impl(&project_id, &task_id, if_match) {
+        Ok(task) => with_etag(StatusCode::OK, &task.etag, &task),
         Err(err) if err.contains("not found") => (StatusCode::NOT_FOUND, err).into_response(),
+        Err(err) if err.starts_with("Conflict") => {
+            conflict(get_task_handler(project_id, task_id).await)
+        }
+        Err(err) if err.starts_with("Invalid") => (StatusCode::BAD_REQUEST, err).into_response(),
         Err(err) => (
             StatusCode::INTERNAL_SERVER_ERROR,
             format!("Failed to toggle task: {}", err),
@@ -152,11 +208,21 @@ pub async fn toggle_task_handler(project_id: String, task_id: String) -> impl In
 pub async fn update_task_meta_handler(
     project_id: String,
     task_id: String,
+    if_match: Option<&str>,
     payload: UpdateTaskMetaRequest,
 ) -> impl IntoResponse {
-    match update_task_meta_impl(&project_id, &task_id, payload) {
-        Ok(task) => Json(task).into_response(),
-        Err(err) if err.contains("not found") => (StatusCode::NOT_FOUND, err).into_response(),
+    match update_task_meta_impl(&project_id, &task_id, payload, if_match) {
+        Ok(task) => with_etag(StatusCode::OK, &task.etag, &task),
+        Err(WriteError::Invalid(report)) => invalid_frontmatter(report),
+        Err(WriteError::Other(err)) if err.contains("not found") => {
+            (StatusCode::NOT_FOUND, err).into_response()
+        }
+        Err(WriteError::Other(err)) if err.starts_with("Invalid") => {
+            (StatusCode::BAD_REQUEST, err).into_response()
+        }
+        Err(WriteError::Other(err)) if err.starts_with("Conflict") => {
+            conflict(get_task_handler(project_id, task_id).await)
+        }
         Err(err) => (
             StatusCode::INTERNAL_SERVER_ERROR,
             format!("Failed to update task metadata: {}", err),
@@ -258,12 +324,16 @@ fn extract_task_fields(fm: &serde_yaml::Mapping, path: &StdPath, project_id: &st
         path: format!("projects/{}/tasks/{}.md", project_id, filename),
         created: frontmatter::get_str_or(fm, "created", ""),
         updated: frontmatter::get_str_or(fm, "updated", ""),
+        etag: String::new(),
     }
 }
 
 fn parse_task_file(content: &str, path: &StdPath, project_id: &str) -> Option<Task> {
     let (fm, _, _) = frontmatter::parse_frontmatter(content);
-    Some(extract_task_fields(&fm, path, project_id))
+    Some(Task {
+        etag: etag::of(content.as_bytes()),
+        ..extract_task_fields(&fm, path, project_id)
+    })
 }
 
 fn create_task_impl(
@@ -271,7 +341,8 @@ fn create_task_impl(
     title: &str,
     section: Option<&str>,
     parent_id: Option<&str>,
-) -> Result<TaskWithContent, String> {
+    template: Option<&str>,
+) -> Result<TaskWithContent, WriteError> {
     use chrono::Utc;
 
     let tasks_dir = ensure_tasks_dir(project_id)?;
@@ -281,10 +352,16 @@ fn create_task_impl(
     let filename = format!("task-{}", now.format("%Y%m%d-%H%M%S"));
     let task_path = tasks_dir.join(format!("{}.md", filename));
 
-    let section = section.unwrap_or("Active").to_string();
     let now_str = now.to_rfc3339();
     let id = format!("{}-{}", project_id, filename);
 
+    let ctx = TemplateContext {
+        title: title.to_string(),
+        date: now.date_naive(),
+        project: Some(templates::project_name(project_id)),
+    };
+    let rendered = templates::for_new("task", template, &ctx)?;
+
     let mut fm = serde_yaml::Mapping::new();
     fm.insert(
         serde_yaml::Value::from("id"),
@@ -302,18 +379,31 @@ fn create_task_impl(
         serde_yaml::Value::from("completed"),
         serde_yaml::Value::from(false),
     );
-    fm.insert(
-        serde_yaml::Value::from("section"),
-        serde_yaml::Value::from(section.clone()),
-    );
-    fm.insert(
-        serde_yaml::Value::from("priority"),
-        serde_yaml::Value::from("normal"),
-    );
-    fm.insert(
-        serde_yaml::Value::from("is_active"),
-        serde_yaml::Value::from(true),
-    );
+
+    // Template fields (priority, tags, ...) replace the built-in defaults
+    if let Some(rendered) = &rendered {
+        for (key, value) in &rendered.frontmatter {
+            if !matches!(
+                key.as_str(),
+                Some("title" | "completed" | "project_id" | "parent_id")
+            ) {
+                fm.insert(key.clone(), value.clone());
+            }
+        }
+    }
+    if let Some(section) = section {
+        fm.insert(
+            serde_yaml::Value::from("section"),
+            serde_yaml::Value::from(section),
+        );
+    }
+    schemas::apply_defaults(&task_path, &mut fm);
+    fm.entry(serde_yaml::Value::from("section"))
+        .or_insert_with(|| serde_yaml::Value::from("Active"));
+    fm.entry(serde_yaml::Value::from("priority"))
+        .or_insert_with(|| serde_yaml::Value::from("normal"));
+    fm.entry(serde_yaml::Value::from("is_active"))
+        .or_insert_with(|| serde_yaml::Value::from(true));
     fm.insert(
         serde_yaml::Value::from("project_id"),
         serde_yaml::Value::from(project_id),
@@ -330,79 +420,39 @@ fn create_task_impl(
     );
     fm.insert(
         serde_yaml::Value::from("updated"),
-        serde_yaml::Value::from(now_str.clone()),
+        serde_yaml::Value::from(now_str),
     );
 
-    let body = format!("# {}\n\n", title);
+    let (body, cursor) = match rendered {
+        Some(rendered) => (rendered.body, rendered.cursor),
+        None => (format!("# {}\n\n", title), None),
+    };
+    schemas::check(&task_path, &fm)?;
     let content = frontmatter::serialize_frontmatter(&fm, &body)?;
 
     filesystem::atomic_write(&task_path, content.as_bytes())?;
 
-    Ok(TaskWithContent {
-        id,
-        title: title.to_string(),
-        completed: false,
-        section,
-        priority: Some("normal".to_string()),
-        due_date: None,
-        is_active: true,
-        tags: Vec::new(),
-        parent_id: parent_id.map(String::from),
-        recurrence: None,
-        recurrence_interval: None,
-        project_id: project_id.to_string(),
-        path: format!("projects/{}/tasks/{}.md", project_id, filename),
-        created: now_str.clone(),
-        updated: now_str,
-        content: body,
-    })
+    let mut task = parse_task_with_content(&fm, &body, &content, &task_path, project_id)?;
+    task.cursor = cursor;
+    Ok(task)
 }
 
 fn get_task_impl(project_id: &str, task_id: &str) -> Result<TaskWithContent, String> {
-    let tasks_dir = get_tasks_dir(project_id);
-
-    // Try direct filename match first
-    let task_path = tasks_dir.join(format!("{}.md", task_id));
-
-    if task_path.exists() {
-        return read_task_with_content(&task_path, project_id);
-    }
-
-    // Search by ID in frontmatter
-    if let Ok(entries) = fs::read_dir(&tasks_dir) {
-        for entry in entries.flatten() {
-            let path = entry.path();
-            if path.extension().and_then(|s| s.to_str()) != Some("md") {
-                continue;
-            }
-
-            if let Ok(content) = fs::read_to_string(&path) {
-                let (fm, body, _) = frontmatter::parse_frontmatter(&content);
-
-                let file_id = fm
-                    .get(&serde_yaml::Value::from("id"))
-                    .and_then(|v| v.as_str())
-                    .map(String::from);
-
-                if file_id.as_deref() == Some(task_id) {
-                    return parse_task_with_content(&fm, &body, &path, project_id);
-                }
-            }
-        }
-    }
-
-    Err("Task not found".to_string())
+    let task_path = find_task_path(project_id, task_id)?;
+    read_task_with_content(&task_path, project_id)
 }
 
 fn read_task_with_content(path: &StdPath, project_id: &str) -> Result<TaskWithContent, String> {
     let content = fs::read_to_string(path).map_err(|e| e.to_string())?;
     let (fm, body, _) = frontmatter::parse_frontmatter(&content);
-    parse_task_with_content(&fm, &body, path, project_id)
+    parse_task_with_content(&fm, &body, &content, path, project_id)
 }
 
+/// `file` is the whole file, as read or written, for the ETag.
 fn parse_task_with_content(
     fm: &serde_yaml::Mapping,
     body: &str,
+    file: &str,
     path: &StdPath,
     project_id: &str,
 ) -> Result<TaskWithContent, String> {
@@ -424,6 +474,8 @@ fn parse_task_with_content(
         created: task.created,
         updated: task.updated,
         content: body.to_string(),
+        cursor: None,
+        etag: etag::of(file.as_bytes()),
     })
 }
 
@@ -431,11 +483,13 @@ fn update_task_content_impl(
     project_id: &str,
     task_id: &str,
     new_body: &str,
+    if_match: Option<&str>,
 ) -> Result<TaskWithContent, String> {
     let task_path = find_task_path(project_id, task_id)?;
 
     // Read existing content
     let existing = fs::read_to_string(&task_path).map_err(|e| e.to_string())?;
+    etag::check(if_match, existing.as_bytes())?;
     let (mut fm, _, _) = frontmatter::parse_frontmatter(&existing);
 
     // Update timestamp
@@ -446,22 +500,27 @@ fn update_task_content_impl(
     );
 
     // Serialize with new content (atomic write to prevent corruption)
-    let new_content = frontmatter::serialize_frontmatter(&fm, new_body)?;
+    let new_content = frontmatter::rewrite(&existing, &fm, new_body)?;
     filesystem::atomic_write(&task_path, new_content.as_bytes())?;
 
-    parse_task_with_content(&fm, new_body, &task_path, project_id)
+    parse_task_with_content(&fm, new_body, &new_content, &task_path, project_id)
 }
 
-fn toggle_task_impl(project_id: &str, task_id: &str) -> Result<Task, String> {
+fn toggle_task_impl(
+    project_id: &str,
+    task_id: &str,
+    if_match: Option<&str>,
+) -> Result<Task, String> {
     let task_path = find_task_path(project_id, task_id)?;
 
     // Read existing content
     let existing = fs::read_to_string(&task_path).map_err(|e| e.to_string())?;
+    etag::check(if_match, existing.as_bytes())?;
     let (mut fm, body, _) = frontmatter::parse_frontmatter(&existing);
 
     // Toggle completed
     let current_completed = fm
-        .get(&serde_yaml::Value::from("completed"))
+        .get(serde_yaml::Value::from("completed"))
         .and_then(|v| v.as_bool())
         .unwrap_or(false);
 
@@ -486,35 +545,35 @@ fn toggle_task_impl(project_id: &str, task_id: &str) -> Result<Task, String> {
     );
 
     // Serialize and write (atomic to prevent corruption)
-    let new_content = frontmatter::serialize_frontmatter(&fm, &body)?;
+    let new_content = frontmatter::rewrite(&existing, &fm, &body)?;
     filesystem::atomic_write(&task_path, new_content.as_bytes())?;
 
     // If completing a recurring task, create the next instance
     if new_completed {
         let recurrence = fm
-            .get(&serde_yaml::Value::from("recurrence"))
+            .get(serde_yaml::Value::from("recurrence"))
             .and_then(|v| v.as_str())
             .map(String::from);
 
         if let Some(rec) = recurrence {
             let interval = fm
-                .get(&serde_yaml::Value::from("recurrence_interval"))
+                .get(serde_yaml::Value::from("recurrence_interval"))
                 .and_then(|v| v.as_u64())
                 .unwrap_or(1) as i64;
 
             let title = fm
-                .get(&serde_yaml::Value::from("title"))
+                .get(serde_yaml::Value::from("title"))
                 .and_then(|v| v.as_str())
                 .unwrap_or("Untitled")
                 .to_string();
 
             let due_date = fm
-                .get(&serde_yaml::Value::from("due_date"))
+                .get(serde_yaml::Value::from("due_date"))
                 .and_then(|v| v.as_str())
                 .map(String::from);
 
             let tags = fm
-                .get(&serde_yaml::Value::from("tags"))
+                .get(serde_yaml::Value::from("tags"))
                 .and_then(|v| v.as_sequence())
                 .map(|seq| {
                     seq.iter()
@@ -683,6 +742,8 @@ fn create_recurring_task_impl(
         created: now_str.clone(),
         updated: now_str,
         content: body,
+        cursor: None,
+        etag: etag::of(content.as_bytes()),
     })
 }
 
@@ -690,11 +751,13 @@ fn update_task_meta_impl(
     project_id: &str,
     task_id: &str,
     meta: UpdateTaskMetaRequest,
-) -> Result<Task, String> {
+    if_match: Option<&str>,
+) -> Result<Task, WriteError> {
     let task_path = find_task_path(project_id, task_id)?;
 
     // Read existing content
     let existing = fs::read_to_string(&task_path).map_err(|e| e.to_string())?;
+    etag::check(if_match, existing.as_bytes())?;
     let (mut fm, body, _) = frontmatter::parse_frontmatter(&existing);
 
     // Update fields if provided
@@ -738,8 +801,8 @@ fn update_task_meta_impl(
     }
     if let Some(recurrence) = meta.recurrence {
         if recurrence.is_empty() {
-            fm.remove(&serde_yaml::Value::from("recurrence"));
-            fm.remove(&serde_yaml::Value::from("recurrence_interval"));
+            fm.remove(serde_yaml::Value::from("recurrence"));
+            fm.remove(serde_yaml::Value::from("recurrence_interval"));
         } else {
             fm.insert(
                 serde_yaml::Value::from("recurrence"),
@@ -761,8 +824,10 @@ fn update_task_meta_impl(
         serde_yaml::Value::from(now),
     );
 
+    schemas::check(&task_path, &fm)?;
+
     // Serialize and write (atomic to prevent corruption)
-    let new_content = frontmatter::serialize_frontmatter(&fm, &body)?;
+    let new_content = frontmatter::rewrite(&existing, &fm, &body)?;
     filesystem::atomic_write(&task_path, new_content.as_bytes())?;
 
     // Return updated task
@@ -780,16 +845,12 @@ fn delete_task_impl(project_id: &str, task_id: &str) -> Result<(), String> {
     let task_path = find_task_path(project_id, task_id)?;
 
     // Move to archive
-    let archive_dir = config::data_dir().join("archive");
-    fs::create_dir_all(&archive_dir).map_err(|e| e.to_string())?;
-
     let filename = task_path
         .file_name()
         .and_then(|s| s.to_str())
         .unwrap_or("task.md");
 
-    let archive_path = archive_dir.join(format!("{}-{}", project_id, filename));
-    fs::rename(&task_path, &archive_path).map_err(|e| e.to_string())?;
+    archive::archive_file(&task_path, &format!("{}-{}", project_id, filename))?;
 
     Ok(())
 }
@@ -803,29 +864,9 @@ fn find_task_path(project_id: &str, task_id: &str) -> Result<std::path::PathBuf,
         return Ok(direct_path);
     }
 
-    // Search by ID in frontmatter
-    if let Ok(entries) = fs::read_dir(&tasks_dir) {
-        for entry in entries.flatten() {
-            let path = entry.path();
-            if path.extension().and_then(|s| s.to_str()) != Some("md") {
-                continue;
-            }
-
-            if let Ok(content) = fs::read_to_string(&path) {
-                let (fm, _, _) = frontmatter::parse_frontmatter(&content);
-
-                let file_id = fm
-                    .get(&serde_yaml::Value::from("id"))
-                    .and_then(|v| v.as_str());
-
-                if file_id == Some(task_id) {
-                    return Ok(path);
-                }
-            }
-        }
-    }
-
-    Err("Task not found".to_string())
+    // Look up the ID in frontmatter via the index registry
+    index::find_path(task_id, |path| path.starts_with(&tasks_dir))
+        .ok_or_else(|| "Task not found".to_string())
 }
 
 // ============ Legacy/Global Task Listing ============
@@ -0,0 +1,46 @@
+use axum::{extract::Path, http::StatusCode, response::IntoResponse, routing::get, Json, Router};
+
+use crate::services::templates;
+
+pub fn router() -> Router {
+    Router::new()
+        .route("/", get(list_templates))
+        .route("/{name}", get(get_template).put(save_template))
+}
+
+async fn list_templates() -> impl IntoResponse {
+    match templates::list() {
+        Ok(list) => Json(list).into_response(),
+        Err(err) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to list templates: {}", err),
+        )
+            .into_response(),
+    }
+}
+
+async fn get_template(Path(name): Path<String>) -> impl IntoResponse {
+    match templates::get(&name) {
+        Ok(template) => Json(template).into_response(),
+        Err(err) if err.starts_with("Invalid") => (StatusCode::BAD_REQUEST, err).into_response(),
+        Err(err) if err.contains("not found") => (StatusCode::NOT_FOUND, err).into_response(),
+        Err(err) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to read template: {}", err),
+        )
+            .into_response(),
+    }
+}
+
+/// Create or replace a template with the raw markdown body.
+async fn save_template(Path(name): Path<String>, body: String) -> impl IntoResponse {
+    match templates::save(&name, &body) {
+        Ok(template) => Json(template).into_response(),
+        Err(err) if err.starts_with("Invalid") => (StatusCode::BAD_REQUEST, err).into_response(),
+        Err(err) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to save template: {}", err),
+        )
+            .into_response(),
+    }
+}
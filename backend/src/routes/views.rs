@@ -0,0 +1,394 @@
+use axum::{
+    extract::{Path, Query},
+    http::{HeaderMap, HeaderName, StatusCode},
+    response::IntoResponse,
+    routing::get,
+    Json, Router,
+};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serde_yaml::{Mapping, Value};
+use std::fs;
+
+use crate::config;
+use crate::routes::{conflict, if_match, with_etag};
+use crate::services::archive;
+use crate::services::etag;
+use crate::services::filesystem;
+use crate::services::frontmatter;
+use crate::services::matcher::{MatchOptions, Needle, TextMatcher};
+use crate::services::search::{self, SearchMode, SearchOptions};
+
+/// Default and maximum page size when running a view
+const DEFAULT_LIMIT: usize = 50;
+const MAX_LIMIT: usize = 500;
+
+/// A saved search, stored as `data/views/{id}.md`.
+/// The query lives in frontmatter; the body is a free-form description.
+#[derive(Debug, Serialize)]
+pub struct SavedView {
+    pub id: String,
+    pub title: String,
+    pub path: String,
+    pub query: String,
+    pub mode: SearchMode,
+    pub case_sensitive: bool,
+    pub whole_word: bool,
+    pub fold_diacritics: bool,
+    pub description: String,
+    pub created: String,
+    pub updated: String,
+    /// ETag of the file as read or written (sent as a header, see `etag::of`)
+    #[serde(skip)]
+    pub etag: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateViewRequest {
+    pub title: String,
+    pub query: String,
+    #[serde(default)]
+    pub mode: SearchMode,
+    #[serde(flatten)]
+    pub matching: MatchOptions,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateViewRequest {
+    pub title: Option<String>,
+    pub query: Option<String>,
+    pub mode: Option<SearchMode>,
+    pub case_sensitive: Option<bool>,
+    pub whole_word: Option<bool>,
+    pub fold_diacritics: Option<bool>,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RunViewQuery {
+    limit: Option<usize>,
+    offset: Option<usize>,
+}
+
+pub fn router() -> Router {
+    Router::new()
+        .route("/", get(list_views).post(create_view))
+        .route("/{id}", get(get_view).put(update_view).delete(delete_view))
+        .route("/{id}/results", get(run_view))
+}
+
+async fn list_views() -> impl IntoResponse {
+    match list_views_impl() {
+        Ok(views) => Json(views).into_response(),
+        Err(err) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to list views: {}", err),
+        )
+            .into_response(),
+    }
+}
+
+fn list_views_impl() -> Result<Vec<SavedView>, String> {
+    let views_dir = config::data_dir().join("views");
+
+    if !views_dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut views = Vec::new();
+
+    for entry in fs::read_dir(&views_dir).map_err(|e| e.to_string())? {
+        let entry = entry.map_err(|e| e.to_string())?;
+        let path = entry.path();
+
+        if path.extension().and_then(|s| s.to_str()) != Some("md") {
+            continue;
+        }
+
+        let id = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("")
+            .to_string();
+
+        match get_view_impl(&id) {
+            Ok(view) => views.push(view),
+            Err(err) => tracing::warn!("Skipping view {:?}: {}", path, err),
+        }
+    }
+
+    views.sort_by_key(|v| v.title.to_lowercase());
+
+    Ok(views)
+}
+
+async fn get_view(Path(id): Path<String>) -> impl IntoResponse {
+    match get_view_impl(&id) {
+        Ok(view) => with_etag(StatusCode::OK, &view.etag, &view),
+        Err(err) if err.contains("not found") => (StatusCode::NOT_FOUND, err).into_response(),
+        Err(err) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to get view: {}", err),
+        )
+            .into_response(),
+    }
+}
+
+fn view_path(id: &str) -> Result<std::path::PathBuf, String> {
+    // Ids are slugs; anything else can't name a view (and must not escape the folder)
+    if id.is_empty() || !id.chars().all(|c| c.is_alphanumeric() || c == '-') {
+        return Err(format!("View not found: {}", id));
+    }
+    Ok(config::data_dir().join("views").join(format!("{}.md", id)))
+}
+
+fn get_view_impl(id: &str) -> Result<SavedView, String> {
+    let path = view_path(id)?;
+
+    if !path.exists() {
+        return Err(format!("View not found: {}", id));
+    }
+
+    let content = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    let (fm, body, _) = frontmatter::parse_frontmatter(&content);
+
+    Ok(view_from_frontmatter(id, &fm, &body, &content))
+}
+
+/// `file` is the whole file, as read or written, for the ETag.
+fn view_from_frontmatter(id: &str, fm: &Mapping, body: &str, file: &str) -> SavedView {
+    let mode = frontmatter::get_str(fm, "mode")
+        .and_then(|m| serde_yaml::from_value(Value::from(m)).ok())
+        .unwrap_or_default();
+
+    SavedView {
+        id: id.to_string(),
+        title: frontmatter::get_str_or(fm, "title", id),
+        path: format!("views/{}.md", id),
+        query: frontmatter::get_str_or(fm, "query", ""),
+        mode,
+        case_sensitive: frontmatter::get_bool_or(fm, "case_sensitive", false),
+        whole_word: frontmatter::get_bool_or(fm, "whole_word", false),
+        fold_diacritics: frontmatter::get_bool_or(fm, "fold_diacritics", false),
+        description: body.trim().to_string(),
+        created: frontmatter::get_str_or(fm, "created", ""),
+        updated: frontmatter::get_str_or(fm, "updated", ""),
+        etag: etag::of(file.as_bytes()),
+    }
+}
+
+async fn create_view(Json(payload): Json<CreateViewRequest>) -> impl IntoResponse {
+    match create_view_impl(&payload) {
+        Ok(view) => with_etag(StatusCode::CREATED, &view.etag, &view),
+        Err(err) if err.contains("already exists") => (StatusCode::CONFLICT, err).into_response(),
+        Err(err) if err.starts_with("Invalid") => (StatusCode::BAD_REQUEST, err).into_response(),
+        Err(err) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to create view: {}", err),
+        )
+            .into_response(),
+    }
+}
+
+fn create_view_impl(payload: &CreateViewRequest) -> Result<SavedView, String> {
+    let slug = filesystem::slugify(&payload.title);
+
+    if slug.is_empty() {
+        return Err("Invalid view title".to_string());
+    }
+
+    validate_query(&payload.query, payload.mode)?;
+
+    let views_dir = config::data_dir().join("views");
+    fs::create_dir_all(&views_dir).map_err(|e| e.to_string())?;
+
+    let path = view_path(&slug)?;
+    if path.exists() {
+        return Err(format!("View already exists: {}", slug));
+    }
+
+    let now = Utc::now().to_rfc3339();
+
+    let mut fm = Mapping::new();
+    fm.insert(Value::from("id"), Value::from(slug.clone()));
+    fm.insert(Value::from("type"), Value::from("view"));
+    fm.insert(Value::from("title"), Value::from(payload.title.clone()));
+    fm.insert(Value::from("query"), Value::from(payload.query.clone()));
+    set_options(&mut fm, payload.mode, &payload.matching);
+    fm.insert(Value::from("created"), Value::from(now.clone()));
+    fm.insert(Value::from("updated"), Value::from(now));
+
+    let body = payload.description.as_deref().unwrap_or("");
+    let content = frontmatter::serialize_frontmatter(&fm, body)?;
+
+    filesystem::atomic_write(&path, content.as_bytes())?;
+
+    Ok(view_from_frontmatter(&slug, &fm, body, &content))
+}
+
+async fn update_view(
+    Path(id): Path<String>,
+    headers: HeaderMap,
+    Json(payload): Json<UpdateViewRequest>,
+) -> impl IntoResponse {
+    match update_view_impl(&id, &payload, if_match(&headers)) {
+        Ok(view) => with_etag(StatusCode::OK, &view.etag, &view),
+        Err(err) if err.contains("not found") => (StatusCode::NOT_FOUND, err).into_response(),
+        Err(err) if err.starts_with("Conflict") => conflict(get_view(Path(id)).await),
+        Err(err) if err.starts_with("Invalid") => (StatusCode::BAD_REQUEST, err).into_response(),
+        Err(err) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to update view: {}", err),
+        )
+            .into_response(),
+    }
+}
+
+fn update_view_impl(
+    id: &str,
+    payload: &UpdateViewRequest,
+    if_match: Option<&str>,
+) -> Result<SavedView, String> {
+    let path = view_path(id)?;
+
+    if !path.exists() {
+        return Err(format!("View not found: {}", id));
+    }
+
+    let content = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    etag::check(if_match, content.as_bytes())?;
+    let (mut fm, body, _) = frontmatter::parse_frontmatter(&content);
+    let current = view_from_frontmatter(id, &fm, &body, &content);
+
+    let query = payload.query.clone().unwrap_or(current.query);
+    let mode = payload.mode.unwrap_or(current.mode);
+    let matching = MatchOptions {
+        case_sensitive: payload.case_sensitive.unwrap_or(current.case_sensitive),
+        whole_word: payload.whole_word.unwrap_or(current.whole_word),
+        fold_diacritics: payload.fold_diacritics.unwrap_or(current.fold_diacritics),
+    };
+
+    validate_query(&query, mode)?;
+
+    if let Some(ref title) = payload.title {
+        fm.insert(Value::from("title"), Value::from(title.clone()));
+    }
+    fm.insert(Value::from("query"), Value::from(query));
+    set_options(&mut fm, mode, &matching);
+    frontmatter::update_frontmatter(&mut fm);
+
+    let body = payload.description.clone().unwrap_or(body);
+    let new_content = frontmatter::rewrite(&content, &fm, &body)?;
+
+    filesystem::atomic_write(&path, new_content.as_bytes())?;
+
+    Ok(view_from_frontmatter(id, &fm, &body, &new_content))
+}
+
+/// Store the search mode and only the match options that are switched on.
+fn set_options(fm: &mut Mapping, mode: SearchMode, matching: &MatchOptions) {
+    let mode = serde_yaml::to_value(mode).unwrap_or(Value::from("query"));
+    fm.insert(Value::from("mode"), mode);
+
+    for (key, enabled) in [
+        ("case_sensitive", matching.case_sensitive),
+        ("whole_word", matching.whole_word),
+        ("fold_diacritics", matching.fold_diacritics),
+    ] {
+        if enabled {
+            fm.insert(Value::from(key), Value::from(true));
+        } else {
+            fm.remove(Value::from(key));
+        }
+    }
+}
+
+/// Reject queries that would fail every time the view is run.
+fn validate_query(query: &str, mode: SearchMode) -> Result<(), String> {
+    if query.trim().is_empty() {
+        return Err("Invalid query: empty".to_string());
+    }
+
+    search::parse_query(query, mode)?;
+    if mode == SearchMode::Regex {
+        TextMatcher::new(
+            &[Needle::Regex(query.to_string())],
+            &MatchOptions::default(),
+        )?;
+    }
+    Ok(())
+}
+
+async fn delete_view(Path(id): Path<String>) -> impl IntoResponse {
+    match delete_view_impl(&id) {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(err) if err.contains("not found") => (StatusCode::NOT_FOUND, err).into_response(),
+        Err(err) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to delete view: {}", err),
+        )
+            .into_response(),
+    }
+}
+
+/// Move a view to the archive (like deleting a note).
+fn delete_view_impl(id: &str) -> Result<(), String> {
+    let path = view_path(id)?;
+
+    if !path.exists() {
+        return Err(format!("View not found: {}", id));
+    }
+
+    archive::archive_file(&path, &format!("view-{}.md", id))?;
+
+    Ok(())
+}
+
+/// Execute a saved view. Results and headers are the same as `GET /api/search`.
+async fn run_view(Path(id): Path<String>, Query(params): Query<RunViewQuery>) -> impl IntoResponse {
+    let view = match get_view_impl(&id) {
+        Ok(view) => view,
+        Err(err) if err.contains("not found") => {
+            return (StatusCode::NOT_FOUND, err).into_response()
+        }
+        Err(err) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to get view: {}", err),
+            )
+                .into_response()
+        }
+    };
+
+    let limit = params.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+    let offset = params.offset.unwrap_or(0);
+    let options = SearchOptions {
+        mode: view.mode,
+        matching: MatchOptions {
+            case_sensitive: view.case_sensitive,
+            whole_word: view.whole_word,
+            fold_diacritics: view.fold_diacritics,
+        },
+        ..Default::default()
+    };
+
+    match search::search_notes(&view.query, &options, limit, offset) {
+        Ok(page) => (
+            [(
+                HeaderName::from_static("x-total-count"),
+                page.total.to_string(),
+            )],
+            Json(page.results),
+        )
+            .into_response(),
+        Err(err) if err.starts_with("Invalid query") => {
+            (StatusCode::BAD_REQUEST, err).into_response()
+        }
+        Err(err) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to run view: {}", err),
+        )
+            .into_response(),
+    }
+}
@@ -0,0 +1,376 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::Utc;
+use serde::Serialize;
+use serde_yaml::{Mapping, Value};
+
+use crate::config;
+use crate::services::filesystem;
+use crate::services::frontmatter;
+use crate::services::index;
+
+/// Frontmatter keys recording where an archived file came from
+const ARCHIVED_FROM: &str = "archived_from";
+const ARCHIVED_AT: &str = "archived_at";
+
+/// A file in `data/archive/`
+#[derive(Debug, Serialize)]
+pub struct ArchivedItem {
+    /// File name inside the archive, used to address the item
+    pub name: String,
+    pub id: String,
+    pub title: String,
+    /// note, task, project, daily, view, ...
+    pub item_type: String,
+    pub project_id: Option<String>,
+    /// Data-relative path the item is restored to
+    pub original_path: String,
+    /// Whether `original_path` was recorded at archive time (or guessed for older items)
+    pub recorded: bool,
+    pub archived_at: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ArchivedItemWithContent {
+    #[serde(flatten)]
+    pub item: ArchivedItem,
+    pub content: String,
+}
+
+fn archive_dir() -> PathBuf {
+    config::data_dir().join("archive")
+}
+
+/// Move a file into the archive as `name` (suffixed with `-2`, `-3`, ... if taken),
+/// recording its original path in frontmatter. Returns the archived name.
+pub fn archive_file(path: &Path, name: &str) -> Result<String, String> {
+    let archived = archived_content(path)?;
+    archive_prepared(path, name, &archived)
+}
+
+/// The content `path` is archived with: its frontmatter plus the origin keys.
+/// Frontmatter that doesn't parse is kept as it is, below a fresh block holding
+/// only the origin, so a broken file can still be archived (and restored as it was).
+pub fn archived_content(path: &Path) -> Result<String, String> {
+    let content = fs::read_to_string(path).map_err(|e| e.to_string())?;
+
+    let mut origin = Mapping::new();
+    origin.insert(
+        Value::from(ARCHIVED_FROM),
+        Value::from(filesystem::normalize_path(path)),
+    );
+    origin.insert(
+        Value::from(ARCHIVED_AT),
+        Value::from(Utc::now().to_rfc3339()),
+    );
+
+    with_origin(&content, origin)
+}
+
+fn with_origin(content: &str, origin: Mapping) -> Result<String, String> {
+    let parsed = frontmatter::parse(content);
+    if parsed.error.is_some() {
+        return frontmatter::serialize_frontmatter(&origin, content);
+    }
+    let mut fm = parsed.frontmatter;
+    fm.extend(origin);
+    frontmatter::rewrite(content, &fm, &parsed.body)
+}
+
+/// Move a file into the archive with content from `archived_content`.
+pub fn archive_prepared(path: &Path, name: &str, archived: &str) -> Result<String, String> {
+    let dir = archive_dir();
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+
+    let name = unique_name(&dir, name);
+    let target = dir.join(&name);
+
+    fs::rename(path, &target).map_err(|e| e.to_string())?;
+    index::remove_file(path);
+    filesystem::atomic_write(&target, archived.as_bytes())?;
+
+    Ok(name)
+}
+
+fn unique_name(dir: &Path, name: &str) -> String {
+    let stem = name.strip_suffix(".md").unwrap_or(name);
+    let mut candidate = format!("{}.md", stem);
+    let mut n = 2;
+    while dir.join(&candidate).exists() {
+        candidate = format!("{}-{}.md", stem, n);
+        n += 1;
+    }
+    candidate
+}
+
+/// Archived items, most recently archived first.
+/// `query` matches titles, ids, original paths and content (case-insensitive).
+pub fn list(query: Option<&str>, item_type: Option<&str>) -> Result<Vec<ArchivedItem>, String> {
+    let dir = archive_dir();
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let query = query
+        .map(str::to_lowercase)
+        .filter(|q| !q.trim().is_empty());
+    let mut items = Vec::new();
+
+    for entry in fs::read_dir(&dir).map_err(|e| e.to_string())? {
+        let path = entry.map_err(|e| e.to_string())?.path();
+        let Some(name) = path.file_name().and_then(|s| s.to_str()) else {
+            continue;
+        };
+        if !name.ends_with(".md") || name.starts_with('.') {
+            continue;
+        }
+
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(err) => {
+                tracing::warn!("Skipping archived file {:?}: {}", path, err);
+                continue;
+            }
+        };
+        let (fm, _, _) = frontmatter::parse_frontmatter(&content);
+        let item = describe(name, &fm);
+
+        if item_type.is_some_and(|t| t != item.item_type) {
+            continue;
+        }
+        if let Some(q) = &query {
+            let haystacks = [&item.title, &item.id, &item.original_path, &content];
+            if !haystacks.iter().any(|h| h.to_lowercase().contains(q)) {
+                continue;
+            }
+        }
+
+        items.push(item);
+    }
+
+    items.sort_by(|a, b| {
+        b.archived_at
+            .cmp(&a.archived_at)
+            .then_with(|| a.name.cmp(&b.name))
+    });
+
+    Ok(items)
+}
+
+/// An archived item with its content (archive metadata stripped).
+pub fn get(name: &str) -> Result<ArchivedItemWithContent, String> {
+    let path = item_path(name)?;
+    let content = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    let (mut fm, body, _) = frontmatter::parse_frontmatter(&content);
+    let item = describe(name, &fm);
+
+    Ok(ArchivedItemWithContent {
+        item,
+        content: original_content(&content, &mut fm, &body)?,
+    })
+}
+
+/// Move an archived item back to its original path.
+pub fn restore(name: &str) -> Result<ArchivedItem, String> {
+    let path = item_path(name)?;
+    let content = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    let (mut fm, body, _) = frontmatter::parse_frontmatter(&content);
+    let item = describe(name, &fm);
+
+    // The origin comes from frontmatter, which anyone can edit
+    if !is_restorable(&item.original_path) {
+        return Err(format!("Invalid original path: {}", item.original_path));
+    }
+
+    let target = config::data_dir().join(&item.original_path);
+    if target.exists() {
+        return Err(format!(
+            "A file already exists at {}; move or rename it first",
+            item.original_path
+        ));
+    }
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+
+    let restored = original_content(&content, &mut fm, &body)?;
+    filesystem::atomic_write(&target, restored.as_bytes())?;
+    fs::remove_file(&path).map_err(|e| e.to_string())?;
+
+    Ok(item)
+}
+
+/// Whether `path` is a markdown file in a folder archived items come from:
+/// `notes/`, `projects/<id>/notes/`, `projects/<id>/tasks/`, `daily/` or `views/`.
+fn is_restorable(path: &str) -> bool {
+    let parts: Vec<&str> = path.split('/').collect();
+    if !path.ends_with(".md") || parts.iter().any(|p| p.is_empty() || p.starts_with('.')) {
+        return false;
+    }
+    match parts.as_slice() {
+        ["notes", "assets", ..] => false,
+        ["notes", _, ..] | ["daily", _] | ["views", _] => true,
+        ["projects", _, "notes" | "tasks", _, ..] => true,
+        _ => false,
+    }
+}
+
+/// Permanently delete an archived item.
+pub fn purge(name: &str) -> Result<(), String> {
+    let path = item_path(name)?;
+    fs::remove_file(&path).map_err(|e| e.to_string())
+}
+
+/// File content without the archive metadata (no frontmatter if that was all there was).
+fn original_content(content: &str, fm: &mut Mapping, body: &str) -> Result<String, String> {
+    fm.remove(Value::from(ARCHIVED_FROM));
+    fm.remove(Value::from(ARCHIVED_AT));
+    if fm.is_empty() {
+        Ok(body.trim_start().to_string())
+    } else {
+        frontmatter::rewrite(content, fm, body)
+    }
+}
+
+fn item_path(name: &str) -> Result<PathBuf, String> {
+    // Names are plain file names; anything else can't be in the (flat) archive
+    let valid = !name.is_empty()
+        && !name.starts_with('.')
+        && !name.contains(['/', '\\'])
+        && name.ends_with(".md");
+    let path = archive_dir().join(name);
+    if !valid || !path.exists() {
+        return Err(format!("Archived item not found: {}", name));
+    }
+    Ok(path)
+}
+
+fn describe(name: &str, fm: &Mapping) -> ArchivedItem {
+    let recorded = frontmatter::get_str(fm, ARCHIVED_FROM);
+    let original_path = recorded.clone().unwrap_or_else(|| guess_origin(name, fm));
+
+    // The archive name may carry a prefix or collision suffix; the original doesn't
+    let stem = original_path
+        .rsplit('/')
+        .next()
+        .and_then(|file| file.strip_suffix(".md"))
+        .unwrap_or(name);
+    let item_type = frontmatter::get_str(fm, "type")
+        .unwrap_or_else(|| index::detect_doc_type(&original_path).to_string());
+    let project_id = frontmatter::get_str(fm, "project_id").or_else(|| {
+        let mut parts = original_path.split('/');
+        match (parts.next(), parts.next()) {
+            (Some("projects"), Some(id)) => Some(id.to_string()),
+            _ => None,
+        }
+    });
+
+    ArchivedItem {
+        name: name.to_string(),
+        id: frontmatter::get_str(fm, "id")
+            .unwrap_or_else(|| frontmatter::derive_id_from_path(Path::new(&original_path))),
+        title: frontmatter::get_str_or(fm, "title", stem),
+        item_type,
+        project_id,
+        original_path,
+        recorded: recorded.is_some(),
+        archived_at: frontmatter::get_str(fm, ARCHIVED_AT),
+    }
+}
+
+/// Best guess at where an item archived before origins were recorded came from,
+/// based on the naming used when archiving: `view-{id}`, `{project}-{file}`, `{file}`.
+fn guess_origin(name: &str, fm: &Mapping) -> String {
+    let doc_type = frontmatter::get_str(fm, "type");
+
+    if let Some(view) = name.strip_prefix("view-") {
+        if doc_type.as_deref().is_none_or(|t| t == "view") {
+            return format!("views/{}", view);
+        }
+    }
+
+    if doc_type.as_deref() == Some("daily") {
+        return format!("daily/{}", name);
+    }
+
+    if let Some(project_id) = frontmatter::get_str(fm, "project_id") {
+        if let Some(file) = name.strip_prefix(&format!("{}-", project_id)) {
+            let folder = if doc_type.as_deref() == Some("task") {
+                "tasks"
+            } else {
+                "notes"
+            };
+            return format!("projects/{}/{}/{}", project_id, folder, file);
+        }
+    }
+
+    format!("notes/{}", name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fm(yaml: &str) -> Mapping {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    #[test]
+    fn test_guess_origin() {
+        assert_eq!(
+            guess_origin(
+                "ops-task-1.md",
+                &fm("id: ops-task-1\ntype: task\nproject_id: ops")
+            ),
+            "projects/ops/tasks/task-1.md"
+        );
+        assert_eq!(
+            guess_origin("ops-20260101-120000.md", &fm("type: note\nproject_id: ops")),
+            "projects/ops/notes/20260101-120000.md"
+        );
+        assert_eq!(
+            guess_origin("view-open-tasks.md", &fm("type: view")),
+            "views/open-tasks.md"
+        );
+        assert_eq!(
+            guess_origin("2026-01-01.md", &fm("type: daily")),
+            "daily/2026-01-01.md"
+        );
+        assert_eq!(guess_origin("pg.md", &Mapping::new()), "notes/pg.md");
+    }
+
+    #[test]
+    fn test_is_restorable() {
+        assert!(is_restorable("notes/guides/pg.md"));
+        assert!(is_restorable("projects/ops/tasks/task-1.md"));
+        assert!(is_restorable("daily/2026-01-01.md"));
+        assert!(is_restorable("views/open.md"));
+
+        assert!(!is_restorable("notes/pg.txt"));
+        assert!(!is_restorable("notes/../.git/config.md"));
+        assert!(!is_restorable(".git/hooks/pre-commit.md"));
+        assert!(!is_restorable("notes/assets/x.md"));
+        assert!(!is_restorable("archive/pg.md"));
+        assert!(!is_restorable("templates/note.md"));
+        assert!(!is_restorable("projects/ops/index.md"));
+        assert!(!is_restorable("projects/ops/assets/x.md"));
+    }
+
+    #[test]
+    fn test_archive_keeps_invalid_frontmatter() {
+        let content = "---\ntitle: [unclosed\n---\n\nBody\n";
+        let origin = fm("archived_from: notes/broken.md\narchived_at: 2026-01-01T00:00:00Z");
+
+        let archived = with_origin(content, origin).unwrap();
+        let (mut parsed, body, _) = frontmatter::parse_frontmatter(&archived);
+        assert_eq!(
+            describe("broken.md", &parsed).original_path,
+            "notes/broken.md"
+        );
+        assert_eq!(
+            original_content(&archived, &mut parsed, &body).unwrap(),
+            content
+        );
+    }
+}
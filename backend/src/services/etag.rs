@@ -0,0 +1,49 @@
+use git2::{ObjectType, Oid};
+
+/// ETag of a file's content: its git blob hash (as `git hash-object` prints
+/// it), quoted. It changes with any edit, including ones made outside Ironpad.
+pub fn of(content: &[u8]) -> String {
+    Oid::hash_object(ObjectType::Blob, content)
+        .map(|oid| format!("\"{}\"", oid))
+        .unwrap_or_default()
+}
+
+/// Check a write's `If-Match` header against the file's current content.
+/// Without the header the write is unconditional; `*` matches any version.
+pub fn check(if_match: Option<&str>, current: &[u8]) -> Result<(), String> {
+    let Some(if_match) = if_match else {
+        return Ok(());
+    };
+
+    let etag = of(current);
+    let matches = if_match.split(',').map(str::trim).any(|tag| {
+        tag == "*" || tag.trim_start_matches("W/").trim_matches('"') == etag.trim_matches('"')
+    });
+    if !matches {
+        return Err(format!(
+            "Conflict: the file has changed since it was read (now {})",
+            etag
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check() {
+        let etag = of(b"hello\n");
+        // Same as `printf 'hello\n' | git hash-object --stdin`
+        assert_eq!(etag, "\"ce013625030ba8dba906f756967f9e9ca394464a\"");
+
+        assert!(check(None, b"anything").is_ok());
+        assert!(check(Some(&etag), b"hello\n").is_ok());
+        assert!(check(Some("*"), b"hello\n").is_ok());
+        assert!(check(Some(&format!("\"x\", W/{}", etag)), b"hello\n").is_ok());
+        assert!(check(Some(&etag), b"hello again\n")
+            .unwrap_err()
+            .starts_with("Conflict"));
+    }
+}
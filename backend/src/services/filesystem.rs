@@ -1,12 +1,19 @@
 use std::fs;
 use std::io::Write;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use serde_yaml::Value;
 use walkdir::WalkDir;
 
-use crate::models::note::{Note, NoteSummary};
+use crate::models::note::{NewNote, Note, NoteMetaUpdate, NoteSummary};
+use crate::services::archive;
+use crate::services::etag;
+use crate::services::folders;
 use crate::services::frontmatter;
+use crate::services::index;
+use crate::services::rename;
+use crate::services::schemas::{self, WriteError};
+use crate::services::templates::{self, TemplateContext};
 
 use crate::config;
 
@@ -52,42 +59,37 @@ fn is_ignored(path: &Path) -> bool {
 }
 
 fn is_note_file(path: &Path) -> bool {
-    let path_str = path.to_string_lossy();
-
-    // data/notes/**/*.md (handles both forward and back slashes)
-    if path_str.contains("notes") && !path_str.contains("archive") {
-        return true;
-    }
-
-    // data/projects/*/index.md
-    if path_str.contains("projects")
-        && path.file_name().and_then(|s| s.to_str()) == Some("index.md")
-    {
-        return true;
-    }
-
-    // Root-level files (index.md, inbox.md) - parent is the data dir
-    if let Some(parent) = path.parent() {
-        if parent == config::data_dir() {
-            return true;
-        }
-    }
-
-    false
+    let Ok(rel) = path.strip_prefix(config::data_dir()) else {
+        return false;
+    };
+    let parts: Vec<&str> = rel.iter().filter_map(|part| part.to_str()).collect();
+
+    matches!(
+        parts.as_slice(),
+        // data/notes/**/*.md and data/projects/*/notes/**/*.md
+        ["notes", _, ..]
+            | ["projects", _, "notes", _, ..]
+            // data/views/*.md (saved searches)
+            | ["views", _]
+            // data/projects/*/index.md
+            | ["projects", _, "index.md"]
+            // Root-level files (index.md, inbox.md)
+            | [_]
+    )
 }
 
-fn parse_note_summary(path: &Path) -> Result<NoteSummary, String> {
+pub fn parse_note_summary(path: &Path) -> Result<NoteSummary, String> {
     let content = fs::read_to_string(path).map_err(|e| e.to_string())?;
     let (fm, _body, _has_fm) = frontmatter::parse_frontmatter(&content);
 
     let id = fm
-        .get(&Value::from("id"))
+        .get(Value::from("id"))
         .and_then(|v| v.as_str())
         .map(String::from)
         .unwrap_or_else(|| frontmatter::derive_id_from_path(path));
 
     let title = fm
-        .get(&Value::from("title"))
+        .get(Value::from("title"))
         .and_then(|v| v.as_str())
         .map(String::from)
         .unwrap_or_else(|| {
@@ -98,13 +100,13 @@ fn parse_note_summary(path: &Path) -> Result<NoteSummary, String> {
         });
 
     let note_type = fm
-        .get(&Value::from("type"))
+        .get(Value::from("type"))
         .and_then(|v| v.as_str())
         .unwrap_or("note")
         .to_string();
 
     let updated = fm
-        .get(&Value::from("updated"))
+        .get(Value::from("updated"))
         .and_then(|v| v.as_str())
         .map(String::from);
 
@@ -131,74 +133,91 @@ pub fn normalize_path(path: &Path) -> String {
         .to_string()
 }
 
+/// Find a note's file by ID using the index registry.
+fn find_note_path(note_id: &str) -> Option<PathBuf> {
+    index::find_path(note_id, is_note_file)
+}
+
 /// Read a full note by deterministic ID.
 pub fn read_note_by_id(note_id: &str) -> Result<Note, String> {
-    let root = config::data_dir();
+    let path = find_note_path(note_id).ok_or_else(|| format!("Note not found: {}", note_id))?;
 
-    for entry in WalkDir::new(root)
-        .into_iter()
-        .filter_entry(|e| !is_ignored(e.path()))
-        .filter_map(Result::ok)
-    {
-        let path = entry.path();
-
-        if path.extension().and_then(|s| s.to_str()) != Some("md") {
-            continue;
-        }
-
-        if !is_note_file(path) {
-            continue;
-        }
-
-        let content = fs::read_to_string(path).map_err(|e| e.to_string())?;
-        let (fm, body, _has_fm) = frontmatter::parse_frontmatter(&content);
-
-        let derived_id = fm
-            .get(&Value::from("id"))
-            .and_then(|v| v.as_str())
-            .map(String::from)
-            .unwrap_or_else(|| frontmatter::derive_id_from_path(path));
-
-        if derived_id != note_id {
-            continue;
-        }
+    let content = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    let (fm, body, _has_fm) = frontmatter::parse_frontmatter(&content);
 
-        let note_type = fm
-            .get(&Value::from("type"))
-            .and_then(|v| v.as_str())
-            .unwrap_or("note")
-            .to_string();
-
-        return Ok(Note {
-            id: derived_id,
-            path: normalize_path(path),
-            note_type,
-            frontmatter: fm,
-            content: body.trim_start().to_string(),
-        });
-    }
+    let note_type = fm
+        .get(Value::from("type"))
+        .and_then(|v| v.as_str())
+        .unwrap_or("note")
+        .to_string();
 
-    Err(format!("Note not found: {}", note_id))
+    Ok(Note {
+        id: note_id.to_string(),
+        path: normalize_path(&path),
+        note_type,
+        frontmatter: fm,
+        content: body.trim_start().to_string(),
+        cursor: None,
+        etag: etag::of(content.as_bytes()),
+    })
 }
 
-/// Create a new empty note in data/notes/.
-pub fn create_note() -> Result<Note, String> {
+/// Create a new note in data/notes/ (or a folder below it).
+/// Titled notes are named after a slug of the title, others after the time;
+/// either gets a `-2`, `-3`, ... suffix if the filename or id is taken.
+pub fn create_note(new: &NewNote) -> Result<Note, WriteError> {
     use chrono::Utc;
 
-    let dir = config::data_dir().join("notes");
+    let mut dir = config::data_dir().join("notes");
+    if let Some(folder) = new.folder.as_deref().filter(|f| !f.trim().is_empty()) {
+        dir = dir.join(folders::validate_folder(folder)?);
+    }
     fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
 
-    let filename = format!("{}.md", Utc::now().format("%Y%m%d-%H%M%S"));
-    let path = dir.join(&filename);
+    let title = new
+        .title
+        .as_deref()
+        .map(str::trim)
+        .filter(|t| !t.is_empty());
+    let stem = title
+        .map(slugify)
+        .filter(|slug| !slug.is_empty())
+        .unwrap_or_else(|| Utc::now().format("%Y%m%d-%H%M%S").to_string());
+    let path = unique_note_path(&dir, &stem, None);
+
+    let ctx = TemplateContext {
+        title: title.unwrap_or("Untitled").to_string(),
+        date: Utc::now().date_naive(),
+        project: None,
+    };
+    let rendered = templates::for_new("note", new.template.as_deref(), &ctx)?;
 
-    let fm = frontmatter::generate_frontmatter(&path, "note");
-    let content = frontmatter::serialize_frontmatter(&fm, "")?;
+    let mut fm = frontmatter::generate_frontmatter(&path, "note");
+    if let Some(rendered) = &rendered {
+        for (key, value) in &rendered.frontmatter {
+            fm.insert(key.clone(), value.clone());
+        }
+    }
+    if let Some(title) = title {
+        fm.insert(Value::from("title"), Value::from(title));
+    }
+    if !new.tags.is_empty() {
+        fm.insert(Value::from("tags"), Value::from(new.tags.clone()));
+    }
+    schemas::check_new(&path, &mut fm)?;
+
+    let (body, cursor) = match (&new.content, rendered) {
+        (Some(content), _) => (content.clone(), None),
+        (None, Some(rendered)) => (rendered.body, rendered.cursor),
+        (None, None) => (String::new(), None),
+    };
+    let content = frontmatter::serialize_frontmatter(&fm, &body)?;
 
     // Atomic write: write to temp file, then rename
     atomic_write(&path, content.as_bytes())?;
 
     let id = fm
-        .get(&Value::from("id"))
+        .get(Value::from("id"))
         .and_then(|v| v.as_str())
         .unwrap_or("")
         .to_string();
@@ -208,120 +227,151 @@ pub fn create_note() -> Result<Note, String> {
         path: normalize_path(&path),
         note_type: "note".to_string(),
         frontmatter: fm,
-        content: String::new(),
+        content: body.trim_start().to_string(),
+        cursor,
+        etag: etag::of(content.as_bytes()),
     })
 }
 
-/// Update an existing note by ID with full markdown payload.
-/// Handles notes with or without existing frontmatter.
-/// Preserves user-defined fields, updates backend-owned fields.
-pub fn update_note(note_id: &str, new_content: &str) -> Result<Note, String> {
-    let root = config::data_dir();
-
-    for entry in WalkDir::new(root)
-        .into_iter()
-        .filter_entry(|e| !is_ignored(e.path()))
-        .filter_map(Result::ok)
-    {
-        let path = entry.path();
-
-        if path.extension().and_then(|s| s.to_str()) != Some("md") {
-            continue;
-        }
-
-        if !is_note_file(path) {
-            continue;
+/// Lowercase filename slug: letters and digits, other runs become `-`.
+pub fn slugify(title: &str) -> String {
+    let mut slug = String::new();
+    for c in title.to_lowercase().chars() {
+        if c.is_alphanumeric() {
+            slug.push(c);
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
         }
+    }
+    slug.trim_end_matches('-').to_string()
+}
 
-        let content = fs::read_to_string(path).map_err(|e| e.to_string())?;
-        let (mut fm, _old_body, has_fm) = frontmatter::parse_frontmatter(&content);
-
-        let derived_id = fm
-            .get(&Value::from("id"))
-            .and_then(|v| v.as_str())
-            .map(String::from)
-            .unwrap_or_else(|| frontmatter::derive_id_from_path(path));
-
-        if derived_id != note_id {
-            continue;
+/// `dir/{stem}.md`, suffixed until neither the file nor its derived id is
+/// taken by another document (`current` is the note being renamed, if any).
+fn unique_note_path(dir: &Path, stem: &str, current: Option<&Path>) -> PathBuf {
+    let available = |path: &Path| {
+        if current == Some(path) {
+            return true;
         }
+        let id = frontmatter::derive_id_from_path(path);
+        let rel = normalize_path(path);
+        !path.exists() && !index::read(|idx| idx.docs_with_id(&id).any(|doc| doc.path != rel))
+    };
 
-        // Ensure frontmatter has all required fields
-        // This handles files without frontmatter or with incomplete frontmatter
-        if !has_fm || !frontmatter::is_frontmatter_complete(&fm) {
-            frontmatter::ensure_frontmatter(&mut fm, path);
-        } else {
-            // Just update the timestamp
-            frontmatter::update_frontmatter(&mut fm);
-        }
+    let mut path = dir.join(format!("{}.md", stem));
+    let mut n = 2;
+    while !available(&path) {
+        path = dir.join(format!("{}-{}.md", stem, n));
+        n += 1;
+    }
+    path
+}
 
-        // Rebuild file content
-        let rebuilt = frontmatter::serialize_frontmatter(&fm, new_content.trim_start())?;
+/// Update an existing note by ID with full markdown payload.
+/// Handles notes with or without existing frontmatter.
+/// Preserves user-defined fields, updates backend-owned fields.
+/// With `if_match`, fails with a conflict if the file changed since it was read.
+pub fn update_note(
+    note_id: &str,
+    new_content: &str,
+    if_match: Option<&str>,
+) -> Result<Note, String> {
+    let path = find_note_path(note_id).ok_or_else(|| format!("Note not found: {}", note_id))?;
+
+    let content = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    etag::check(if_match, content.as_bytes())?;
+    let (mut fm, _old_body, has_fm) = frontmatter::parse_frontmatter(&content);
+
+    // Ensure frontmatter has all required fields
+    // This handles files without frontmatter or with incomplete frontmatter
+    if !has_fm || !frontmatter::is_frontmatter_complete(&fm) {
+        frontmatter::ensure_frontmatter(&mut fm, &path);
+    } else {
+        // Just update the timestamp
+        frontmatter::update_frontmatter(&mut fm);
+    }
 
-        // Atomic write
-        atomic_write(path, rebuilt.as_bytes())?;
+    // Rebuild file content
+    let rebuilt = frontmatter::rewrite(&content, &fm, new_content.trim_start())?;
 
-        let note_type = fm
-            .get(&Value::from("type"))
-            .and_then(|v| v.as_str())
-            .unwrap_or("note")
-            .to_string();
+    // Atomic write
+    atomic_write(&path, rebuilt.as_bytes())?;
 
-        return Ok(Note {
-            id: derived_id,
-            path: normalize_path(path),
-            note_type,
-            frontmatter: fm,
-            content: new_content.to_string(),
-        });
-    }
+    let note_type = fm
+        .get(Value::from("type"))
+        .and_then(|v| v.as_str())
+        .unwrap_or("note")
+        .to_string();
 
-    Err(format!("Note not found: {}", note_id))
+    Ok(Note {
+        id: note_id.to_string(),
+        path: normalize_path(&path),
+        note_type,
+        frontmatter: fm,
+        content: new_content.to_string(),
+        cursor: None,
+        etag: etag::of(rebuilt.as_bytes()),
+    })
 }
 
-/// Archive a note by ID (move to data/archive/).
-pub fn archive_note(note_id: &str) -> Result<(), String> {
-    let root = config::data_dir();
-    let archive_dir = config::data_dir().join("archive");
-
-    fs::create_dir_all(&archive_dir).map_err(|e| e.to_string())?;
+/// Update a note's title and tags. With `rename_file`, a changed title also
+/// renames the file to match (keeping the id and rewriting links to it).
+pub fn update_note_meta(
+    note_id: &str,
+    meta: &NoteMetaUpdate,
+    if_match: Option<&str>,
+) -> Result<Note, WriteError> {
+    let path = find_note_path(note_id).ok_or_else(|| format!("Note not found: {}", note_id))?;
+
+    let content = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    etag::check(if_match, content.as_bytes())?;
+    let (mut fm, body, has_fm) = frontmatter::parse_frontmatter(&content);
+
+    if !has_fm || !frontmatter::is_frontmatter_complete(&fm) {
+        frontmatter::ensure_frontmatter(&mut fm, &path);
+    } else {
+        frontmatter::update_frontmatter(&mut fm);
+    }
 
-    for entry in WalkDir::new(root)
-        .into_iter()
-        .filter_entry(|e| !is_ignored(e.path()))
-        .filter_map(Result::ok)
-    {
-        let path = entry.path();
+    let title = meta
+        .title
+        .as_deref()
+        .map(str::trim)
+        .filter(|t| !t.is_empty());
+    if let Some(title) = title {
+        fm.insert(Value::from("title"), Value::from(title));
+    }
+    if let Some(tags) = &meta.tags {
+        fm.insert(Value::from("tags"), Value::from(tags.clone()));
+    }
+    schemas::check(&path, &fm)?;
 
-        if path.extension().and_then(|s| s.to_str()) != Some("md") {
-            continue;
-        }
+    let rebuilt = frontmatter::rewrite(&content, &fm, &body)?;
+    atomic_write(&path, rebuilt.as_bytes())?;
 
-        if !is_note_file(path) {
-            continue;
+    let slug = title.map(slugify).filter(|slug| !slug.is_empty());
+    if let (true, Some(slug)) = (meta.rename_file, slug) {
+        let dir = path.parent().ok_or("Invalid path")?;
+        let target = unique_note_path(dir, &slug, Some(&path));
+        if target != path {
+            rename::move_note(&path, &normalize_path(&target), None, None)?;
         }
+    }
 
-        let content = fs::read_to_string(path).map_err(|e| e.to_string())?;
-        let (fm, _, _) = frontmatter::parse_frontmatter(&content);
-
-        let derived_id = fm
-            .get(&Value::from("id"))
-            .and_then(|v| v.as_str())
-            .map(String::from)
-            .unwrap_or_else(|| frontmatter::derive_id_from_path(path));
-
-        if derived_id != note_id {
-            continue;
-        }
+    Ok(read_note_by_id(note_id)?)
+}
 
-        let filename = path.file_name().ok_or("Invalid filename")?;
-        let target = archive_dir.join(filename);
+/// Archive a note by ID (move to data/archive/).
+pub fn archive_note(note_id: &str) -> Result<(), String> {
+    let path = find_note_path(note_id).ok_or_else(|| format!("Note not found: {}", note_id))?;
 
-        fs::rename(path, target).map_err(|e| e.to_string())?;
-        return Ok(());
-    }
+    let filename = path
+        .file_name()
+        .and_then(|s| s.to_str())
+        .ok_or("Invalid filename")?;
 
-    Err(format!("Note not found: {}", note_id))
+    archive::archive_file(&path, filename)?;
+    Ok(())
 }
 
 /// Atomic write: write to temp file, then rename.
@@ -348,5 +398,20 @@ pub fn atomic_write(path: &Path, contents: &[u8]) -> Result<(), String> {
     // Rename temp file to target (atomic on most filesystems)
     fs::rename(&temp_path, path).map_err(|e| e.to_string())?;
 
+    // Keep the search index in sync with our own writes
+    index::index_content(path, &String::from_utf8_lossy(contents));
+
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slugify() {
+        assert_eq!(slugify("Weekly Sync: Q3!"), "weekly-sync-q3");
+        assert_eq!(slugify("  --Café  notes--"), "café-notes");
+        assert_eq!(slugify("!!!"), "");
+    }
+}
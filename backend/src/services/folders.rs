@@ -0,0 +1,293 @@
+use std::cmp::Ordering;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::config;
+use crate::models::note::NoteSummary;
+use crate::services::archive;
+use crate::services::filesystem;
+use crate::services::rename::{self, FolderMoveResult};
+
+/// Per-folder file holding the manual order of its entries, as a YAML list
+/// of sub-folder and note file names
+pub const ORDER_FILE: &str = ".order.yml";
+
+/// A folder under `data/notes/` with its contents
+#[derive(Debug, Serialize)]
+pub struct FolderNode {
+    pub name: String,
+    /// Data-relative path, e.g. `notes/guides`
+    pub path: String,
+    pub folders: Vec<FolderNode>,
+    pub notes: Vec<NoteSummary>,
+}
+
+fn notes_dir() -> PathBuf {
+    config::data_dir().join("notes")
+}
+
+/// Check a folder path relative to `notes/` and return it normalised
+/// (forward slashes, no leading or trailing slash).
+pub fn validate_folder(path: &str) -> Result<String, String> {
+    let path = path.trim().trim_matches('/');
+    let valid = !path.is_empty()
+        && !path.contains('\\')
+        && path.split('/').all(|part| {
+            !part.is_empty() && !part.starts_with('.') && part != "assets" && part == part.trim()
+        });
+    if !valid {
+        return Err(format!("Invalid folder path: {}", path));
+    }
+    Ok(path.to_string())
+}
+
+/// Result of deleting a folder
+#[derive(Debug, Serialize)]
+pub struct DeletedFolder {
+    pub path: String,
+    /// Archive names of the notes that were inside
+    pub archived: Vec<String>,
+}
+
+/// Create `notes/{path}` (and any missing parents).
+pub fn create_folder(path: &str) -> Result<FolderNode, String> {
+    let path = validate_folder(path)?;
+    let abs = notes_dir().join(&path);
+    if abs.exists() {
+        return Err(format!("Folder already exists: notes/{}", path));
+    }
+    fs::create_dir_all(&abs).map_err(|e| e.to_string())?;
+    read_folder(&abs)
+}
+
+/// Delete `notes/{path}`. Notes inside are archived, so they can be restored
+/// to where they were; a non-empty folder needs `recursive`.
+pub fn delete_folder(path: &str, recursive: bool) -> Result<DeletedFolder, String> {
+    let path = validate_folder(path)?;
+    let abs = notes_dir().join(&path);
+    if !abs.is_dir() {
+        return Err(format!("Folder not found: notes/{}", path));
+    }
+
+    let mut notes = Vec::new();
+    for entry in walkdir::WalkDir::new(&abs).min_depth(1) {
+        let entry = entry.map_err(|e| e.to_string())?;
+        if entry.file_type().is_dir() {
+            continue;
+        }
+        let name = entry.file_name().to_string_lossy();
+        if name.ends_with(".md") {
+            notes.push(entry.into_path());
+        } else if name != ORDER_FILE {
+            return Err(format!(
+                "Invalid folder: notes/{} contains files other than notes ({})",
+                path,
+                filesystem::normalize_path(entry.path())
+            ));
+        }
+    }
+
+    if !notes.is_empty() && !recursive {
+        return Err(format!(
+            "Invalid request: notes/{} is not empty (pass recursive=true to archive its notes)",
+            path
+        ));
+    }
+
+    // Read everything before moving anything, so a bad file can't leave the folder half archived
+    let prepared = notes
+        .into_iter()
+        .map(|note| archive::archived_content(&note).map(|content| (note, content)))
+        .collect::<Result<Vec<_>, String>>()?;
+
+    let mut archived = Vec::new();
+    for (note, content) in prepared {
+        let name = note
+            .file_name()
+            .and_then(|s| s.to_str())
+            .ok_or("Invalid filename")?;
+        archived.push(archive::archive_prepared(&note, name, &content)?);
+    }
+
+    fs::remove_dir_all(&abs).map_err(|e| e.to_string())?;
+    Ok(DeletedFolder {
+        path: format!("notes/{}", path),
+        archived,
+    })
+}
+
+/// Move or rename `notes/{from}` to `notes/{to}` (paths relative to `notes/`),
+/// keeping a renamed folder's place in its parent's manual order.
+pub fn move_folder(
+    from: &str,
+    to: &str,
+    message: Option<&str>,
+) -> Result<FolderMoveResult, String> {
+    let (from, to) = (validate_folder(from)?, validate_folder(to)?);
+
+    // A renamed folder keeps its place; the order file goes in the move's commit
+    let mut extra = Vec::new();
+    let (from_abs, to_abs) = (notes_dir().join(&from), notes_dir().join(&to));
+    if from_abs.parent() == to_abs.parent() {
+        if let (Some(dir), Some(old), Some(new)) = (
+            to_abs.parent(),
+            file_name_of(&from_abs),
+            file_name_of(&to_abs),
+        ) {
+            let mut order = read_order(dir);
+            if let Some(entry) = order.iter_mut().find(|entry| *entry == old) {
+                *entry = new.to_string();
+                let yaml = serde_yaml::to_string(&order).map_err(|e| e.to_string())?;
+                extra.push((filesystem::normalize_path(&dir.join(ORDER_FILE)), yaml));
+            }
+        }
+    }
+
+    rename::move_folder(
+        &format!("notes/{}", from),
+        &format!("notes/{}", to),
+        &extra,
+        message,
+    )
+}
+
+/// Set the manual order of `notes/{path}` (empty for `notes/` itself).
+/// Entries not listed keep the default order after the listed ones.
+/// Returns the reordered folder.
+pub fn set_order(path: &str, order: &[String]) -> Result<FolderNode, String> {
+    let dir = if path.trim().trim_matches('/').is_empty() {
+        notes_dir()
+    } else {
+        notes_dir().join(validate_folder(path)?)
+    };
+    if !dir.is_dir() {
+        return Err(format!("Folder not found: notes/{}", path));
+    }
+
+    if let Some(name) = order.iter().find(|name| {
+        name.contains(['/', '\\']) || name.starts_with('.') || !dir.join(name).exists()
+    }) {
+        return Err(format!("Invalid order: no entry named {}", name));
+    }
+
+    let order_path = dir.join(ORDER_FILE);
+    if order.is_empty() {
+        if order_path.exists() {
+            fs::remove_file(&order_path).map_err(|e| e.to_string())?;
+        }
+    } else {
+        let yaml = serde_yaml::to_string(order).map_err(|e| e.to_string())?;
+        filesystem::atomic_write(&order_path, yaml.as_bytes())?;
+    }
+
+    read_folder(&dir)
+}
+
+/// The `notes/` folder as a tree: manually ordered entries first, then
+/// folders by name and notes by title.
+pub fn tree() -> Result<FolderNode, String> {
+    let root = notes_dir();
+    if !root.exists() {
+        return Ok(FolderNode {
+            name: "notes".to_string(),
+            path: "notes".to_string(),
+            folders: Vec::new(),
+            notes: Vec::new(),
+        });
+    }
+    read_folder(&root)
+}
+
+fn read_folder(dir: &Path) -> Result<FolderNode, String> {
+    let order = read_order(dir);
+    let mut folders = Vec::new();
+    let mut notes = Vec::new();
+
+    for entry in fs::read_dir(dir).map_err(|e| e.to_string())? {
+        let path = entry.map_err(|e| e.to_string())?.path();
+        let Some(name) = path.file_name().and_then(|s| s.to_str()) else {
+            continue;
+        };
+        if name.starts_with('.') || name == "assets" {
+            continue;
+        }
+
+        if path.is_dir() {
+            folders.push(read_folder(&path)?);
+        } else if name.ends_with(".md") {
+            match filesystem::parse_note_summary(&path) {
+                Ok(note) => notes.push(note),
+                Err(err) => tracing::warn!("Skipping file {:?}: {}", path, err),
+            }
+        }
+    }
+
+    folders.sort_by(|a, b| {
+        by_order(&order, &a.name, &b.name)
+            .then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+    });
+    notes.sort_by(|a, b| {
+        by_order(&order, file_name(&a.path), file_name(&b.path))
+            .then_with(|| a.title.to_lowercase().cmp(&b.title.to_lowercase()))
+    });
+
+    Ok(FolderNode {
+        name: dir
+            .file_name()
+            .and_then(|s| s.to_str())
+            .unwrap_or_default()
+            .to_string(),
+        path: filesystem::normalize_path(dir),
+        folders,
+        notes,
+    })
+}
+
+fn read_order(dir: &Path) -> Vec<String> {
+    let Ok(content) = fs::read_to_string(dir.join(ORDER_FILE)) else {
+        return Vec::new();
+    };
+    serde_yaml::from_str(&content).unwrap_or_else(|err| {
+        tracing::warn!("Ignoring invalid {} in {:?}: {}", ORDER_FILE, dir, err);
+        Vec::new()
+    })
+}
+
+fn file_name_of(path: &Path) -> Option<&str> {
+    path.file_name().and_then(|s| s.to_str())
+}
+
+fn file_name(path: &str) -> &str {
+    path.rsplit('/').next().unwrap_or(path)
+}
+
+/// Listed names come first, in list order
+fn by_order(order: &[String], a: &str, b: &str) -> Ordering {
+    let position = |name: &str| order.iter().position(|o| o == name).unwrap_or(usize::MAX);
+    position(a).cmp(&position(b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_folder() {
+        assert_eq!(validate_folder("/guides/db/").unwrap(), "guides/db");
+        assert!(validate_folder("").is_err());
+        assert!(validate_folder("guides/../..").is_err());
+        assert!(validate_folder("guides/.hidden").is_err());
+        assert!(validate_folder("guides/assets").is_err());
+        assert!(validate_folder("a\\b").is_err());
+    }
+
+    #[test]
+    fn test_by_order() {
+        let order = vec!["b.md".to_string(), "a.md".to_string()];
+        assert_eq!(by_order(&order, "b.md", "a.md"), Ordering::Less);
+        assert_eq!(by_order(&order, "a.md", "c.md"), Ordering::Less);
+        assert_eq!(by_order(&order, "c.md", "d.md"), Ordering::Equal);
+    }
+}
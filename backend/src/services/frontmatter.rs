@@ -2,9 +2,12 @@ use std::path::Path;
 
 use chrono::Utc;
 use serde_yaml::{Mapping, Value};
+use toml_edit::{Datetime, DocumentMut, Item, Table, TomlError, Value as TomlValue};
 
 /// Derive deterministic ID from file path.
 /// Matches filesystem ID logic: strips data directory prefix and folder name.
+/// Notes in sub-folders join the folders with `:` (`notes/guides/db.md` is
+/// `guides:db`), which is escaped inside names so no two paths share an id.
 pub fn derive_id_from_path(path: &Path) -> String {
     let path_str = path.to_string_lossy();
 
@@ -24,28 +27,154 @@ pub fn derive_id_from_path(path: &Path) -> String {
 
     // Drop top-level folder name (notes, projects, etc.) if we have multiple parts
     if parts.len() > 1 {
-        parts.remove(0);
+        let top = parts.remove(0);
+        // A `-` join would give `notes/a/b-c.md` and `notes/a-b/c.md` the same id
+        if top == "notes" {
+            return parts
+                .iter()
+                .map(|part| part.replace('%', "%25").replace(':', "%3A"))
+                .collect::<Vec<_>>()
+                .join(":");
+        }
     }
 
     parts.join("-")
 }
 
+/// Frontmatter syntax, chosen by the opening fence
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// `---`, closed by `---` or `...`
+    Yaml,
+    /// `+++`, as written by Hugo and Zola
+    Toml,
+    /// `;;;`
+    Json,
+}
+
+impl Format {
+    fn fence(self) -> &'static str {
+        match self {
+            Format::Yaml => "---",
+            Format::Toml => "+++",
+            Format::Json => ";;;",
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Format::Yaml => "YAML",
+            Format::Toml => "TOML",
+            Format::Json => "JSON",
+        }
+    }
+}
+
+/// A file's frontmatter and body, as read
+#[derive(Debug)]
+pub struct Parsed {
+    pub frontmatter: Mapping,
+    pub body: String,
+    /// None if the file has no frontmatter
+    pub format: Option<Format>,
+    /// Why the frontmatter couldn't be read; `frontmatter` is empty then
+    pub error: Option<String>,
+}
+
+/// A file cut at its frontmatter fences. Concatenated, the parts are the file.
+struct Fenced<'a> {
+    format: Format,
+    /// Opening fence line, newline included
+    open: &'a str,
+    text: &'a str,
+    /// Closing fence line, newline included unless the file ends there
+    close: &'a str,
+    body: &'a str,
+}
+
+/// Find the frontmatter block: the first line is a fence on its own and the
+/// block ends at the next line holding the same fence. A `---` inside a value
+/// or a horizontal rule after the block doesn't end it early.
+fn fence(content: &str) -> Option<Fenced<'_>> {
+    let open_len = content.find('\n')? + 1;
+    let open = &content[..open_len];
+    let format = match open.trim_end() {
+        "---" => Format::Yaml,
+        "+++" => Format::Toml,
+        ";;;" => Format::Json,
+        _ => return None,
+    };
+
+    let mut pos = open_len;
+    for line in content[open_len..].split_inclusive('\n') {
+        let fence = line.trim_end();
+        if fence == format.fence() || (format == Format::Yaml && fence == "...") {
+            return Some(Fenced {
+                format,
+                open,
+                text: &content[open_len..pos],
+                close: line,
+                body: &content[pos + line.len()..],
+            });
+        }
+        pos += line.len();
+    }
+    None
+}
+
+/// Parse frontmatter from file content. Malformed frontmatter is reported in
+/// `error` instead of failing the read.
+pub fn parse(content: &str) -> Parsed {
+    let Some(fenced) = fence(content) else {
+        return Parsed {
+            frontmatter: Mapping::new(),
+            body: content.to_string(),
+            format: None,
+            error: None,
+        };
+    };
+
+    let (frontmatter, error) = match read(fenced.format, fenced.text) {
+        Ok(map) => (map, None),
+        Err(err) => (Mapping::new(), Some(err)),
+    };
+    Parsed {
+        frontmatter,
+        body: fenced.body.to_string(),
+        format: Some(fenced.format),
+        error,
+    }
+}
+
 /// Parse frontmatter from file content.
 /// Returns (frontmatter mapping, body content, has_frontmatter flag).
+/// Frontmatter that fails to parse comes back empty (see `parse`).
 pub fn parse_frontmatter(content: &str) -> (Mapping, String, bool) {
-    if !content.starts_with("---") {
-        return (Mapping::new(), content.to_string(), false);
-    }
+    let parsed = parse(content);
+    (parsed.frontmatter, parsed.body, parsed.format.is_some())
+}
 
-    let mut parts = content.splitn(3, "---");
-    parts.next(); // empty before first ---
-    let yaml = parts.next().unwrap_or("");
-    let body = parts.next().unwrap_or("");
+/// The mapping in frontmatter text
+fn read(format: Format, text: &str) -> Result<Mapping, String> {
+    if text.trim().is_empty() {
+        return Ok(Mapping::new());
+    }
 
-    let fm: Value = serde_yaml::from_str(yaml).unwrap_or(Value::Null);
-    let map = fm.as_mapping().cloned().unwrap_or_default();
+    let error = |err: String| format!("{} error: {}", format.name(), err);
+    let value = match format {
+        Format::Yaml => serde_yaml::from_str(text).map_err(|e| error(e.to_string()))?,
+        Format::Toml => {
+            let doc: DocumentMut = text.parse().map_err(|e: TomlError| error(e.to_string()))?;
+            Value::Mapping(from_toml_table(doc.as_table()))
+        }
+        Format::Json => serde_json::from_str(text).map_err(|e| error(e.to_string()))?,
+    };
 
-    (map, body.to_string(), true)
+    match value {
+        Value::Mapping(map) => Ok(map),
+        Value::Null => Ok(Mapping::new()),
+        _ => Err(error("expected keys and values".to_string())),
+    }
 }
 
 /// Serialize frontmatter and body back to markdown string.
@@ -61,6 +190,287 @@ pub fn serialize_frontmatter(frontmatter: &Mapping, body: &str) -> Result<String
     Ok(content)
 }
 
+/// Serialize an edited file without disturbing what wasn't edited.
+/// `original` is the file as read; `frontmatter` and `body` are its new parts.
+///
+/// The frontmatter keeps its format. In YAML and TOML, top-level keys whose
+/// value is unchanged keep their exact text, so comments, quoting, flow
+/// sequences, blank lines and key order survive; changed keys are re-emitted
+/// in place, removed ones dropped and new ones appended. YAML that can't be
+/// split into top-level entries, and JSON, are re-serialized as a whole.
+/// Files without frontmatter get YAML (see `serialize_frontmatter`).
+///
+/// Fails if the original frontmatter doesn't parse: the caller read it as
+/// empty, and writing that back would lose it.
+pub fn rewrite(original: &str, frontmatter: &Mapping, body: &str) -> Result<String, String> {
+    let Some(fenced) = fence(original) else {
+        return serialize_frontmatter(frontmatter, body);
+    };
+    if let Err(err) = read(fenced.format, fenced.text) {
+        return Err(format!(
+            "Invalid frontmatter, fix it before saving ({})",
+            err
+        ));
+    }
+
+    let text = match fenced.format {
+        Format::Yaml => match edit_yaml(fenced.text, frontmatter)? {
+            Some(yaml) => yaml,
+            None => serde_yaml::to_string(frontmatter).map_err(|e| e.to_string())?,
+        },
+        Format::Toml => edit_toml(fenced.text, frontmatter)?,
+        Format::Json => {
+            let json = serde_json::to_string_pretty(frontmatter).map_err(|e| e.to_string())?;
+            format!("{}\n", json)
+        }
+    };
+
+    // Keep the spacing between the closing fence and the body
+    let body = body.trim_start();
+    let rest = fenced.body;
+    let mut close = fenced.close.to_string();
+    let mut gap = &rest[..rest.len() - rest.trim_start().len()];
+    if rest.is_empty() && !body.is_empty() {
+        // The file ended at the closing fence
+        if !close.ends_with('\n') {
+            close.push('\n');
+        }
+        gap = "\n";
+    }
+
+    Ok(format!("{}{}{}{}{}", fenced.open, text, close, gap, body))
+}
+
+/// Apply `new` to the YAML text `yaml`, entry by entry. None if the text
+/// can't be taken apart safely (anchors, complex keys, ...).
+fn edit_yaml(yaml: &str, new: &Mapping) -> Result<Option<String>, String> {
+    let old = match serde_yaml::from_str::<Value>(yaml) {
+        Ok(Value::Mapping(old)) => old,
+        Ok(Value::Null) => Mapping::new(),
+        _ => return Ok(None),
+    };
+
+    // Each entry's text must parse to exactly one key, and together to the whole mapping
+    let mut segments = Vec::new();
+    let mut seen = Mapping::new();
+    for (text, is_entry) in segments_of(yaml) {
+        let entry = if is_entry {
+            let Ok(entry) = serde_yaml::from_str::<Mapping>(text) else {
+                return Ok(None);
+            };
+            let mut entries = entry.into_iter();
+            let (Some((key, value)), None) = (entries.next(), entries.next()) else {
+                return Ok(None);
+            };
+            seen.insert(key.clone(), value.clone());
+            Some((key, value))
+        } else {
+            None
+        };
+        segments.push((text, entry));
+    }
+    if seen != old {
+        return Ok(None);
+    }
+
+    let mut out = String::with_capacity(yaml.len());
+    for (text, entry) in segments {
+        match entry {
+            None => out.push_str(text),
+            Some((key, value)) => match new.get(&key) {
+                None => {}
+                Some(new_value) if *new_value == value => out.push_str(text),
+                Some(new_value) => out.push_str(&emit(&key, new_value)?),
+            },
+        }
+    }
+
+    if !out.is_empty() && !out.ends_with('\n') {
+        out.push('\n');
+    }
+    for (key, value) in new {
+        if !old.contains_key(key) {
+            out.push_str(&emit(key, value)?);
+        }
+    }
+
+    Ok(Some(out))
+}
+
+/// `key: value` as serde_yaml writes it
+fn emit(key: &Value, value: &Value) -> Result<String, String> {
+    let mut entry = Mapping::new();
+    entry.insert(key.clone(), value.clone());
+    serde_yaml::to_string(&entry).map_err(|e| e.to_string())
+}
+
+/// Split YAML text into top-level entries (a key line plus its indented or
+/// `- ` continuation lines) and everything else (comments, blank lines),
+/// flagging entries. Concatenated, the segments are the original text.
+fn segments_of(yaml: &str) -> Vec<(&str, bool)> {
+    let is_continuation = |line: &str| {
+        line.starts_with([' ', '\t']) && !line.trim().is_empty()
+            || line.starts_with("- ")
+            || line.trim_end() == "-"
+    };
+    let is_blank = |line: &str| line.trim().is_empty();
+
+    let lines: Vec<&str> = yaml.split_inclusive('\n').collect();
+    let mut segments = Vec::new();
+    let mut start = 0;
+    let mut i = 0;
+
+    while i < lines.len() {
+        let line = lines[i];
+        let is_key = !line.starts_with([' ', '\t', '#', '-']) && !is_blank(line);
+        if !is_key {
+            segments.push((line, false));
+            start += line.len();
+            i += 1;
+            continue;
+        }
+
+        // Blank lines only belong to the entry if more of it follows
+        let mut end = i + 1;
+        let mut j = i + 1;
+        while j < lines.len() && (is_continuation(lines[j]) || is_blank(lines[j])) {
+            j += 1;
+            if is_continuation(lines[j - 1]) {
+                end = j;
+            }
+        }
+
+        let len: usize = lines[i..end].iter().map(|l| l.len()).sum();
+        segments.push((&yaml[start..start + len], true));
+        start += len;
+        i = end;
+    }
+
+    segments
+}
+
+/// Apply `new` to the TOML text `toml`. toml_edit keeps the formatting of
+/// everything it isn't told to change.
+fn edit_toml(toml: &str, new: &Mapping) -> Result<String, String> {
+    let mut doc: DocumentMut = toml.parse().map_err(|e: TomlError| e.to_string())?;
+    let old = from_toml_table(doc.as_table());
+
+    let removed: Vec<String> = doc
+        .iter()
+        .map(|(key, _)| key.to_string())
+        .filter(|key| !new.contains_key(key.as_str()))
+        .collect();
+    for key in removed {
+        doc.remove(&key);
+    }
+
+    for (key, value) in new {
+        let key = key
+            .as_str()
+            .ok_or_else(|| "TOML frontmatter keys must be text".to_string())?;
+        if old.get(key) == Some(value) {
+            continue;
+        }
+        match to_toml_item(value, doc.get(key)) {
+            Some(item) => {
+                doc.insert(key, item);
+            }
+            None => {
+                doc.remove(key);
+            }
+        }
+    }
+
+    Ok(doc.to_string())
+}
+
+fn from_toml_table<'a>(entries: impl IntoIterator<Item = (&'a str, &'a Item)>) -> Mapping {
+    entries
+        .into_iter()
+        .map(|(key, item)| (Value::from(key), from_toml_item(item)))
+        .collect()
+}
+
+fn from_toml_item(item: &Item) -> Value {
+    match item {
+        Item::None => Value::Null,
+        Item::Value(value) => from_toml_value(value),
+        Item::Table(table) => Value::Mapping(from_toml_table(table)),
+        Item::ArrayOfTables(tables) => tables
+            .iter()
+            .map(|table| Value::Mapping(from_toml_table(table)))
+            .collect(),
+    }
+}
+
+/// TOML dates and times become strings, as they are in YAML frontmatter
+fn from_toml_value(value: &TomlValue) -> Value {
+    match value {
+        TomlValue::String(s) => Value::from(s.value().as_str()),
+        TomlValue::Integer(i) => Value::from(*i.value()),
+        TomlValue::Float(f) => Value::from(*f.value()),
+        TomlValue::Boolean(b) => Value::from(*b.value()),
+        TomlValue::Datetime(dt) => Value::from(dt.value().to_string()),
+        TomlValue::Array(array) => array.iter().map(from_toml_value).collect(),
+        TomlValue::InlineTable(table) => Value::Mapping(
+            table
+                .iter()
+                .map(|(key, value)| (Value::from(key), from_toml_value(value)))
+                .collect(),
+        ),
+    }
+}
+
+/// `value` as TOML, replacing `old`. Null has no TOML form and removes the key.
+fn to_toml_item(value: &Value, old: Option<&Item>) -> Option<Item> {
+    if let Value::Mapping(map) = value {
+        if !matches!(old, Some(Item::Value(_))) {
+            let mut table = Table::new();
+            for (key, value) in map {
+                if let (Some(key), Some(item)) = (key.as_str(), to_toml_item(value, None)) {
+                    table.insert(key, item);
+                }
+            }
+            return Some(Item::Table(table));
+        }
+    }
+
+    let old = old.and_then(Item::as_value);
+    let mut new = to_toml_value(value, old)?;
+    if let Some(old) = old {
+        // Keep a trailing comment
+        *new.decor_mut() = old.decor().clone();
+    }
+    Some(Item::Value(new))
+}
+
+fn to_toml_value(value: &Value, old: Option<&TomlValue>) -> Option<TomlValue> {
+    Some(match value {
+        Value::Null => return None,
+        Value::Bool(b) => TomlValue::from(*b),
+        Value::Number(n) => match n.as_i64() {
+            Some(i) => TomlValue::from(i),
+            None => TomlValue::from(n.as_f64()?),
+        },
+        // New dates stay TOML dates, and so do changed ones
+        Value::String(s) => match s.parse::<Datetime>() {
+            Ok(dt) if old.is_none_or(TomlValue::is_datetime) => TomlValue::from(dt),
+            _ => TomlValue::from(s.as_str()),
+        },
+        Value::Sequence(seq) => TomlValue::Array(
+            seq.iter()
+                .filter_map(|value| to_toml_value(value, None))
+                .collect(),
+        ),
+        Value::Mapping(map) => TomlValue::InlineTable(
+            map.iter()
+                .filter_map(|(key, value)| Some((key.as_str()?, to_toml_value(value, None)?)))
+                .collect(),
+        ),
+        Value::Tagged(tagged) => return to_toml_value(&tagged.value, old),
+    })
+}
+
 /// Generate initial frontmatter for a newly created file.
 /// Sets backend-owned fields only.
 pub fn generate_frontmatter(path: &Path, note_type: &str) -> Mapping {
@@ -86,13 +496,13 @@ pub fn ensure_frontmatter(existing: &mut Mapping, path: &Path) {
     let now = Utc::now().to_rfc3339();
 
     // Ensure ID exists (derive from path if missing)
-    if !existing.contains_key(&Value::from("id")) {
+    if !existing.contains_key(Value::from("id")) {
         let id = derive_id_from_path(path);
         existing.insert(Value::from("id"), Value::from(id));
     }
 
     // Ensure created timestamp exists (set once, never overwritten)
-    if !existing.contains_key(&Value::from("created")) {
+    if !existing.contains_key(Value::from("created")) {
         existing.insert(Value::from("created"), Value::from(now.clone()));
     }
 
@@ -109,16 +519,16 @@ pub fn update_frontmatter(existing: &mut Mapping) {
 
 /// Check if frontmatter has all required backend-owned fields.
 pub fn is_frontmatter_complete(frontmatter: &Mapping) -> bool {
-    frontmatter.contains_key(&Value::from("id"))
-        && frontmatter.contains_key(&Value::from("created"))
-        && frontmatter.contains_key(&Value::from("updated"))
+    frontmatter.contains_key(Value::from("id"))
+        && frontmatter.contains_key(Value::from("created"))
+        && frontmatter.contains_key(Value::from("updated"))
 }
 
 // ============ Helper functions for cleaner frontmatter field access ============
 
 /// Get a string value from frontmatter by key.
 pub fn get_str(fm: &Mapping, key: &str) -> Option<String> {
-    fm.get(&Value::from(key))
+    fm.get(Value::from(key))
         .and_then(|v| v.as_str())
         .map(String::from)
 }
@@ -130,7 +540,7 @@ pub fn get_str_or(fm: &Mapping, key: &str, default: &str) -> String {
 
 /// Get a bool value from frontmatter by key.
 pub fn get_bool(fm: &Mapping, key: &str) -> Option<bool> {
-    fm.get(&Value::from(key)).and_then(|v| v.as_bool())
+    fm.get(Value::from(key)).and_then(|v| v.as_bool())
 }
 
 /// Get a bool value from frontmatter, with a default fallback.
@@ -140,12 +550,12 @@ pub fn get_bool_or(fm: &Mapping, key: &str, default: bool) -> bool {
 
 /// Get a u64 value from frontmatter by key.
 pub fn get_u64(fm: &Mapping, key: &str) -> Option<u64> {
-    fm.get(&Value::from(key)).and_then(|v| v.as_u64())
+    fm.get(Value::from(key)).and_then(|v| v.as_u64())
 }
 
 /// Get a string sequence (tags, etc.) from frontmatter by key.
 pub fn get_string_seq(fm: &Mapping, key: &str) -> Vec<String> {
-    fm.get(&Value::from(key))
+    fm.get(Value::from(key))
         .and_then(|v| v.as_sequence())
         .map(|seq| {
             seq.iter()
@@ -165,12 +575,9 @@ mod tests {
         let (fm, body, has_fm) = parse_frontmatter(content);
 
         assert!(has_fm);
+        assert_eq!(fm.get(Value::from("id")).unwrap().as_str().unwrap(), "test");
         assert_eq!(
-            fm.get(&Value::from("id")).unwrap().as_str().unwrap(),
-            "test"
-        );
-        assert_eq!(
-            fm.get(&Value::from("title")).unwrap().as_str().unwrap(),
+            fm.get(Value::from("title")).unwrap().as_str().unwrap(),
             "Test Note"
         );
         assert!(body.contains("Body content"));
@@ -193,5 +600,105 @@ mod tests {
 
         let path = Path::new("data/projects/myproject/index.md");
         assert_eq!(derive_id_from_path(path), "myproject-index");
+
+        let path = Path::new("data/notes/a/b-c.md");
+        assert_eq!(derive_id_from_path(path), "a:b-c");
+        let path = Path::new("data/notes/a-b/c.md");
+        assert_eq!(derive_id_from_path(path), "a-b:c");
+        let path = Path::new("data/notes/a:b.md");
+        assert_eq!(derive_id_from_path(path), "a%3Ab");
+    }
+
+    #[test]
+    fn test_rewrite_keeps_untouched_yaml() {
+        let original = "---\n# Written in Obsidian\nid: test\ntitle: \"Quoted: title\"\ntags: [a, b]   # flow\n\naliases:\n  - one\n\n  - two\ncompleted: false\nupdated: 2026-01-01T00:00:00Z\n---\nBody\n";
+        let (mut fm, body, _) = parse_frontmatter(original);
+
+        fm.insert(Value::from("completed"), Value::from(true));
+        fm.insert(Value::from("section"), Value::from("Completed"));
+        fm.remove(Value::from("updated"));
+        let rewritten = rewrite(original, &fm, &body).unwrap();
+
+        assert_eq!(
+            rewritten,
+            "---\n# Written in Obsidian\nid: test\ntitle: \"Quoted: title\"\ntags: [a, b]   # flow\n\naliases:\n  - one\n\n  - two\ncompleted: true\nsection: Completed\n---\nBody\n"
+        );
+        assert_eq!(
+            rewrite(original, &parse_frontmatter(original).0, &body).unwrap(),
+            original
+        );
+    }
+
+    #[test]
+    fn test_rewrite_falls_back() {
+        // An alias can't be edited entry by entry
+        let original = "---\nbase: &b x\ncopy: *b\n---\n\nBody";
+        let (mut fm, body, _) = parse_frontmatter(original);
+        fm.insert(Value::from("base"), Value::from("y"));
+        assert_eq!(
+            rewrite(original, &fm, &body).unwrap(),
+            serialize_frontmatter(&fm, &body).unwrap()
+        );
+
+        // No frontmatter yet
+        let mut fm = Mapping::new();
+        fm.insert(Value::from("id"), Value::from("x"));
+        assert_eq!(
+            rewrite("Body", &fm, "Body").unwrap(),
+            "---\nid: x\n---\n\nBody"
+        );
+    }
+
+    #[test]
+    fn test_parse_fences() {
+        // `---` in a value, and a horizontal rule right after the block
+        let content = "---\ntitle: \"a --- b\"\n---\n---\nAfter the rule";
+        let (fm, body, has_fm) = parse_frontmatter(content);
+        assert!(has_fm);
+        assert_eq!(get_str(&fm, "title").unwrap(), "a --- b");
+        assert_eq!(body, "---\nAfter the rule");
+
+        // A rule with no closing fence is body
+        let content = "---\nJust a rule above";
+        assert!(!parse_frontmatter(content).2);
+
+        let parsed = parse("---\ntitle: [unclosed\n---\nBody");
+        assert!(parsed.frontmatter.is_empty());
+        assert!(parsed.error.unwrap().starts_with("YAML error"));
+    }
+
+    #[test]
+    fn test_toml_and_json() {
+        let original = "+++\ntitle = \"Hugo post\" # from the site\ndate = 2024-01-02T03:04:05Z\ntags = [\"rust\"]\n\n[extra]\nauthor = \"me\"\n+++\n\nBody\n";
+        let parsed = parse(original);
+        assert_eq!(parsed.format, Some(Format::Toml));
+        let mut fm = parsed.frontmatter;
+        assert_eq!(get_str(&fm, "date").unwrap(), "2024-01-02T03:04:05Z");
+        assert_eq!(get_string_seq(&fm, "tags"), vec!["rust"]);
+
+        fm.insert(Value::from("title"), Value::from("Renamed"));
+        fm.insert(Value::from("updated"), Value::from("2026-02-05T12:34:56Z"));
+        assert_eq!(
+            rewrite(original, &fm, &parsed.body).unwrap(),
+            "+++\ntitle = \"Renamed\" # from the site\ndate = 2024-01-02T03:04:05Z\ntags = [\"rust\"]\nupdated = 2026-02-05T12:34:56Z\n\n[extra]\nauthor = \"me\"\n+++\n\nBody\n"
+        );
+
+        let original = ";;;\n{\"title\": \"Post\", \"draft\": true}\n;;;\nBody";
+        let (mut fm, body, _) = parse_frontmatter(original);
+        fm.insert(Value::from("draft"), Value::from(false));
+        assert_eq!(
+            rewrite(original, &fm, &body).unwrap(),
+            ";;;\n{\n  \"title\": \"Post\",\n  \"draft\": false\n}\n;;;\nBody"
+        );
+    }
+
+    #[test]
+    fn test_rewrite_refuses_invalid_frontmatter() {
+        let original = "---\ntitle: Notes\nstatus: [draft\n---\nBody";
+        let (mut fm, body, _) = parse_frontmatter(original);
+        fm.insert(Value::from("updated"), Value::from("now"));
+
+        let err = rewrite(original, &fm, &body).unwrap_err();
+        assert!(err.starts_with("Invalid frontmatter"));
     }
 }
@@ -0,0 +1,161 @@
+use crate::services::matcher;
+
+/// Result of fuzzy-matching a pattern against a string
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FuzzyMatch {
+    /// Higher is better
+    pub score: i64,
+    /// Char indices in the text that matched the pattern
+    pub positions: Vec<usize>,
+}
+
+/// Scoring weights
+const SCORE_MATCH: i64 = 16;
+const BONUS_START: i64 = 12;
+const BONUS_BOUNDARY: i64 = 10;
+const BONUS_CONSECUTIVE: i64 = 12;
+const PENALTY_GAP: i64 = 1;
+const MAX_LEADING_PENALTY: i64 = 8;
+
+/// Fuzzy-match `pattern` against `text`: every pattern char must appear in
+/// order. Matching ignores case, diacritics and whitespace in the pattern.
+/// Consecutive matches and matches at word starts score higher, gaps lower.
+pub fn fuzzy_match(pattern: &str, text: &str) -> Option<FuzzyMatch> {
+    let pattern: Vec<char> = fold(pattern).filter(|c| !c.is_whitespace()).collect();
+    let original: Vec<char> = text.chars().collect();
+    let chars: Vec<char> = fold(text).collect();
+    let (m, n) = (pattern.len(), chars.len());
+
+    if m == 0 || m > n || !is_subsequence(&pattern, &chars) {
+        return None;
+    }
+
+    let bonus: Vec<i64> = (0..n).map(|j| position_bonus(&original, j)).collect();
+
+    // best[i][j]: best score with pattern[i] matched at text[j];
+    // from[i][j]: where pattern[i - 1] was matched on that path
+    const NONE: i64 = i64::MIN / 2;
+    let mut best = vec![vec![NONE; n]; m];
+    let mut from = vec![vec![0usize; n]; m];
+
+    // Where the first char lands matters most, so its bonus counts double
+    for j in 0..n {
+        if chars[j] == pattern[0] {
+            let leading = (j as i64 * PENALTY_GAP).min(MAX_LEADING_PENALTY);
+            best[0][j] = SCORE_MATCH + 2 * bonus[j] - leading;
+        }
+    }
+
+    for i in 1..m {
+        // Best (score, position) of the previous row at least two chars back,
+        // with the gap penalty applied up to the current column
+        let mut gapped: (i64, usize) = (NONE, 0);
+
+        for j in i..n {
+            if j >= 2 {
+                let candidate = best[i - 1][j - 2] - PENALTY_GAP;
+                gapped.0 -= PENALTY_GAP;
+                if candidate > gapped.0 {
+                    gapped = (candidate, j - 2);
+                }
+            }
+
+            if chars[j] != pattern[i] {
+                continue;
+            }
+
+            let consecutive = best[i - 1][j - 1] + BONUS_CONSECUTIVE;
+            let (prev_score, prev_pos) = if consecutive >= gapped.0 {
+                (consecutive, j - 1)
+            } else {
+                gapped
+            };
+
+            if prev_score > NONE / 2 {
+                best[i][j] = prev_score + SCORE_MATCH + bonus[j];
+                from[i][j] = prev_pos;
+            }
+        }
+    }
+
+    let (mut j, score) = best[m - 1]
+        .iter()
+        .enumerate()
+        .max_by_key(|(_, s)| **s)
+        .map(|(j, s)| (j, *s))?;
+
+    if score <= NONE / 2 {
+        return None;
+    }
+
+    let mut positions = vec![0; m];
+    for i in (0..m).rev() {
+        positions[i] = j;
+        j = from[i][j];
+    }
+
+    Some(FuzzyMatch { score, positions })
+}
+
+fn fold(text: &str) -> impl Iterator<Item = char> + '_ {
+    // One char per input char, so positions line up with the original
+    text.chars()
+        .map(|c| matcher::fold_char(c).to_lowercase().next().unwrap_or(c))
+}
+
+fn is_subsequence(pattern: &[char], chars: &[char]) -> bool {
+    let mut rest = chars.iter();
+    pattern.iter().all(|p| rest.any(|c| c == p))
+}
+
+/// Bonus for matching at the start of the text or of a word (incl. camelCase).
+fn position_bonus(chars: &[char], j: usize) -> i64 {
+    if j == 0 {
+        return BONUS_START;
+    }
+
+    let prev = chars[j - 1];
+    let cur = chars[j];
+    let word_start = !prev.is_alphanumeric() && cur.is_alphanumeric();
+    let camel_case = prev.is_lowercase() && cur.is_uppercase();
+    if word_start || camel_case {
+        BONUS_BOUNDARY
+    } else {
+        0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fuzzy_match_positions() {
+        let m = fuzzy_match("pgd", "Postgres Guide").unwrap();
+        assert_eq!(m.positions, vec![0, 9, 12]);
+
+        let m = fuzzy_match("guide", "Postgres Guide").unwrap();
+        assert_eq!(m.positions, vec![9, 10, 11, 12, 13]);
+
+        assert!(fuzzy_match("xyz", "Postgres Guide").is_none());
+        assert!(fuzzy_match("", "Postgres Guide").is_none());
+    }
+
+    #[test]
+    fn test_fuzzy_match_ranking() {
+        let word_start = fuzzy_match("pg", "Postgres Guide").unwrap();
+        let scattered = fuzzy_match("pg", "upgrade").unwrap();
+        assert!(word_start.score > scattered.score);
+
+        let exact = fuzzy_match("backup", "Backups").unwrap();
+        let spread = fuzzy_match("backup", "black cup").unwrap();
+        assert!(exact.score > spread.score);
+
+        let acronym = fuzzy_match("pgd", "Postgres Guide").unwrap();
+        let inside = fuzzy_match("pgd", "Upgrade db").unwrap();
+        assert!(acronym.score > inside.score);
+
+        assert!(fuzzy_match("cafe", "Café menu").is_some());
+        assert!(fuzzy_match("post guide", "Postgres Guide").is_some());
+    }
+}
@@ -1,3 +1,4 @@
+use std::path::Path;
 use std::time::Duration;
 
 use chrono::Utc;
@@ -92,9 +93,9 @@ pub struct RemoteInfo {
     pub behind: usize,
 }
 
-/// Auto-commit is enabled by default.
-/// The background task simply tries to commit every interval;
-/// commit_all() already handles "no changes" gracefully.
+// Auto-commit is enabled by default.
+// The background task simply tries to commit every interval;
+// commit_all() already handles "no changes" gracefully.
 
 /// Get repository status
 pub fn get_status() -> Result<RepoStatus, String> {
@@ -186,6 +187,43 @@ pub fn commit_all(message: Option<&str>) -> Result<CommitInfo, String> {
         .map_err(|e| e.to_string())?;
     index.write().map_err(|e| e.to_string())?;
 
+    commit_index(&repo, &mut index, message.unwrap_or("Auto-save"))
+}
+
+/// Create a commit with only `paths` (data-relative files or folders) staged,
+/// so other pending changes are left for a later commit.
+/// Paths that no longer exist are committed as deleted.
+pub fn commit_paths<S: AsRef<str>>(paths: &[S], message: &str) -> Result<CommitInfo, String> {
+    let data_path = config::data_dir();
+    let repo = Repository::open(data_path).map_err(|e| format!("Not a git repository: {}", e))?;
+
+    let mut index = repo.index().map_err(|e| e.to_string())?;
+    for path in paths {
+        let path = path.as_ref();
+        let abs = data_path.join(path);
+        if abs.is_file() {
+            index.add_path(Path::new(path)).map_err(|e| e.to_string())?;
+        } else if abs.is_dir() {
+            index
+                .add_all([path].iter(), git2::IndexAddOption::DEFAULT, None)
+                .map_err(|e| e.to_string())?;
+        } else {
+            index
+                .remove_all([path].iter(), None)
+                .map_err(|e| e.to_string())?;
+        }
+    }
+    index.write().map_err(|e| e.to_string())?;
+
+    commit_index(&repo, &mut index, message)
+}
+
+/// Commit the staged index on top of HEAD.
+fn commit_index(
+    repo: &Repository,
+    index: &mut git2::Index,
+    message: &str,
+) -> Result<CommitInfo, String> {
     // Check if there are changes to commit
     let tree_id = index.write_tree().map_err(|e| e.to_string())?;
     let tree = repo.find_tree(tree_id).map_err(|e| e.to_string())?;
@@ -204,9 +242,8 @@ pub fn commit_all(message: Option<&str>) -> Result<CommitInfo, String> {
     let sig = Signature::now("Ironpad", "ironpad@local").map_err(|e| e.to_string())?;
 
     // Generate commit message
-    let msg = message.unwrap_or_else(|| "Auto-save");
     let timestamp = Utc::now().format("%Y-%m-%d %H:%M");
-    let full_message = format!("{} ({})", msg, timestamp);
+    let full_message = format!("{} ({})", message, timestamp);
 
     // Create commit
     let parents: Vec<&git2::Commit> = parent.as_ref().map(|p| vec![p]).unwrap_or_default();
@@ -277,13 +314,11 @@ pub fn check_conflicts() -> Result<Vec<String>, String> {
     // Also check the index for conflicts
     let index = repo.index().map_err(|e| e.to_string())?;
     if index.has_conflicts() {
-        for conflict in index.conflicts().map_err(|e| e.to_string())? {
-            if let Ok(conflict) = conflict {
-                if let Some(ancestor) = conflict.ancestor {
-                    if let Some(path) = std::str::from_utf8(&ancestor.path).ok() {
-                        if !conflicts.contains(&path.to_string()) {
-                            conflicts.push(path.to_string());
-                        }
+        for conflict in index.conflicts().map_err(|e| e.to_string())?.flatten() {
+            if let Some(ancestor) = conflict.ancestor {
+                if let Ok(path) = std::str::from_utf8(&ancestor.path) {
+                    if !conflicts.contains(&path.to_string()) {
+                        conflicts.push(path.to_string());
                     }
                 }
             }
@@ -507,45 +542,10 @@ fn parse_diff(diff: &git2::Diff) -> Result<DiffInfo, String> {
             _ => "unknown",
         };
 
-        let mut hunks = Vec::new();
-        let mut additions = 0;
-        let mut deletions = 0;
-
-        // Get patch for this file
-        if let Ok(patch) = git2::Patch::from_diff(diff, delta_idx) {
-            if let Some(p) = patch {
-                for hunk_idx in 0..p.num_hunks() {
-                    if let Ok((hunk, _)) = p.hunk(hunk_idx) {
-                        let mut lines = Vec::new();
-
-                        for line_idx in 0..p.num_lines_in_hunk(hunk_idx).unwrap_or(0) {
-                            if let Ok(line) = p.line_in_hunk(hunk_idx, line_idx) {
-                                let origin = line.origin();
-                                let content = std::str::from_utf8(line.content())
-                                    .unwrap_or("")
-                                    .to_string();
-
-                                match origin {
-                                    '+' => additions += 1,
-                                    '-' => deletions += 1,
-                                    _ => {}
-                                }
-
-                                lines.push(DiffLine { origin, content });
-                            }
-                        }
-
-                        hunks.push(DiffHunk {
-                            header: std::str::from_utf8(hunk.header())
-                                .unwrap_or("")
-                                .trim()
-                                .to_string(),
-                            lines,
-                        });
-                    }
-                }
-            }
-        }
+        let (hunks, additions, deletions) = match git2::Patch::from_diff(diff, delta_idx) {
+            Ok(Some(p)) => parse_patch(&p),
+            _ => (Vec::new(), 0, 0),
+        };
 
         files.push(FileDiff {
             path,
@@ -566,6 +566,68 @@ fn parse_diff(diff: &git2::Diff) -> Result<DiffInfo, String> {
     })
 }
 
+/// Collect the hunks of a patch, with addition and deletion counts
+fn parse_patch(p: &git2::Patch) -> (Vec<DiffHunk>, usize, usize) {
+    let mut hunks = Vec::new();
+    let mut additions = 0;
+    let mut deletions = 0;
+
+    for hunk_idx in 0..p.num_hunks() {
+        if let Ok((hunk, _)) = p.hunk(hunk_idx) {
+            let mut lines = Vec::new();
+
+            for line_idx in 0..p.num_lines_in_hunk(hunk_idx).unwrap_or(0) {
+                if let Ok(line) = p.line_in_hunk(hunk_idx, line_idx) {
+                    let origin = line.origin();
+                    let content = std::str::from_utf8(line.content())
+                        .unwrap_or("")
+                        .to_string();
+
+                    match origin {
+                        '+' => additions += 1,
+                        '-' => deletions += 1,
+                        _ => {}
+                    }
+
+                    lines.push(DiffLine { origin, content });
+                }
+            }
+
+            hunks.push(DiffHunk {
+                header: std::str::from_utf8(hunk.header())
+                    .unwrap_or("")
+                    .trim()
+                    .to_string(),
+                lines,
+            });
+        }
+    }
+
+    (hunks, additions, deletions)
+}
+
+/// Diff two versions of a file that haven't been written yet (e.g. to preview an edit)
+pub fn diff_text(path: &str, old: &str, new: &str) -> Result<FileDiff, String> {
+    let patch = git2::Patch::from_buffers(
+        old.as_bytes(),
+        Some(std::path::Path::new(path)),
+        new.as_bytes(),
+        Some(std::path::Path::new(path)),
+        None,
+    )
+    .map_err(|e| e.to_string())?;
+
+    let (hunks, additions, deletions) = parse_patch(&patch);
+
+    Ok(FileDiff {
+        path: path.to_string(),
+        status: "modified".to_string(),
+        additions,
+        deletions,
+        hunks,
+    })
+}
+
 /// Get remote repository information
 pub fn get_remote_info() -> Result<Option<RemoteInfo>, String> {
     let data_path = config::data_dir();
@@ -0,0 +1,387 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+
+use serde::Serialize;
+
+use crate::services::frontmatter;
+use crate::services::index::{self, IndexedDoc, SearchIndex};
+use crate::services::links;
+use crate::services::tags;
+
+/// Node kinds in the graph (document types plus tags)
+pub const NODE_KINDS: [&str; 5] = ["note", "task", "project", "daily", "tag"];
+
+/// A document or tag in the graph
+#[derive(Debug, Clone, Serialize)]
+pub struct GraphNode {
+    /// Data-relative path for documents, `tag:{name}` for tags
+    pub id: String,
+    /// note, task, project, daily or tag
+    pub kind: &'static str,
+    /// The document's own id (or the tag name)
+    pub entity_id: String,
+    pub title: String,
+    pub project_id: Option<String>,
+    /// Connected component the node belongs to (0 is the largest)
+    pub cluster: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct GraphEdge {
+    pub source: String,
+    pub target: String,
+    /// link, parent, project or tag
+    pub kind: &'static str,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Cluster {
+    pub id: usize,
+    pub size: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Graph {
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+    /// Ids of nodes without any edge
+    pub orphans: Vec<String>,
+    /// Connected components, largest first
+    pub clusters: Vec<Cluster>,
+}
+
+#[derive(Debug, Default)]
+pub struct GraphFilter {
+    /// Only documents belonging to this project (and their tags)
+    pub project: Option<String>,
+    /// Only these node kinds
+    pub kinds: Option<Vec<String>>,
+    /// Only documents with this tag
+    pub tag: Option<String>,
+    /// Node id (or document id) to centre the graph on
+    pub focus: Option<String>,
+    /// Maximum number of hops from the focus node
+    pub depth: usize,
+}
+
+/// The vault-wide graph, filtered. Built from the search index, so it
+/// reflects every change the watcher or our own writes have seen.
+pub fn graph(filter: &GraphFilter) -> Result<Graph, String> {
+    if let Some(kinds) = &filter.kinds {
+        if let Some(kind) = kinds.iter().find(|k| !NODE_KINDS.contains(&k.as_str())) {
+            return Err(format!("Invalid type: {}", kind));
+        }
+    }
+
+    let (nodes, edges) = index::read(build);
+    let (nodes, edges) = apply_filter(nodes, edges, filter)?;
+    Ok(with_clusters(nodes, edges))
+}
+
+fn doc_kind(doc: &IndexedDoc) -> Option<&'static str> {
+    match doc.doc_type.as_str() {
+        "note" => Some("note"),
+        "task" => Some("task"),
+        "project" => Some("project"),
+        "daily" => Some("daily"),
+        _ => None,
+    }
+}
+
+fn tag_node_id(tag: &str) -> String {
+    format!("tag:{}", tags::normalize(tag))
+}
+
+/// Every node and edge in the index.
+fn build(idx: &SearchIndex) -> (BTreeMap<String, GraphNode>, BTreeSet<GraphEdge>) {
+    let docs: Vec<(&IndexedDoc, &'static str)> = idx
+        .docs()
+        .filter_map(|doc| doc_kind(doc).map(|kind| (doc, kind)))
+        .collect();
+
+    let mut nodes = BTreeMap::new();
+    let mut edges = BTreeSet::new();
+    let mut edge = |source: &str, target: &str, kind| {
+        if source != target {
+            edges.insert(GraphEdge {
+                source: source.to_string(),
+                target: target.to_string(),
+                kind,
+            });
+        }
+    };
+
+    // Link keys → documents they resolve to
+    let mut targets: HashMap<String, Vec<&str>> = HashMap::new();
+    for (doc, _) in &docs {
+        for key in links::target_keys(doc) {
+            targets.entry(key).or_default().push(&doc.path);
+        }
+    }
+
+    for (doc, kind) in &docs {
+        let project_id = doc.project_id();
+
+        nodes.insert(
+            doc.path.clone(),
+            GraphNode {
+                id: doc.path.clone(),
+                kind,
+                entity_id: doc.id.clone(),
+                title: doc.title.clone(),
+                project_id: project_id.clone(),
+                cluster: 0,
+            },
+        );
+
+        for link in &doc.links {
+            for target in targets.get(&link.key).into_iter().flatten() {
+                edge(&doc.path, target, "link");
+            }
+        }
+
+        if let Some(project_id) = &project_id {
+            let project_path = format!("projects/{}/index.md", project_id);
+            if *kind != "project" && idx.get(&project_path).is_some() {
+                edge(&doc.path, &project_path, "project");
+            }
+
+            if let Some(parent_id) = frontmatter::get_str(&doc.frontmatter, "parent_id") {
+                let parent = idx
+                    .docs_with_id(&parent_id)
+                    .find(|p| p.doc_type == "task" && p.project_id().as_ref() == Some(project_id));
+                if let Some(parent) = parent {
+                    edge(&doc.path, &parent.path, "parent");
+                }
+            }
+        }
+
+        for tag in &doc.tags {
+            let id = tag_node_id(tag);
+            nodes.entry(id.clone()).or_insert_with(|| GraphNode {
+                id: id.clone(),
+                kind: "tag",
+                entity_id: tag.clone(),
+                title: tag.clone(),
+                project_id: None,
+                cluster: 0,
+            });
+            edge(&doc.path, &id, "tag");
+        }
+    }
+
+    (nodes, edges)
+}
+
+fn apply_filter(
+    mut nodes: BTreeMap<String, GraphNode>,
+    mut edges: BTreeSet<GraphEdge>,
+    filter: &GraphFilter,
+) -> Result<(BTreeMap<String, GraphNode>, BTreeSet<GraphEdge>), String> {
+    let tagged = filter.tag.as_deref().map(tags::normalize);
+
+    // Documents first; tags survive only if a remaining document carries them
+    let mut keep: HashSet<String> = nodes
+        .values()
+        .filter(|node| node.kind != "tag")
+        .filter(|node| {
+            filter
+                .project
+                .as_ref()
+                .is_none_or(|p| node.project_id.as_ref() == Some(p))
+        })
+        .filter(|node| {
+            tagged.as_ref().is_none_or(|tag| {
+                edges.iter().any(|e| {
+                    e.kind == "tag"
+                        && e.source == node.id
+                        && e.target
+                            .strip_prefix("tag:")
+                            .is_some_and(|t| tags::has_tag(t, tag))
+                })
+            })
+        })
+        .map(|node| node.id.clone())
+        .collect();
+
+    let tags: Vec<String> = edges
+        .iter()
+        .filter(|e| e.kind == "tag" && keep.contains(&e.source))
+        .map(|e| e.target.clone())
+        .collect();
+    keep.extend(tags);
+
+    if let Some(kinds) = &filter.kinds {
+        keep.retain(|id| kinds.iter().any(|k| k == nodes[id].kind));
+    }
+
+    nodes.retain(|id, _| keep.contains(id));
+    edges.retain(|e| keep.contains(&e.source) && keep.contains(&e.target));
+
+    if let Some(focus) = &filter.focus {
+        let start = if nodes.contains_key(focus) {
+            focus.clone()
+        } else {
+            nodes
+                .values()
+                .find(|n| n.kind != "tag" && &n.entity_id == focus)
+                .map(|n| n.id.clone())
+                .ok_or_else(|| format!("Node not found: {}", focus))?
+        };
+
+        let reachable = within(&edges, &start, filter.depth);
+        nodes.retain(|id, _| reachable.contains(id));
+        edges.retain(|e| reachable.contains(&e.source) && reachable.contains(&e.target));
+    }
+
+    Ok((nodes, edges))
+}
+
+fn adjacency(edges: &BTreeSet<GraphEdge>) -> HashMap<&str, Vec<&str>> {
+    let mut adjacent: HashMap<&str, Vec<&str>> = HashMap::new();
+    for e in edges {
+        adjacent.entry(&e.source).or_default().push(&e.target);
+        adjacent.entry(&e.target).or_default().push(&e.source);
+    }
+    adjacent
+}
+
+/// Nodes at most `depth` hops from `start`, ignoring edge direction.
+fn within(edges: &BTreeSet<GraphEdge>, start: &str, depth: usize) -> HashSet<String> {
+    let adjacent = adjacency(edges);
+    let mut seen: HashSet<String> = HashSet::from([start.to_string()]);
+    let mut queue = VecDeque::from([(start, 0)]);
+
+    while let Some((node, hops)) = queue.pop_front() {
+        if hops == depth {
+            continue;
+        }
+        for next in adjacent.get(node).into_iter().flatten() {
+            if seen.insert(next.to_string()) {
+                queue.push_back((next, hops + 1));
+            }
+        }
+    }
+
+    seen
+}
+
+/// Number connected components (largest first) and list nodes without edges.
+fn with_clusters(mut nodes: BTreeMap<String, GraphNode>, edges: BTreeSet<GraphEdge>) -> Graph {
+    let adjacent = adjacency(&edges);
+
+    let mut components: Vec<Vec<String>> = Vec::new();
+    let mut seen: HashSet<&str> = HashSet::new();
+    for id in nodes.keys() {
+        if !seen.insert(id) {
+            continue;
+        }
+        let mut component = vec![id.clone()];
+        let mut stack = vec![id.as_str()];
+        while let Some(node) = stack.pop() {
+            for next in adjacent.get(node).into_iter().flatten() {
+                if seen.insert(next) {
+                    component.push(next.to_string());
+                    stack.push(next);
+                }
+            }
+        }
+        components.push(component);
+    }
+
+    // Stable: ties keep the order of their first node id
+    components.sort_by_key(|c| std::cmp::Reverse(c.len()));
+
+    let mut clusters = Vec::with_capacity(components.len());
+    for (cluster, members) in components.iter().enumerate() {
+        for id in members {
+            if let Some(node) = nodes.get_mut(id) {
+                node.cluster = cluster;
+            }
+        }
+        clusters.push(Cluster {
+            id: cluster,
+            size: members.len(),
+        });
+    }
+
+    let orphans = nodes
+        .keys()
+        .filter(|id| !adjacent.contains_key(id.as_str()))
+        .cloned()
+        .collect();
+
+    Graph {
+        nodes: nodes.into_values().collect(),
+        edges: edges.into_iter().collect(),
+        orphans,
+        clusters,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    fn vault() -> SearchIndex {
+        let mut idx = SearchIndex::default();
+        idx.insert(
+            Path::new("data/notes/pg.md"),
+            "---\nid: pg\ntitle: Postgres\ntags: [infra]\n---\nSee [[backup]]",
+        );
+        idx.insert(Path::new("data/notes/backup.md"), "Backups");
+        idx.insert(Path::new("data/notes/lonely.md"), "Nothing links here");
+        idx.insert(
+            Path::new("data/projects/ops/index.md"),
+            "---\nid: ops\ntitle: Ops\n---\n",
+        );
+        idx.insert(
+            Path::new("data/projects/ops/tasks/task-1.md"),
+            "---\nid: task-1\ntitle: Upgrade\ntags: [infra]\n---\n",
+        );
+        idx.insert(
+            Path::new("data/projects/ops/tasks/task-2.md"),
+            "---\nid: task-2\ntitle: Dump\nparent_id: task-1\n---\n",
+        );
+        idx
+    }
+
+    #[test]
+    fn test_build_graph() {
+        let (nodes, edges) = build(&vault());
+        let graph = with_clusters(nodes, edges);
+
+        let kinds: Vec<_> = graph.edges.iter().map(|e| e.kind).collect();
+        assert!(kinds.contains(&"link"));
+        assert!(kinds.contains(&"parent"));
+        assert!(kinds.contains(&"project"));
+        assert!(kinds.contains(&"tag"));
+
+        assert_eq!(graph.orphans, vec!["notes/lonely.md"]);
+        assert_eq!(graph.clusters.len(), 2);
+        assert_eq!(graph.clusters[0].size, 6);
+    }
+
+    #[test]
+    fn test_filter_graph() {
+        let (nodes, edges) = build(&vault());
+        let filter = GraphFilter {
+            project: Some("ops".to_string()),
+            kinds: Some(vec!["task".to_string()]),
+            ..Default::default()
+        };
+        let (nodes, edges) = apply_filter(nodes, edges, &filter).unwrap();
+        assert_eq!(nodes.len(), 2);
+        assert_eq!(edges.len(), 1);
+
+        let (nodes, edges) = build(&vault());
+        let filter = GraphFilter {
+            focus: Some("backup".to_string()),
+            depth: 1,
+            ..Default::default()
+        };
+        let (nodes, _) = apply_filter(nodes, edges, &filter).unwrap();
+        let ids: Vec<&str> = nodes.keys().map(String::as_str).collect();
+        assert_eq!(ids, vec!["notes/backup.md", "notes/pg.md"]);
+    }
+}
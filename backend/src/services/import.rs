@@ -0,0 +1,1150 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::{NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use serde_yaml::{Mapping, Value};
+use walkdir::WalkDir;
+
+use crate::config;
+use crate::services::filesystem;
+use crate::services::folders;
+use crate::services::frontmatter;
+use crate::services::git::{self, CommitInfo};
+use crate::services::index;
+use crate::services::links::{self, LinkRef, WikiLink};
+use crate::services::markdown::Targets;
+use crate::services::tags;
+
+/// Vault formats the importer understands
+pub const IMPORT_FORMATS: [&str; 2] = ["obsidian", "logseq"];
+
+/// Attachments copied into `notes/assets` (other files are skipped)
+const ASSET_EXTENSIONS: [&str; 9] = [
+    "png", "jpg", "jpeg", "gif", "webp", "svg", "bmp", "pdf", "tiff",
+];
+
+/// What to import and where
+#[derive(Debug, Default, Deserialize)]
+pub struct ImportOptions {
+    /// `obsidian` or `logseq`; detected from the vault when omitted
+    pub format: Option<String>,
+    /// Folder under `notes/` to import into (default: `notes/` itself)
+    pub folder: Option<String>,
+    /// Move checklist items (`- [ ]`, Logseq `TODO`/`DONE`) into task files of this project
+    pub tasks_project: Option<String>,
+    /// Write the files; without it the import is a dry run that only reports
+    #[serde(default)]
+    pub apply: bool,
+    /// Commit message (defaults to a summary of the import)
+    pub message: Option<String>,
+}
+
+/// What an import wrote, or would write on a dry run
+#[derive(Debug, Serialize)]
+pub struct ImportReport {
+    pub source: String,
+    pub format: String,
+    /// False for a dry run: nothing was written
+    pub applied: bool,
+    pub notes: Vec<ImportedFile>,
+    pub assets: Vec<ImportedFile>,
+    pub tasks: Vec<ImportedTask>,
+    /// Wiki links, embeds and markdown links pointed at their imported targets
+    pub links_rewritten: usize,
+    /// Links to pages or files that aren't in the vault (left as written)
+    pub unresolved: Vec<UnresolvedLink>,
+    pub skipped: Vec<SkippedFile>,
+    /// The commit recording the import (None for a dry run or if git is unavailable)
+    pub commit: Option<CommitInfo>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ImportedFile {
+    /// Path in the source vault
+    pub from: String,
+    /// Data-relative path in Ironpad
+    pub to: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ImportedTask {
+    /// Note the item was taken from, in the source vault
+    pub from: String,
+    pub to: String,
+    pub title: String,
+    pub completed: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct UnresolvedLink {
+    pub file: String,
+    pub target: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SkippedFile {
+    pub path: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Obsidian,
+    Logseq,
+}
+
+/// A markdown file on its way into the data folder
+struct Page {
+    from: String,
+    to: String,
+    title: String,
+    frontmatter: Mapping,
+    body: String,
+}
+
+/// An attachment on its way into `notes/assets`
+struct Asset {
+    from: String,
+    name: String,
+    /// An identical file is already in `notes/assets`
+    existing: bool,
+}
+
+struct Task {
+    from: String,
+    to: String,
+    id: String,
+    item: TaskLine,
+    /// Wiki link target of the note it came from
+    note_target: String,
+    note_title: String,
+}
+
+/// A checklist item in an imported note
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct TaskLine {
+    indent: String,
+    title: String,
+    completed: bool,
+    due_date: Option<String>,
+    priority: Option<String>,
+}
+
+/// Import an Obsidian or Logseq vault at `source`: markdown files become
+/// notes (Logseq journals become daily notes), attachments go to
+/// `notes/assets`, and links are rewritten to resolve in Ironpad. Unless
+/// `options.apply` is set nothing is written and the report shows the plan.
+pub fn import(source: &Path, options: &ImportOptions) -> Result<ImportReport, String> {
+    let source = fs::canonicalize(source)
+        .ok()
+        .filter(|s| s.is_dir())
+        .ok_or_else(|| format!("Invalid source: {} is not a folder", source.display()))?;
+    let data = fs::canonicalize(config::data_dir()).map_err(|e| e.to_string())?;
+    if source.starts_with(&data) || data.starts_with(&source) {
+        return Err(format!(
+            "Invalid source: {} overlaps the data folder",
+            source.display()
+        ));
+    }
+
+    let format = match options.format.as_deref() {
+        Some("obsidian") => Format::Obsidian,
+        Some("logseq") => Format::Logseq,
+        Some(other) => {
+            return Err(format!(
+                "Invalid format: {} (expected {})",
+                other,
+                IMPORT_FORMATS.join(" or ")
+            ))
+        }
+        None => detect_format(&source),
+    };
+    let folder = options
+        .folder
+        .as_deref()
+        .filter(|f| !f.trim().is_empty())
+        .map(folders::validate_folder)
+        .transpose()?;
+    if let Some(project) = &options.tasks_project {
+        if project.is_empty()
+            || project.starts_with('.')
+            || project.contains('/')
+            || project.contains('\\')
+        {
+            return Err(format!("Invalid tasks_project: {}", project));
+        }
+        if !data
+            .join("projects")
+            .join(project)
+            .join("index.md")
+            .is_file()
+        {
+            return Err(format!("Project not found: {}", project));
+        }
+    }
+
+    let mut plan = Plan::new(format, config::data_dir());
+    plan.scan(&source, folder.as_deref())?;
+    plan.rewrite_links();
+    if let Some(project) = &options.tasks_project {
+        plan.extract_tasks(project);
+    }
+
+    let mut report = plan.report(&source);
+    if options.apply {
+        plan.write(&source)?;
+        let default_message = format!("Import {} notes from {}", report.notes.len(), report.format);
+        let message = options.message.as_deref().unwrap_or(&default_message);
+        report.commit = match git::commit_paths(&plan.written(), message) {
+            Ok(info) => Some(info),
+            Err(err) => {
+                tracing::warn!("Import written but not committed: {}", err);
+                None
+            }
+        };
+        report.applied = true;
+    }
+
+    Ok(report)
+}
+
+/// Logseq keeps its settings in `logseq/`, Obsidian in `.obsidian/`.
+fn detect_format(source: &Path) -> Format {
+    let logseq = source.join("logseq").is_dir()
+        || (source.join("pages").is_dir() && source.join("journals").is_dir());
+    if logseq && !source.join(".obsidian").is_dir() {
+        Format::Logseq
+    } else {
+        Format::Obsidian
+    }
+}
+
+struct Plan {
+    format: Format,
+    /// The data folder
+    root: PathBuf,
+    pages: Vec<Page>,
+    assets: Vec<Asset>,
+    tasks: Vec<Task>,
+    skipped: Vec<SkippedFile>,
+    unresolved: Vec<UnresolvedLink>,
+    links_rewritten: usize,
+    /// How links name each page (see `link_targets`)
+    targets: Vec<String>,
+    /// Link keys (lowercase name, path, title or alias) → page
+    page_keys: HashMap<String, usize>,
+    /// Lowercase file name and source path → asset
+    asset_keys: HashMap<String, usize>,
+    /// Data-relative paths already taken by the plan
+    taken: HashSet<String>,
+    /// Ids given to planned notes and tasks
+    ids: HashSet<String>,
+}
+
+impl Plan {
+    fn new(format: Format, root: &Path) -> Self {
+        Plan {
+            format,
+            root: root.to_path_buf(),
+            pages: Vec::new(),
+            assets: Vec::new(),
+            tasks: Vec::new(),
+            skipped: Vec::new(),
+            unresolved: Vec::new(),
+            links_rewritten: 0,
+            targets: Vec::new(),
+            page_keys: HashMap::new(),
+            asset_keys: HashMap::new(),
+            taken: HashSet::new(),
+            ids: HashSet::new(),
+        }
+    }
+
+    /// Plan a destination for every file in the vault.
+    fn scan(&mut self, source: &Path, folder: Option<&str>) -> Result<(), String> {
+        let entries = WalkDir::new(source)
+            .sort_by_file_name()
+            .into_iter()
+            .filter_entry(|e| e.depth() == 0 || !is_vault_internal(e.path(), source))
+            .filter_map(Result::ok)
+            .filter(|e| e.file_type().is_file());
+
+        let mut aliases: Vec<(String, usize)> = Vec::new();
+        for entry in entries {
+            let from = relative(entry.path(), source);
+            let is_markdown = entry.path().extension().is_some_and(|ext| ext == "md");
+
+            if !is_markdown {
+                self.plan_asset(entry.path(), &from)?;
+                continue;
+            }
+
+            let content = match fs::read_to_string(entry.path()) {
+                Ok(content) => content,
+                Err(err) => {
+                    self.skip(&from, &format!("unreadable: {}", err));
+                    continue;
+                }
+            };
+            let Some(page) = self.plan_page(&from, &content, folder) else {
+                continue;
+            };
+
+            // Filenames and paths resolve first; titles and aliases after
+            let index = self.pages.len();
+            let path_key = links::normalize_key(&from);
+            let stem = path_key.rsplit('/').next().unwrap_or(&path_key).to_string();
+            for key in [path_key.clone(), stem] {
+                self.page_keys.entry(key).or_insert(index);
+            }
+            aliases.push((links::normalize_key(&page.title), index));
+            for alias in frontmatter::get_string_seq(&page.frontmatter, "aliases") {
+                aliases.push((links::normalize_key(&alias), index));
+            }
+            if self.format == Format::Logseq {
+                aliases.push((page_name(&from).to_lowercase(), index));
+            }
+            self.pages.push(page);
+        }
+
+        for (key, index) in aliases {
+            self.page_keys.entry(key).or_insert(index);
+        }
+        Ok(())
+    }
+
+    fn plan_asset(&mut self, path: &Path, from: &str) -> Result<(), String> {
+        let extension = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(str::to_lowercase)
+            .unwrap_or_default();
+        if !ASSET_EXTENSIONS.contains(&extension.as_str()) {
+            self.skip(from, "unsupported file type");
+            return Ok(());
+        }
+
+        let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("file");
+        let stem = match filesystem::slugify(stem) {
+            slug if slug.is_empty() => "file".to_string(),
+            slug => slug,
+        };
+        let assets = self.root.join("notes").join("assets");
+        let bytes = fs::read(path).map_err(|e| format!("Failed to read {}: {}", from, e))?;
+
+        let mut name = format!("{}.{}", stem, extension);
+        let mut n = 2;
+        let existing = loop {
+            let rel = format!("notes/assets/{}", name);
+            if !self.taken.contains(&rel) {
+                match fs::read(assets.join(&name)) {
+                    Ok(current) if current == bytes => break true,
+                    Ok(_) => {}
+                    Err(_) => break false,
+                }
+            }
+            name = format!("{}-{}.{}", stem, n, extension);
+            n += 1;
+        };
+        self.taken.insert(format!("notes/assets/{}", name));
+
+        let index = self.assets.len();
+        let file_name = from.rsplit('/').next().unwrap_or(from).to_lowercase();
+        self.asset_keys.entry(file_name).or_insert(index);
+        self.asset_keys.entry(from.to_lowercase()).or_insert(index);
+        self.assets.push(Asset {
+            from: from.to_string(),
+            name,
+            existing,
+        });
+        Ok(())
+    }
+
+    /// Destination and normalised frontmatter of a markdown file, or None if skipped
+    fn plan_page(&mut self, from: &str, content: &str, folder: Option<&str>) -> Option<Page> {
+        let parsed = frontmatter::parse(content);
+        if let Some(err) = parsed.error {
+            self.skip(from, &format!("invalid frontmatter: {}", err));
+            return None;
+        }
+        let mut fm = parsed.frontmatter;
+        let mut body = parsed.body;
+        if self.format == Format::Logseq {
+            let (properties, rest) = take_properties(&body);
+            for (key, value) in properties {
+                fm.entry(Value::from(key)).or_insert(value);
+            }
+            body = rest;
+        }
+        normalize_fields(&mut fm);
+
+        let root = self.root.clone();
+        if let Some(date) = journal_date(from, self.format) {
+            let to = format!("daily/{}.md", date);
+            if root.join(&to).exists() || self.taken.contains(&to) {
+                self.skip(from, &format!("daily note {} already exists", date));
+                return None;
+            }
+            let title = date.format("%A, %B %d, %Y").to_string();
+            fm.insert(Value::from("id"), Value::from(format!("daily-{}", date)));
+            fm.insert(Value::from("type"), Value::from("daily"));
+            fm.entry(Value::from("title"))
+                .or_insert_with(|| Value::from(title.clone()));
+            fm.insert(Value::from("date"), Value::from(date.to_string()));
+            frontmatter::ensure_frontmatter(&mut fm, &root.join(&to));
+            self.taken.insert(to.clone());
+            self.ids.insert(format!("daily-{}", date));
+            let title = frontmatter::get_str_or(&fm, "title", &title);
+            return Some(Page {
+                from: from.to_string(),
+                to,
+                title,
+                frontmatter: fm,
+                body,
+            });
+        }
+
+        let name = match self.format {
+            Format::Logseq => page_name(from),
+            Format::Obsidian => file_stem(from).to_string(),
+        };
+        let title = frontmatter::get_str(&fm, "title").unwrap_or_else(|| name.clone());
+        let mut dir: Vec<String> = vec!["notes".to_string()];
+        dir.extend(folder.map(String::from));
+        let mut parts: Vec<&str> = match self.format {
+            // Logseq keeps pages flat; namespaces (`a/b`) become folders
+            Format::Logseq => name.split('/').collect(),
+            Format::Obsidian => from.trim_end_matches(".md").split('/').collect(),
+        };
+        let stem = parts.pop().unwrap_or_default();
+        dir.extend(parts.into_iter().map(folder_name));
+        let to = self.unique_path(&dir.join("/"), &filesystem::slugify(stem));
+
+        // An id from the source vault is kept unless something already uses it
+        if let Some(id) = frontmatter::get_str(&fm, "id") {
+            if !self.id_available(&id) {
+                fm.remove(Value::from("id"));
+            }
+        }
+        fm.insert(Value::from("type"), Value::from("note"));
+        fm.insert(Value::from("title"), Value::from(title.clone()));
+        frontmatter::ensure_frontmatter(&mut fm, &root.join(&to));
+        if let Some(id) = frontmatter::get_str(&fm, "id") {
+            self.ids.insert(id);
+        }
+
+        Some(Page {
+            from: from.to_string(),
+            to,
+            title,
+            frontmatter: fm,
+            body,
+        })
+    }
+
+    /// Whether no indexed document and no planned note or task has `id`.
+    fn id_available(&self, id: &str) -> bool {
+        !self.ids.contains(id) && !index::read(|idx| idx.docs_with_id(id).next().is_some())
+    }
+
+    /// `{dir}/{stem}.md`, suffixed `-2`, `-3`, ... while the path or the id
+    /// derived from it is taken.
+    fn unique_path(&mut self, dir: &str, stem: &str) -> String {
+        let stem = if stem.is_empty() { "untitled" } else { stem };
+        let root = self.root.clone();
+        let available = |path: &str| {
+            !self.taken.contains(path)
+                && !root.join(path).exists()
+                && self.id_available(&frontmatter::derive_id_from_path(&root.join(path)))
+        };
+
+        let mut path = format!("{}/{}.md", dir, stem);
+        let mut n = 2;
+        while !available(&path) {
+            path = format!("{}/{}-{}.md", dir, stem, n);
+            n += 1;
+        }
+        self.taken.insert(path.clone());
+        path
+    }
+
+    /// Point wiki links, embeds and markdown links at the imported notes and assets.
+    fn rewrite_links(&mut self) {
+        let existing = index::read(|idx| Targets::new(idx.docs()));
+        self.targets = self.link_targets();
+
+        for i in 0..self.pages.len() {
+            let (from, to) = (self.pages[i].from.clone(), self.pages[i].to.clone());
+            let mut count = 0;
+            let mut unresolved = Vec::new();
+
+            let body = links::replace_wiki_links(&self.pages[i].body, |link| {
+                if link.target.is_empty() {
+                    return None;
+                }
+                let replacement = if is_asset_name(link.target) {
+                    self.find_asset(&from, link.target)
+                        .map(|asset| wiki_link(link, &asset.name, None))
+                } else {
+                    self.find_page(link.target).map(|page| {
+                        let target = &self.targets[page];
+                        let alias = (target != link.target).then_some(link.target);
+                        wiki_link(link, target, alias)
+                    })
+                };
+                if replacement.is_none() && existing.get(link.target).is_none() {
+                    unresolved.push(link.target.to_string());
+                }
+                let replacement = replacement?;
+                count += 1;
+                Some(replacement)
+            });
+
+            let (body, markdown_count) = links::rewrite_links(&body, |target| {
+                let LinkRef::Markdown(dest) = target else {
+                    return None;
+                };
+                let resolved = links::resolve_path(&from, dest)?;
+                if let Some(asset) = self.find_asset(&from, &resolved) {
+                    return Some(links::relative_path(
+                        &to,
+                        &format!("notes/assets/{}", asset.name),
+                    ));
+                }
+                let page = self.page_keys.get(&links::normalize_key(&resolved))?;
+                let page = &self.pages[*page];
+                (page.from == resolved).then(|| links::relative_path(&to, &page.to))
+            });
+
+            self.pages[i].body = body;
+            self.links_rewritten += count + markdown_count;
+            self.unresolved
+                .extend(unresolved.into_iter().map(|target| UnresolvedLink {
+                    file: from.clone(),
+                    target,
+                }));
+        }
+    }
+
+    /// How links name each imported page: its title if nothing else in the
+    /// vault answers to it, else its data-relative path.
+    fn link_targets(&self) -> Vec<String> {
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        index::read(|idx| {
+            for doc in idx.docs() {
+                for key in links::target_keys(doc) {
+                    *counts.entry(key).or_default() += 1;
+                }
+            }
+        });
+        for page in &self.pages {
+            let path = page.to.trim_end_matches(".md");
+            let mut keys = vec![
+                links::normalize_key(&page.title),
+                links::normalize_key(path.rsplit('/').next().unwrap_or(path)),
+                links::normalize_key(path),
+            ];
+            keys.extend(
+                frontmatter::get_str(&page.frontmatter, "id").map(|id| links::normalize_key(&id)),
+            );
+            keys.sort();
+            keys.dedup();
+            for key in keys {
+                *counts.entry(key).or_default() += 1;
+            }
+        }
+
+        self.pages
+            .iter()
+            .map(|page| {
+                let unique = counts.get(&links::normalize_key(&page.title)) == Some(&1);
+                if unique && !page.title.contains(['[', ']', '|', '#']) {
+                    page.title.clone()
+                } else {
+                    page.to.trim_end_matches(".md").to_string()
+                }
+            })
+            .collect()
+    }
+
+    fn find_page(&self, target: &str) -> Option<usize> {
+        let key = links::normalize_key(target);
+        self.page_keys.get(&key).copied()
+    }
+
+    /// An attachment by source path (relative to the vault root or to
+    /// `from`) or, as Obsidian resolves them, by file name.
+    fn find_asset(&self, from: &str, target: &str) -> Option<&Asset> {
+        let relative = links::resolve_path(from, target)
+            .unwrap_or_default()
+            .to_lowercase();
+        let target = target.trim_start_matches('/').to_lowercase();
+        let name = target.rsplit('/').next().unwrap_or(&target);
+        let index = [target.as_str(), relative.as_str(), name]
+            .into_iter()
+            .find_map(|key| self.asset_keys.get(key).copied())?;
+        Some(&self.assets[index])
+    }
+
+    /// Move checklist items out of the notes into task files of `project`,
+    /// leaving a link to each task in its place.
+    fn extract_tasks(&mut self, project: &str) {
+        let root = self.root.clone();
+        for i in 0..self.pages.len() {
+            let note_target = self.targets[i].clone();
+            let page = &self.pages[i];
+            let (from, note_title) = (page.from.clone(), page.title.clone());
+
+            let mut found = Vec::new();
+            let body = replace_task_lines(&page.body, self.format, |item| {
+                found.push(item.clone());
+                found.len() - 1
+            });
+            let mut body = body;
+
+            for (n, item) in found.into_iter().enumerate() {
+                let stem = format!("task-{}", filesystem::slugify(&item.title));
+                let mut name = stem.clone();
+                let mut suffix = 2;
+                let (to, id) = loop {
+                    let to = format!("projects/{}/tasks/{}.md", project, name);
+                    let id = format!("{}-{}", project, name);
+                    if !self.taken.contains(&to)
+                        && !root.join(&to).exists()
+                        && self.id_available(&id)
+                    {
+                        break (to, id);
+                    }
+                    name = format!("{}-{}", stem, suffix);
+                    suffix += 1;
+                };
+                self.taken.insert(to.clone());
+                self.ids.insert(id.clone());
+
+                let link = format!("{}- [[{}|{}]]", item.indent, id, item.title);
+                body = body.replacen(&task_placeholder(n), &link, 1);
+                self.tasks.push(Task {
+                    from: from.clone(),
+                    to,
+                    id,
+                    item,
+                    note_target: note_target.clone(),
+                    note_title: note_title.clone(),
+                });
+            }
+            self.pages[i].body = body;
+        }
+    }
+
+    fn report(&self, source: &Path) -> ImportReport {
+        ImportReport {
+            source: source.display().to_string(),
+            format: match self.format {
+                Format::Obsidian => "obsidian",
+                Format::Logseq => "logseq",
+            }
+            .to_string(),
+            applied: false,
+            notes: self
+                .pages
+                .iter()
+                .map(|p| ImportedFile {
+                    from: p.from.clone(),
+                    to: p.to.clone(),
+                })
+                .collect(),
+            assets: self
+                .assets
+                .iter()
+                .map(|a| ImportedFile {
+                    from: a.from.clone(),
+                    to: format!("notes/assets/{}", a.name),
+                })
+                .collect(),
+            tasks: self
+                .tasks
+                .iter()
+                .map(|t| ImportedTask {
+                    from: t.from.clone(),
+                    to: t.to.clone(),
+                    title: t.item.title.clone(),
+                    completed: t.item.completed,
+                })
+                .collect(),
+            links_rewritten: self.links_rewritten,
+            unresolved: self.unresolved.clone(),
+            skipped: self.skipped.clone(),
+            commit: None,
+        }
+    }
+
+    fn write(&self, source: &Path) -> Result<(), String> {
+        let root = self.root.clone();
+
+        let assets = root.join("notes").join("assets");
+        if !self.assets.is_empty() {
+            fs::create_dir_all(&assets).map_err(|e| e.to_string())?;
+        }
+        for asset in self.assets.iter().filter(|a| !a.existing) {
+            fs::copy(source.join(&asset.from), assets.join(&asset.name))
+                .map_err(|e| format!("Failed to copy {}: {}", asset.from, e))?;
+        }
+
+        for page in &self.pages {
+            let content = frontmatter::serialize_frontmatter(&page.frontmatter, &page.body)?;
+            write_file(&root.join(&page.to), &content)?;
+        }
+
+        let now = Utc::now().to_rfc3339();
+        for task in &self.tasks {
+            let item = &task.item;
+            let project = task.to.split('/').nth(1).unwrap_or_default();
+            let mut fm = Mapping::new();
+            fm.insert(Value::from("id"), Value::from(task.id.clone()));
+            fm.insert(Value::from("type"), Value::from("task"));
+            fm.insert(Value::from("title"), Value::from(item.title.clone()));
+            fm.insert(Value::from("completed"), Value::from(item.completed));
+            let section = if item.completed {
+                "Completed"
+            } else {
+                "Active"
+            };
+            fm.insert(Value::from("section"), Value::from(section));
+            let priority = item.priority.as_deref().unwrap_or("normal");
+            fm.insert(Value::from("priority"), Value::from(priority));
+            if let Some(due) = &item.due_date {
+                fm.insert(Value::from("due_date"), Value::from(due.clone()));
+            }
+            fm.insert(Value::from("is_active"), Value::from(true));
+            fm.insert(Value::from("project_id"), Value::from(project));
+            fm.insert(Value::from("created"), Value::from(now.clone()));
+            fm.insert(Value::from("updated"), Value::from(now.clone()));
+
+            let note_link = if task.note_target == task.note_title {
+                task.note_target.clone()
+            } else {
+                format!("{}|{}", task.note_target, task.note_title)
+            };
+            let body = format!("# {}\n\nFrom [[{}]]\n", item.title, note_link);
+            let content = frontmatter::serialize_frontmatter(&fm, &body)?;
+            write_file(&root.join(&task.to), &content)?;
+        }
+
+        tracing::info!(
+            "Imported {} notes, {} assets and {} tasks from {}",
+            self.pages.len(),
+            self.assets.len(),
+            self.tasks.len(),
+            source.display()
+        );
+        Ok(())
+    }
+
+    /// Data-relative paths of every file `write` creates.
+    fn written(&self) -> Vec<String> {
+        let assets = self
+            .assets
+            .iter()
+            .filter(|a| !a.existing)
+            .map(|a| format!("notes/assets/{}", a.name));
+        let pages = self.pages.iter().map(|p| p.to.clone());
+        let tasks = self.tasks.iter().map(|t| t.to.clone());
+        assets.chain(pages).chain(tasks).collect()
+    }
+
+    fn skip(&mut self, path: &str, reason: &str) {
+        self.skipped.push(SkippedFile {
+            path: path.to_string(),
+            reason: reason.to_string(),
+        });
+    }
+}
+
+fn write_file(path: &Path, content: &str) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    filesystem::atomic_write(path, content.as_bytes())
+}
+
+/// Folders the vault app keeps for itself (settings, trash, backups)
+fn is_vault_internal(path: &Path, source: &Path) -> bool {
+    let name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or_default();
+    name.starts_with('.') || (path.parent() == Some(source) && name == "logseq")
+}
+
+fn relative(path: &Path, root: &Path) -> String {
+    let rel: PathBuf = path.strip_prefix(root).unwrap_or(path).to_path_buf();
+    rel.components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// A folder name as Ironpad accepts it (see `folders::validate_folder`)
+fn folder_name(name: &str) -> String {
+    let name = name.trim();
+    if name.is_empty() || name.starts_with('.') || name.eq_ignore_ascii_case("assets") {
+        format!(
+            "{}-notes",
+            filesystem::slugify(name).trim_start_matches('-')
+        )
+    } else {
+        name.to_string()
+    }
+}
+
+/// The page name a file stands for: its stem, with Logseq's namespace
+/// encoding (`a___b.md`, `a%2Fb.md`) decoded to `a/b`.
+fn page_name(from: &str) -> String {
+    file_stem(from)
+        .replace("___", "/")
+        .replace("%2F", "/")
+        .replace("%2f", "/")
+}
+
+fn file_stem(path: &str) -> &str {
+    let file = path.rsplit('/').next().unwrap_or(path);
+    file.strip_suffix(".md").unwrap_or(file)
+}
+
+/// Date of a Logseq journal (`journals/2026_01_31.md`)
+fn journal_date(from: &str, format: Format) -> Option<NaiveDate> {
+    let stem = from.strip_prefix("journals/")?.strip_suffix(".md")?;
+    if format != Format::Logseq || stem.contains('/') {
+        return None;
+    }
+    NaiveDate::parse_from_str(stem, "%Y_%m_%d")
+        .or_else(|_| NaiveDate::parse_from_str(stem, "%Y-%m-%d"))
+        .ok()
+}
+
+/// Leading `key:: value` lines (Logseq page properties), taken out of the body.
+fn take_properties(body: &str) -> (Vec<(String, Value)>, String) {
+    let mut properties = Vec::new();
+    let mut rest = body;
+
+    while let Some(line) = rest.split_inclusive('\n').next() {
+        let Some((key, value)) = line.trim_end().split_once(":: ") else {
+            break;
+        };
+        if key.is_empty()
+            || !key
+                .chars()
+                .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+        {
+            break;
+        }
+        let key = key.to_lowercase();
+        let value = match key.as_str() {
+            "tags" | "alias" | "aliases" => Value::from(
+                value
+                    .split(',')
+                    .map(|v| v.trim().trim_start_matches('#'))
+                    .map(|v| {
+                        v.trim_start_matches("[[")
+                            .trim_end_matches("]]")
+                            .to_string()
+                    })
+                    .filter(|v| !v.is_empty())
+                    .collect::<Vec<_>>(),
+            ),
+            _ => Value::from(value.trim()),
+        };
+        let key = if key == "alias" {
+            "aliases".to_string()
+        } else {
+            key
+        };
+        properties.push((key, value));
+        rest = &rest[line.len()..];
+    }
+
+    (properties, rest.trim_start_matches('\n').to_string())
+}
+
+/// Tags as a list of normalised tags, aliases as a list (both may be written
+/// as a single comma- or space-separated string).
+fn normalize_fields(fm: &mut Mapping) {
+    if let Some(alias) = fm.remove(Value::from("alias")) {
+        fm.entry(Value::from("aliases")).or_insert(alias);
+    }
+    for key in ["tags", "aliases"] {
+        let Some(value) = fm.get(Value::from(key)) else {
+            continue;
+        };
+        let items: Vec<String> = match value {
+            Value::String(s) if key == "tags" => s.split([',', ' ']).map(String::from).collect(),
+            Value::String(s) => s.split(',').map(String::from).collect(),
+            Value::Sequence(seq) => seq
+                .iter()
+                .filter_map(|v| v.as_str().map(String::from))
+                .collect(),
+            _ => continue,
+        };
+        let mut items: Vec<String> = items
+            .iter()
+            .map(|item| match key {
+                "tags" => tags::normalize(item),
+                _ => item.trim().to_string(),
+            })
+            .filter(|item| !item.is_empty())
+            .collect();
+        items.dedup();
+        fm.insert(Value::from(key), Value::from(items));
+    }
+}
+
+fn is_asset_name(target: &str) -> bool {
+    target
+        .rsplit_once('.')
+        .is_some_and(|(_, ext)| ASSET_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+}
+
+/// A wiki link like `link` (embed, heading and alias kept) pointing at
+/// `target`, with `alias` used when the link had none.
+fn wiki_link(link: &WikiLink, target: &str, alias: Option<&str>) -> String {
+    let mut out = String::new();
+    if link.embed {
+        out.push('!');
+    }
+    out.push_str("[[");
+    out.push_str(target);
+    if let Some(heading) = link.heading {
+        out.push('#');
+        out.push_str(heading);
+    }
+    if let Some(alias) = link.alias.or(alias) {
+        out.push('|');
+        out.push_str(alias);
+    }
+    out.push_str("]]");
+    out
+}
+
+fn task_placeholder(n: usize) -> String {
+    format!("\u{0}task-{}\u{0}", n)
+}
+
+/// Replace each checklist item outside fenced code blocks with a
+/// placeholder (see `task_placeholder`) for the number `found` returns.
+/// A Logseq `DEADLINE: <date>` line under an item becomes its due date.
+fn replace_task_lines(
+    body: &str,
+    format: Format,
+    mut found: impl FnMut(&TaskLine) -> usize,
+) -> String {
+    let mut output = String::with_capacity(body.len());
+    let mut in_code_block = false;
+    let mut lines = body.split_inclusive('\n').peekable();
+
+    while let Some(line) = lines.next() {
+        if links::toggles_code_block(line) {
+            in_code_block = !in_code_block;
+        }
+        let item = (!in_code_block)
+            .then(|| parse_task_line(line.trim_end_matches(['\r', '\n']), format))
+            .flatten();
+        let Some(mut item) = item else {
+            output.push_str(line);
+            continue;
+        };
+
+        if format == Format::Logseq {
+            let deadline = lines
+                .peek()
+                .and_then(|next| next.trim().strip_prefix("DEADLINE: <"))
+                .and_then(|date| date.get(..10))
+                .filter(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d").is_ok())
+                .map(String::from);
+            if let Some(date) = deadline {
+                item.due_date.get_or_insert(date);
+                lines.next();
+            }
+        }
+
+        output.push_str(&task_placeholder(found(&item)));
+        if line.ends_with('\n') {
+            output.push('\n');
+        }
+    }
+
+    output
+}
+
+/// An Obsidian `- [ ] item` / `- [x] item` or Logseq `- TODO item` /
+/// `- DONE item`. Obsidian Tasks due dates (`📅 2026-01-31`) and priorities
+/// (`⏫`, `🔽`) and Logseq priorities (`[#A]`) are taken out of the title.
+fn parse_task_line(line: &str, format: Format) -> Option<TaskLine> {
+    let trimmed = line.trim_start();
+    let indent = &line[..line.len() - trimmed.len()];
+
+    let (completed, rest) = match format {
+        Format::Obsidian => {
+            let item = ["- ", "* ", "+ "]
+                .iter()
+                .find_map(|marker| trimmed.strip_prefix(marker))?;
+            if let Some(rest) = item.strip_prefix("[ ] ") {
+                (false, rest)
+            } else {
+                let rest = item
+                    .strip_prefix("[x] ")
+                    .or_else(|| item.strip_prefix("[X] "))?;
+                (true, rest)
+            }
+        }
+        Format::Logseq => {
+            let (marker, rest) = trimmed.strip_prefix("- ")?.split_once(' ')?;
+            match marker {
+                "TODO" | "DOING" | "NOW" | "LATER" | "WAITING" => (false, rest),
+                "DONE" => (true, rest),
+                _ => return None,
+            }
+        }
+    };
+
+    let mut title = Vec::new();
+    let mut due_date = None;
+    let mut priority = None;
+    let mut tokens = rest.split_whitespace().peekable();
+    while let Some(token) = tokens.next() {
+        let is_date = |t: &&str| NaiveDate::parse_from_str(t, "%Y-%m-%d").is_ok();
+        match token {
+            "📅" if tokens.peek().is_some_and(is_date) => {
+                due_date = tokens.next().map(String::from);
+            }
+            "⏳" | "🛫" | "✅" | "➕" if tokens.peek().is_some_and(is_date) => {
+                tokens.next();
+            }
+            "🔺" | "⏫" | "[#A]" => priority = Some("high".to_string()),
+            "🔼" | "[#B]" => priority = Some("normal".to_string()),
+            "🔽" | "⏬" | "[#C]" => priority = Some("low".to_string()),
+            token => title.push(token),
+        }
+    }
+    if title.is_empty() {
+        return None;
+    }
+
+    // Links in the title keep their label (the task file links back instead)
+    let title = links::replace_wiki_links(&title.join(" "), |link| Some(link.label()));
+
+    Some(TaskLine {
+        indent: indent.to_string(),
+        title,
+        completed,
+        due_date,
+        priority,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_task_line() {
+        assert_eq!(
+            parse_task_line(
+                "  - [ ] Call [[Bob|bob]] ⏫ 📅 2026-02-01",
+                Format::Obsidian
+            ),
+            Some(TaskLine {
+                indent: "  ".to_string(),
+                title: "Call bob".to_string(),
+                completed: false,
+                due_date: Some("2026-02-01".to_string()),
+                priority: Some("high".to_string()),
+            })
+        );
+        let done = parse_task_line("- [x] Ship it ✅ 2026-01-03", Format::Obsidian).unwrap();
+        assert!(done.completed);
+        assert_eq!(done.title, "Ship it");
+
+        let todo = parse_task_line("- TODO [#C] Tidy up", Format::Logseq).unwrap();
+        assert_eq!((todo.title.as_str(), todo.completed), ("Tidy up", false));
+        assert_eq!(todo.priority.as_deref(), Some("low"));
+        assert!(
+            parse_task_line("- DONE Done", Format::Logseq)
+                .unwrap()
+                .completed
+        );
+
+        assert_eq!(parse_task_line("- [ ] ", Format::Obsidian), None);
+        assert_eq!(parse_task_line("- TODO later", Format::Obsidian), None);
+        assert_eq!(parse_task_line("- [ ] box", Format::Logseq), None);
+        assert_eq!(parse_task_line("- CANCELED gone", Format::Logseq), None);
+    }
+
+    #[test]
+    fn test_replace_task_lines() {
+        let body =
+            "- TODO Renew cert\n  DEADLINE: <2026-03-01 Sun>\n- note\n```\n- TODO code\n```\n";
+        let mut items = Vec::new();
+        let output = replace_task_lines(body, Format::Logseq, |item| {
+            items.push(item.clone());
+            items.len() - 1
+        });
+        assert_eq!(
+            output,
+            format!("{}\n- note\n```\n- TODO code\n```\n", task_placeholder(0))
+        );
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].due_date.as_deref(), Some("2026-03-01"));
+    }
+
+    #[test]
+    fn test_logseq_pages() {
+        let (properties, body) =
+            take_properties("title:: Rust Notes\ntags:: [[rust]], #lang\n\n- first\n");
+        assert_eq!(
+            properties,
+            [
+                ("title".to_string(), Value::from("Rust Notes")),
+                ("tags".to_string(), Value::from(vec!["rust", "lang"])),
+            ]
+        );
+        assert_eq!(body, "- first\n");
+        assert_eq!(take_properties("- a:: b\n").0, []);
+
+        assert_eq!(page_name("pages/lang___rust.md"), "lang/rust");
+        assert_eq!(page_name("pages/lang%2Frust.md"), "lang/rust");
+        assert_eq!(
+            journal_date("journals/2026_01_31.md", Format::Logseq),
+            NaiveDate::from_ymd_opt(2026, 1, 31)
+        );
+        assert_eq!(
+            journal_date("journals/2026_01_31.md", Format::Obsidian),
+            None
+        );
+    }
+
+    #[test]
+    fn test_planned_ids_are_unique() {
+        let mut plan = Plan::new(Format::Obsidian, Path::new("data"));
+        let id = |page: Option<Page>| frontmatter::get_str(&page.unwrap().frontmatter, "id");
+
+        let kept = id(plan.plan_page("a.md", "---\nid: zq-import\n---\n", None));
+        let derived = id(plan.plan_page("zq-import.md", "Body\n", None));
+        let repeated = id(plan.plan_page("b.md", "---\nid: zq-import\n---\n", None));
+
+        assert_eq!(kept.as_deref(), Some("zq-import"));
+        assert_eq!(derived.as_deref(), Some("zq-import-2"));
+        assert_ne!(repeated, kept);
+        assert_ne!(repeated, derived);
+    }
+
+    #[test]
+    fn test_normalize_fields() {
+        let mut fm: Mapping =
+            serde_yaml::from_str("tags: \"#Work, ideas\"\nalias: Old name").unwrap();
+        normalize_fields(&mut fm);
+        assert_eq!(frontmatter::get_string_seq(&fm, "tags"), ["work", "ideas"]);
+        assert_eq!(frontmatter::get_string_seq(&fm, "aliases"), ["Old name"]);
+    }
+}
@@ -0,0 +1,504 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+use std::time::Instant;
+
+use serde_yaml::Mapping;
+use walkdir::WalkDir;
+
+use crate::config;
+use crate::services::filesystem;
+use crate::services::frontmatter;
+use crate::services::links::{self, Link};
+use crate::services::matcher;
+use crate::services::tags;
+
+/// A single markdown file held in the search index.
+#[derive(Debug, Clone)]
+pub struct IndexedDoc {
+    /// Path relative to the data directory (e.g. `notes/foo.md`)
+    pub path: String,
+    pub id: String,
+    /// Frontmatter title, falling back to the filename
+    pub title: String,
+    /// note, task, project, daily (or any custom frontmatter `type`)
+    pub doc_type: String,
+    pub frontmatter: Mapping,
+    /// Why the frontmatter couldn't be parsed, if it couldn't
+    pub frontmatter_error: Option<String>,
+    /// Full file content, including frontmatter, so line numbers match the file
+    pub content: String,
+    pub updated: Option<String>,
+    /// Outgoing wiki and markdown links
+    pub links: Vec<Link>,
+    /// Frontmatter and inline `#tags`, normalised (see `tags::doc_tags`)
+    pub tags: Vec<String>,
+    /// Term frequencies, kept so the document can be removed from the postings
+    terms: HashMap<String, u32>,
+    /// Total number of terms (for length normalisation when ranking)
+    length: u32,
+}
+
+impl IndexedDoc {
+    /// Owning project, from frontmatter or from the `projects/{id}/...` path.
+    pub fn project_id(&self) -> Option<String> {
+        frontmatter::get_str(&self.frontmatter, "project_id").or_else(|| {
+            let mut parts = self.path.split('/');
+            match (parts.next(), parts.next()) {
+                (Some("projects"), Some(id)) => Some(id.to_string()),
+                _ => None,
+            }
+        })
+    }
+}
+
+/// In-memory inverted index over every markdown file in the data directory.
+/// Terms are kept sorted so prefix lookups are a simple range scan.
+/// It doubles as the id → path registry, so lookups never have to walk the tree.
+#[derive(Debug, Default)]
+pub struct SearchIndex {
+    docs: HashMap<String, IndexedDoc>,
+    /// Paths of the documents with each id (ids are only unique per folder)
+    ids: HashMap<String, BTreeSet<String>>,
+    /// Paths of the documents linking to each link key (see `links::target_keys`)
+    linked_from: HashMap<String, BTreeSet<String>>,
+    postings: BTreeMap<String, HashMap<String, u32>>,
+    total_length: u64,
+    /// Bumped on every change, so derived caches know when to rebuild
+    generation: u64,
+}
+
+/// BM25 parameters
+const BM25_K1: f64 = 1.2;
+const BM25_B: f64 = 0.75;
+
+/// Terms that only share a prefix with the query token count for less
+const PREFIX_MATCH_WEIGHT: f64 = 0.5;
+
+impl SearchIndex {
+    /// Add or replace a document.
+    pub fn insert(&mut self, abs_path: &Path, content: &str) {
+        let path = filesystem::normalize_path(abs_path);
+        self.remove(&path);
+        self.generation += 1;
+
+        let frontmatter::Parsed {
+            frontmatter: fm,
+            body,
+            error: frontmatter_error,
+            ..
+        } = frontmatter::parse(content);
+        let id = frontmatter::get_str(&fm, "id")
+            .unwrap_or_else(|| frontmatter::derive_id_from_path(abs_path));
+        let title = frontmatter::get_str(&fm, "title").unwrap_or_else(|| {
+            abs_path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or("Untitled")
+                .to_string()
+        });
+        let doc_type =
+            frontmatter::get_str(&fm, "type").unwrap_or_else(|| detect_doc_type(&path).to_string());
+        let updated = frontmatter::get_str(&fm, "updated");
+        let tags = tags::doc_tags(&fm, &body);
+
+        let mut terms: HashMap<String, u32> = HashMap::new();
+        for term in tokenize(content) {
+            *terms.entry(term).or_insert(0) += 1;
+        }
+
+        let length = terms.values().sum();
+        self.total_length += length as u64;
+
+        for (term, count) in &terms {
+            self.postings
+                .entry(term.clone())
+                .or_default()
+                .insert(path.clone(), *count);
+        }
+
+        self.ids.entry(id.clone()).or_default().insert(path.clone());
+
+        let links = links::parse_links(&path, content);
+        for link in &links {
+            self.linked_from
+                .entry(link.key.clone())
+                .or_default()
+                .insert(path.clone());
+        }
+
+        self.docs.insert(
+            path.clone(),
+            IndexedDoc {
+                path,
+                id,
+                title,
+                doc_type,
+                frontmatter: fm,
+                frontmatter_error,
+                content: content.to_string(),
+                updated,
+                links,
+                tags,
+                terms,
+                length,
+            },
+        );
+    }
+
+    /// Remove a document by its data-relative path.
+    pub fn remove(&mut self, path: &str) {
+        let Some(doc) = self.docs.remove(path) else {
+            return;
+        };
+
+        self.total_length -= doc.length as u64;
+        self.generation += 1;
+
+        if let Some(paths) = self.ids.get_mut(&doc.id) {
+            paths.remove(path);
+            if paths.is_empty() {
+                self.ids.remove(&doc.id);
+            }
+        }
+
+        for link in &doc.links {
+            if let Some(sources) = self.linked_from.get_mut(&link.key) {
+                sources.remove(path);
+                if sources.is_empty() {
+                    self.linked_from.remove(&link.key);
+                }
+            }
+        }
+
+        for term in doc.terms.keys() {
+            if let Some(posting) = self.postings.get_mut(term) {
+                posting.remove(path);
+                if posting.is_empty() {
+                    self.postings.remove(term);
+                }
+            }
+        }
+    }
+
+    pub fn get(&self, path: &str) -> Option<&IndexedDoc> {
+        self.docs.get(path)
+    }
+
+    /// All documents with this id, in path order.
+    pub fn docs_with_id(&self, id: &str) -> impl Iterator<Item = &IndexedDoc> {
+        self.ids
+            .get(id)
+            .into_iter()
+            .flatten()
+            .filter_map(|path| self.docs.get(path))
+    }
+
+    /// Documents with a link to this key, in path order.
+    pub fn docs_linking_to(&self, key: &str) -> impl Iterator<Item = &IndexedDoc> {
+        self.linked_from
+            .get(key)
+            .into_iter()
+            .flatten()
+            .filter_map(|path| self.docs.get(path))
+    }
+
+    pub fn docs(&self) -> impl Iterator<Item = &IndexedDoc> {
+        self.docs.values()
+    }
+
+    pub fn len(&self) -> usize {
+        self.docs.len()
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Paths of all documents containing exactly this term, with its frequency.
+    pub fn postings(&self, term: &str) -> Option<&HashMap<String, u32>> {
+        self.postings.get(term)
+    }
+
+    /// Paths of all documents containing a term that starts with `prefix`.
+    pub fn docs_with_prefix(&self, prefix: &str) -> HashSet<&str> {
+        self.postings
+            .range(prefix.to_string()..)
+            .take_while(|(term, _)| term.starts_with(prefix))
+            .flat_map(|(_, docs)| docs.keys().map(String::as_str))
+            .collect()
+    }
+
+    /// Paths of all documents containing a term that contains `infix`.
+    pub fn docs_with_infix(&self, infix: &str) -> HashSet<&str> {
+        self.postings
+            .iter()
+            .filter(|(term, _)| term.contains(infix))
+            .flat_map(|(_, docs)| docs.keys().map(String::as_str))
+            .collect()
+    }
+
+    /// BM25 relevance of every document containing at least one token.
+    /// Tokens match index terms by prefix; exact term matches weigh more.
+    pub fn bm25(&self, tokens: &[String]) -> HashMap<&str, f64> {
+        let mut scores: HashMap<&str, f64> = HashMap::new();
+        if self.docs.is_empty() {
+            return scores;
+        }
+
+        let n = self.docs.len() as f64;
+        let avg_length = (self.total_length as f64 / n).max(1.0);
+
+        for token in tokens {
+            for (term, posting) in self
+                .postings
+                .range(token.clone()..)
+                .take_while(|(term, _)| term.starts_with(token.as_str()))
+            {
+                let df = posting.len() as f64;
+                let idf = ((n - df + 0.5) / (df + 0.5) + 1.0).ln();
+                let weight = if term == token {
+                    1.0
+                } else {
+                    PREFIX_MATCH_WEIGHT
+                };
+
+                for (path, tf) in posting {
+                    let Some(doc) = self.docs.get(path) else {
+                        continue;
+                    };
+                    let tf = *tf as f64;
+                    let norm = 1.0 - BM25_B + BM25_B * (doc.length as f64 / avg_length);
+                    let score = idf * tf * (BM25_K1 + 1.0) / (tf + BM25_K1 * norm);
+                    *scores.entry(doc.path.as_str()).or_insert(0.0) += score * weight;
+                }
+            }
+        }
+
+        scores
+    }
+
+    /// Documents that may contain `text` as a substring. Every token of `text`
+    /// must start an indexed term, except a leading one, which may begin
+    /// mid-word ("gres" in "postgres") and so only has to occur in a term.
+    /// Text without tokens matches every document.
+    pub fn candidates(&self, text: &str) -> Vec<&IndexedDoc> {
+        let starts_mid_word = text.starts_with(char::is_alphanumeric);
+        let mut matching: Option<HashSet<&str>> = None;
+
+        for (i, token) in tokenize(text).enumerate() {
+            let docs = if i == 0 && starts_mid_word {
+                self.docs_with_infix(&token)
+            } else {
+                self.docs_with_prefix(&token)
+            };
+            matching = Some(match matching {
+                Some(current) => current.intersection(&docs).copied().collect(),
+                None => docs,
+            });
+
+            if matching.as_ref().is_some_and(|m| m.is_empty()) {
+                return Vec::new();
+            }
+        }
+
+        match matching {
+            Some(paths) => paths.into_iter().filter_map(|p| self.docs.get(p)).collect(),
+            None => self.docs.values().collect(),
+        }
+    }
+}
+
+lazy_static::lazy_static! {
+    static ref INDEX: RwLock<SearchIndex> = RwLock::new(SearchIndex::default());
+}
+
+/// Split text into lowercase alphanumeric terms.
+/// Diacritics are folded so the index can serve accent-insensitive searches;
+/// exact matching is left to `services::matcher`.
+pub fn tokenize(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|t| !t.is_empty())
+        .map(|t| matcher::fold_diacritics(&t.to_lowercase()))
+}
+
+/// Run a closure with read access to the shared index.
+pub fn read<R>(f: impl FnOnce(&SearchIndex) -> R) -> R {
+    let index = INDEX.read().unwrap_or_else(|e| e.into_inner());
+    f(&index)
+}
+
+/// Build the index from scratch by walking the data directory.
+/// Call once at startup; afterwards the index is kept current incrementally.
+pub fn build() {
+    let started = Instant::now();
+    let mut index = SearchIndex::default();
+
+    for entry in WalkDir::new(config::data_dir())
+        .into_iter()
+        .filter_entry(|e| !is_ignored(e.path()))
+        .filter_map(Result::ok)
+    {
+        let path = entry.path();
+        if !is_indexable(path) {
+            continue;
+        }
+
+        match fs::read_to_string(path) {
+            Ok(content) => index.insert(path, &content),
+            Err(err) => tracing::warn!("Skipping {:?} from search index: {}", path, err),
+        }
+    }
+
+    let count = index.len();
+    *INDEX.write().unwrap_or_else(|e| e.into_inner()) = index;
+
+    tracing::info!(
+        "Search index built: {} files in {} ms",
+        count,
+        started.elapsed().as_millis()
+    );
+}
+
+/// Absolute path of the first existing document with this id accepted by
+/// `filter` (which is given the absolute path). Entries for files that are
+/// gone are dropped on the way. Replaces walking the data directory.
+pub fn find_path(id: &str, filter: impl Fn(&Path) -> bool) -> Option<PathBuf> {
+    let root = config::data_dir();
+    let paths: Vec<PathBuf> = read(|idx| {
+        idx.docs_with_id(id)
+            .map(|doc| root.join(&doc.path))
+            .filter(|path| filter(path))
+            .collect()
+    });
+
+    for path in paths {
+        if path.exists() {
+            return Some(path);
+        }
+        // The watcher may not have caught up with a file removed behind our back
+        remove_file(&path);
+    }
+
+    None
+}
+
+/// Index content that was just written to `path`.
+pub fn index_content(path: &Path, content: &str) {
+    if !is_indexable(path) {
+        return;
+    }
+
+    let mut index = INDEX.write().unwrap_or_else(|e| e.into_inner());
+    index.insert(path, content);
+}
+
+/// Re-read a file from disk, or drop it from the index if it no longer exists
+/// (or was moved somewhere that isn't indexed, such as the archive).
+pub fn refresh_file(path: &Path) {
+    if is_indexable(path) && path.exists() {
+        match fs::read_to_string(path) {
+            Ok(content) => index_content(path, &content),
+            Err(err) => tracing::warn!("Failed to re-index {:?}: {}", path, err),
+        }
+    } else {
+        remove_file(path);
+    }
+}
+
+/// Remove a file from the index.
+pub fn remove_file(path: &Path) {
+    let normalized = filesystem::normalize_path(path);
+    let mut index = INDEX.write().unwrap_or_else(|e| e.into_inner());
+    index.remove(&normalized);
+}
+
+/// Markdown files outside of .git, assets, archive, templates and views are indexed.
+fn is_indexable(path: &Path) -> bool {
+    if path.extension().and_then(|s| s.to_str()) != Some("md") {
+        return false;
+    }
+
+    // Skip temp files from atomic writes
+    if path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .is_some_and(|n| n.starts_with('.'))
+    {
+        return false;
+    }
+
+    // Templates and saved views aren't documents (placeholders and stored
+    // queries would pollute search)
+    let relative = path.strip_prefix(config::data_dir()).unwrap_or(path);
+    !is_ignored(relative) && !relative.starts_with("templates") && !relative.starts_with("views")
+}
+
+fn is_ignored(path: &Path) -> bool {
+    path.components().any(|c| {
+        matches!(
+            c.as_os_str().to_str(),
+            Some(".git") | Some("assets") | Some("archive")
+        )
+    })
+}
+
+/// Infer the document type from its location when frontmatter has no `type`.
+pub fn detect_doc_type(path: &str) -> &'static str {
+    let parts: Vec<&str> = path.split('/').collect();
+    match parts.as_slice() {
+        ["daily", ..] => "daily",
+        ["views", ..] => "view",
+        ["projects", _, "tasks", ..] => "task",
+        ["projects", _, "index.md"] => "project",
+        _ => "note",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokenize() {
+        let terms: Vec<String> = tokenize("Hello, World! foo-bar_baz 42 Café").collect();
+        assert_eq!(
+            terms,
+            vec!["hello", "world", "foo", "bar", "baz", "42", "cafe"]
+        );
+    }
+
+    #[test]
+    fn test_insert_and_remove() {
+        let mut index = SearchIndex::default();
+        index.insert(Path::new("data/notes/alpha.md"), "Postgres tuning notes");
+        index.insert(Path::new("data/notes/beta.md"), "Postgres backups");
+
+        assert_eq!(index.len(), 2);
+        assert_eq!(index.candidates("postgres").len(), 2);
+        assert_eq!(index.candidates("tun").len(), 1);
+        assert_eq!(index.candidates("postgres backup").len(), 1);
+
+        assert_eq!(index.docs_with_id("beta").count(), 1);
+        index.remove("notes/beta.md");
+        assert_eq!(index.len(), 1);
+        assert_eq!(index.docs_with_id("beta").count(), 0);
+        assert!(index.docs_with_prefix("backups").is_empty());
+    }
+
+    #[test]
+    fn test_candidates_match_inside_words() {
+        let mut index = SearchIndex::default();
+        index.insert(Path::new("data/notes/alpha.md"), "Postgres tuning xa.b");
+        index.insert(Path::new("data/notes/beta.md"), "Backups of a.b");
+
+        assert_eq!(index.candidates("gres").len(), 1);
+        assert_eq!(index.candidates("a.b").len(), 2);
+        // A leading separator anchors the first token at a word start
+        assert_eq!(index.candidates(".b").len(), 2);
+        assert_eq!(index.candidates("uning").len(), 1);
+        assert_eq!(index.candidates("s tun").len(), 1);
+        assert!(index.candidates("gres backups").is_empty());
+    }
+}
@@ -0,0 +1,530 @@
+use std::collections::BTreeMap;
+use std::ops::Range;
+
+use serde::Serialize;
+
+use crate::services::index::{self, IndexedDoc};
+
+/// An outgoing link found in a document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Link {
+    /// Normalised target: a lowercase id, title, filename or data-relative
+    /// path without `.md` (see `target_keys`)
+    pub key: String,
+    /// 1-based, counting frontmatter lines (like search matches)
+    pub line_number: u32,
+    /// The trimmed line containing the link
+    pub context: String,
+}
+
+/// A document linking to another one, with every line that links
+#[derive(Debug, Serialize)]
+pub struct Backlink {
+    pub id: String,
+    pub path: String,
+    pub title: String,
+    pub note_type: String,
+    pub project_id: Option<String>,
+    pub mentions: Vec<Mention>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Mention {
+    pub line_number: u32,
+    pub context: String,
+}
+
+/// A link target as written in a document
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinkRef<'a> {
+    /// `[[target]]`, without alias or heading
+    Wiki(&'a str),
+    /// `[text](dest)` or `![alt](dest)`, without title or anchor (still percent-encoded)
+    Markdown(&'a str),
+}
+
+/// Find `[[id]]`, `[[Title|alias]]` and relative markdown links (`[text](../notes/a.md)`)
+/// in a document at data-relative `source_path`. Fenced code blocks are skipped.
+pub fn parse_links(source_path: &str, content: &str) -> Vec<Link> {
+    let mut links = Vec::new();
+
+    for (i, line) in outside_code_blocks(content) {
+        for (_, target) in scan_line(line) {
+            let key = match target {
+                LinkRef::Wiki(target) => normalize_key(target),
+                LinkRef::Markdown(dest) => match resolve_path(source_path, dest) {
+                    Some(path) if path.ends_with(".md") => normalize_key(&path),
+                    _ => continue,
+                },
+            };
+            if key.is_empty() {
+                continue;
+            }
+            links.push(Link {
+                key,
+                line_number: (i + 1) as u32,
+                context: line.trim().to_string(),
+            });
+        }
+    }
+
+    links
+}
+
+/// Rewrite link targets outside fenced code blocks. `rewrite` is given each
+/// target and returns its replacement, or None to keep it.
+/// Returns the new content and the number of links changed.
+pub fn rewrite_links(
+    content: &str,
+    mut rewrite: impl FnMut(LinkRef) -> Option<String>,
+) -> (String, usize) {
+    let mut output = String::with_capacity(content.len());
+    let mut count = 0;
+    let mut in_code_block = false;
+
+    for line in content.split_inclusive('\n') {
+        if toggles_code_block(line) {
+            in_code_block = !in_code_block;
+            output.push_str(line);
+            continue;
+        }
+        if in_code_block {
+            output.push_str(line);
+            continue;
+        }
+
+        let mut last = 0;
+        for (range, target) in scan_line(line) {
+            let Some(new) = rewrite(target) else {
+                continue;
+            };
+            output.push_str(&line[last..range.start]);
+            output.push_str(&new);
+            last = range.end;
+            count += 1;
+        }
+        output.push_str(&line[last..]);
+    }
+
+    (output, count)
+}
+
+/// A whole `[[target#heading|alias]]` or `![[embed]]` as written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WikiLink<'a> {
+    pub target: &'a str,
+    pub heading: Option<&'a str>,
+    pub alias: Option<&'a str>,
+    /// Written as `![[...]]`
+    pub embed: bool,
+}
+
+impl WikiLink<'_> {
+    /// The text a reader sees: the alias, else the target (and heading).
+    pub fn label(&self) -> String {
+        match (self.alias, self.heading) {
+            (Some(alias), _) => alias.to_string(),
+            (None, Some(heading)) if self.target.is_empty() => heading.to_string(),
+            (None, Some(heading)) => format!("{} > {}", self.target, heading),
+            (None, None) => self.target.to_string(),
+        }
+    }
+}
+
+/// Replace whole wiki links (brackets, alias and `!` included) outside
+/// fenced code blocks. `replace` returns the replacement, or None to keep one.
+pub fn replace_wiki_links(
+    content: &str,
+    mut replace: impl FnMut(&WikiLink) -> Option<String>,
+) -> String {
+    let mut output = String::with_capacity(content.len());
+    let mut in_code_block = false;
+
+    for line in content.split_inclusive('\n') {
+        if toggles_code_block(line) {
+            in_code_block = !in_code_block;
+            output.push_str(line);
+            continue;
+        }
+        if in_code_block {
+            output.push_str(line);
+            continue;
+        }
+
+        let mut last = 0;
+        for (range, link) in wiki_links(line) {
+            let Some(new) = replace(&link) else {
+                continue;
+            };
+            output.push_str(&line[last..range.start]);
+            output.push_str(&new);
+            last = range.end;
+        }
+        output.push_str(&line[last..]);
+    }
+
+    output
+}
+
+/// Wiki links in a line with the byte range of the whole link.
+fn wiki_links(line: &str) -> Vec<(Range<usize>, WikiLink<'_>)> {
+    let mut found = Vec::new();
+    let mut offset = 0;
+
+    while let Some(start) = line[offset..].find("[[") {
+        let open = offset + start;
+        let inner_start = open + 2;
+        let Some(len) = line[inner_start..].find("]]") else {
+            break;
+        };
+        let inner = &line[inner_start..inner_start + len];
+        let (link, alias) = match inner.split_once('|') {
+            Some((link, alias)) => (link, Some(alias.trim())),
+            None => (inner, None),
+        };
+        let (target, heading) = match link.split_once('#') {
+            Some((target, heading)) => (target, Some(heading.trim())),
+            None => (link, None),
+        };
+
+        let embed = open > 0 && line.as_bytes()[open - 1] == b'!';
+        let range_start = if embed { open - 1 } else { open };
+        found.push((
+            range_start..inner_start + len + 2,
+            WikiLink {
+                target: target.trim(),
+                heading: heading.filter(|h| !h.is_empty()),
+                alias: alias.filter(|a| !a.is_empty()),
+                embed,
+            },
+        ));
+
+        offset = inner_start + len + 2;
+    }
+
+    found
+}
+
+/// Whether `line` opens or closes a fenced code block.
+pub fn toggles_code_block(line: &str) -> bool {
+    let trimmed = line.trim_start();
+    trimmed.starts_with("```") || trimmed.starts_with("~~~")
+}
+
+/// Numbered lines that aren't inside (or delimiting) fenced code blocks.
+fn outside_code_blocks(content: &str) -> impl Iterator<Item = (usize, &str)> {
+    let mut in_code_block = false;
+    content.lines().enumerate().filter(move |(_, line)| {
+        if toggles_code_block(line) {
+            in_code_block = !in_code_block;
+            return false;
+        }
+        !in_code_block
+    })
+}
+
+/// Keys under which links can point at a document: its id, title,
+/// filename and path (all lowercase, without `.md`).
+pub fn target_keys(doc: &IndexedDoc) -> Vec<String> {
+    let path = doc.path.trim_end_matches(".md");
+    let stem = path.rsplit('/').next().unwrap_or(path);
+
+    let mut keys = vec![
+        normalize_key(&doc.id),
+        normalize_key(&doc.title),
+        normalize_key(stem),
+        normalize_key(path),
+    ];
+    keys.sort();
+    keys.dedup();
+    keys
+}
+
+/// Documents linking to the document at `path`, most recently updated first.
+pub fn backlinks(path: &str) -> Result<Vec<Backlink>, String> {
+    index::read(|idx| {
+        let target = idx
+            .get(path)
+            .ok_or_else(|| format!("Document not found in index: {}", path))?;
+        let keys = target_keys(target);
+
+        // Group mentions by linking document
+        let mut sources: BTreeMap<&str, (&IndexedDoc, Vec<Mention>)> = BTreeMap::new();
+        for key in &keys {
+            for source in idx.docs_linking_to(key) {
+                if source.path == target.path {
+                    continue;
+                }
+                let entry = sources
+                    .entry(source.path.as_str())
+                    .or_insert_with(|| (source, Vec::new()));
+                for link in source.links.iter().filter(|l| &l.key == key) {
+                    entry.1.push(Mention {
+                        line_number: link.line_number,
+                        context: link.context.clone(),
+                    });
+                }
+            }
+        }
+
+        let mut backlinks: Vec<Backlink> = sources
+            .into_values()
+            .map(|(source, mut mentions)| {
+                mentions.sort_by_key(|m| m.line_number);
+                mentions.dedup_by_key(|m| m.line_number);
+                Backlink {
+                    id: source.id.clone(),
+                    path: source.path.clone(),
+                    title: source.title.clone(),
+                    note_type: source.doc_type.clone(),
+                    project_id: source.project_id(),
+                    mentions,
+                }
+            })
+            .collect();
+
+        backlinks.sort_by(|a, b| {
+            let updated = |p: &str| idx.get(p).and_then(|d| d.updated.clone());
+            updated(&b.path)
+                .cmp(&updated(&a.path))
+                .then_with(|| a.path.cmp(&b.path))
+        });
+
+        Ok(backlinks)
+    })
+}
+
+/// Link targets in a line with their byte ranges, in order.
+fn scan_line(line: &str) -> Vec<(Range<usize>, LinkRef<'_>)> {
+    let mut targets = wiki_spans(line)
+        .into_iter()
+        .map(|range| (range.clone(), LinkRef::Wiki(&line[range])))
+        .chain(
+            markdown_spans(line)
+                .into_iter()
+                .map(|range| (range.clone(), LinkRef::Markdown(&line[range]))),
+        )
+        .filter(|(range, _)| !range.is_empty())
+        .collect::<Vec<_>>();
+    targets.sort_by_key(|(range, _)| range.start);
+    targets
+}
+
+/// Ranges of the targets of `[[target]]`, `[[target|alias]]` and `[[target#heading]]`.
+fn wiki_spans(line: &str) -> Vec<Range<usize>> {
+    let mut spans = Vec::new();
+    let mut offset = 0;
+
+    while let Some(start) = line[offset..].find("[[") {
+        let inner_start = offset + start + 2;
+        let Some(len) = line[inner_start..].find("]]") else {
+            break;
+        };
+        let inner = &line[inner_start..inner_start + len];
+        let target_len = inner.find(['|', '#']).unwrap_or(inner.len());
+        let target = &inner[..target_len];
+
+        let leading = target.len() - target.trim_start().len();
+        let start = inner_start + leading;
+        spans.push(start..start + target.trim().len());
+
+        offset = inner_start + len + 2;
+    }
+
+    spans
+}
+
+/// Ranges of the destinations of inline links `[text](dest "title")` and images,
+/// without angle brackets, title or `#anchor`.
+fn markdown_spans(line: &str) -> Vec<Range<usize>> {
+    let mut spans = Vec::new();
+    let mut offset = 0;
+
+    while let Some(pos) = line[offset..].find("](") {
+        let dest_start = offset + pos + 2;
+        let Some(len) = line[dest_start..].find(')') else {
+            break;
+        };
+        let inside = &line[dest_start..dest_start + len];
+
+        let leading = inside.len() - inside.trim_start().len();
+        let mut start = dest_start + leading;
+        let dest = inside.split_whitespace().next().unwrap_or("");
+        let mut end = start + dest.len();
+
+        if line[start..end].starts_with('<') && line[start..end].ends_with('>') {
+            start += 1;
+            end -= 1;
+        }
+        if let Some(anchor) = line[start..end].find('#') {
+            end = start + anchor;
+        }
+        spans.push(start..end);
+
+        offset = dest_start + len + 1;
+    }
+
+    spans
+}
+
+/// Resolve a relative link destination against the linking file's folder
+/// (a leading `/` means the data folder). Returns the data-relative path,
+/// or None for URLs and paths escaping the data folder.
+pub fn resolve_path(source_path: &str, dest: &str) -> Option<String> {
+    if dest.is_empty() || dest.contains("://") || dest.starts_with("mailto:") {
+        return None;
+    }
+
+    let dest = percent_decode(dest);
+    let mut parts: Vec<&str> = if dest.starts_with('/') {
+        Vec::new()
+    } else {
+        let mut dir: Vec<&str> = source_path.split('/').collect();
+        dir.pop();
+        dir
+    };
+
+    for part in dest.split('/') {
+        match part {
+            "" | "." => {}
+            ".." => {
+                parts.pop()?;
+            }
+            part => parts.push(part),
+        }
+    }
+
+    Some(parts.join("/"))
+}
+
+/// Relative link destination from the file at `source_path` to `target_path`
+/// (both data-relative), with spaces percent-encoded.
+pub fn relative_path(source_path: &str, target_path: &str) -> String {
+    let from: Vec<&str> = source_path.split('/').collect();
+    let from = &from[..from.len() - 1];
+    let to: Vec<&str> = target_path.split('/').collect();
+
+    let common = from.iter().zip(&to).take_while(|(a, b)| a == b).count();
+
+    let mut parts: Vec<&str> = vec![".."; from.len() - common];
+    parts.extend(&to[common..]);
+    parts.join("/").replace(' ', "%20")
+}
+
+/// Lowercase link key of a wiki target or path, without `.md`.
+pub fn normalize_key(target: &str) -> String {
+    let target = target.trim();
+    target.strip_suffix(".md").unwrap_or(target).to_lowercase()
+}
+
+/// Decode `%XX` escapes (e.g. `%20`) in link destinations.
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            if let Some(byte) = text
+                .get(i + 1..i + 3)
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+            {
+                decoded.push(byte);
+                i += 3;
+                continue;
+            }
+        }
+        decoded.push(bytes[i]);
+        i += 1;
+    }
+
+    String::from_utf8_lossy(&decoded).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_links() {
+        let content = "---\ntitle: A\n---\nSee [[pg]] and [[Postgres Guide|the guide]].\n\
+                       ```\n[[not-a-link]]\n```\n\
+                       Also [backup](../backup.md), ![img](../assets/a.png), [web](https://x.org/a.md)\n\
+                       And [[Daily#Morning]] plus [spaced](My%20Note.md)";
+        let links = parse_links("notes/sub/a.md", content);
+        let keys: Vec<&str> = links.iter().map(|l| l.key.as_str()).collect();
+
+        assert_eq!(
+            keys,
+            vec![
+                "pg",
+                "postgres guide",
+                "notes/backup",
+                "daily",
+                "notes/sub/my note"
+            ]
+        );
+        assert_eq!(links[0].line_number, 4);
+        assert_eq!(
+            links[2].context,
+            "Also [backup](../backup.md), ![img](../assets/a.png), [web](https://x.org/a.md)"
+        );
+    }
+
+    #[test]
+    fn test_resolve_and_relative_paths() {
+        assert_eq!(
+            resolve_path("projects/ops/tasks/t.md", "../index.md").as_deref(),
+            Some("projects/ops/index.md")
+        );
+        assert_eq!(
+            resolve_path("notes/a.md", "/daily/2026-01-01.md").as_deref(),
+            Some("daily/2026-01-01.md")
+        );
+        assert_eq!(resolve_path("a.md", "../../x.md"), None);
+        assert_eq!(resolve_path("notes/a.md", "https://x.org/a.md"), None);
+
+        assert_eq!(relative_path("notes/a.md", "notes/b.md"), "b.md");
+        assert_eq!(
+            relative_path("projects/ops/notes/a.md", "notes/sub/My Note.md"),
+            "../../../notes/sub/My%20Note.md"
+        );
+    }
+
+    #[test]
+    fn test_rewrite_links() {
+        let content = "[[old|Old]] and [x](old.md#top) and [[other]]\n```\n[[old]]\n```\n";
+        let (new, count) = rewrite_links(content, |target| match target {
+            LinkRef::Wiki("old") => Some("new".to_string()),
+            LinkRef::Markdown("old.md") => Some("sub/new.md".to_string()),
+            _ => None,
+        });
+
+        assert_eq!(count, 2);
+        assert_eq!(
+            new,
+            "[[new|Old]] and [x](sub/new.md#top) and [[other]]\n```\n[[old]]\n```\n"
+        );
+    }
+
+    #[test]
+    fn test_replace_wiki_links() {
+        let content =
+            "![[diagram.png]] and [[pg#Setup|the guide]] and [[#Local]]\n```\n[[old]]\n```\n";
+        let mut seen = Vec::new();
+        let new = replace_wiki_links(content, |link| {
+            seen.push((link.target.to_string(), link.label(), link.embed));
+            Some(format!("<{}>", link.target))
+        });
+
+        assert_eq!(new, "<diagram.png> and <pg> and <>\n```\n[[old]]\n```\n");
+        assert_eq!(
+            seen,
+            vec![
+                ("diagram.png".to_string(), "diagram.png".to_string(), true),
+                ("pg".to_string(), "the guide".to_string(), false),
+                ("".to_string(), "Local".to_string(), false),
+            ]
+        );
+    }
+}
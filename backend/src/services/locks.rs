@@ -101,24 +101,6 @@ impl FileLockManager {
         }
     }
 
-    /// Check if a file is locked
-    pub async fn is_locked(&self, path: &str) -> Option<LockInfo> {
-        let locks = self.locks.read().await;
-        locks.get(path).cloned()
-    }
-
-    /// Check if a file is locked by someone other than the given client
-    pub async fn is_locked_by_other(&self, path: &str, client_id: &str) -> Option<LockInfo> {
-        let locks = self.locks.read().await;
-        locks.get(path).and_then(|lock| {
-            if lock.client_id != client_id {
-                Some(lock.clone())
-            } else {
-                None
-            }
-        })
-    }
-
     /// Release all locks held by a client (used on disconnect)
     pub async fn release_all_for_client(&self, client_id: &str) -> Vec<String> {
         let mut locks = self.locks.write().await;
@@ -134,12 +116,6 @@ impl FileLockManager {
 
         paths_to_remove
     }
-
-    /// Get all current locks (for debugging/monitoring)
-    pub async fn get_all_locks(&self) -> Vec<LockInfo> {
-        let locks = self.locks.read().await;
-        locks.values().cloned().collect()
-    }
 }
 
 impl Default for FileLockManager {
@@ -1 +1,474 @@
+use std::collections::HashMap;
 
+use serde::Serialize;
+
+use crate::services::filesystem;
+use crate::services::frontmatter;
+use crate::services::index::{self, IndexedDoc, SearchIndex};
+use crate::services::links::{self, LinkRef, WikiLink};
+
+/// Document types that can be rendered (and linked to from rendered documents)
+pub const RENDER_TYPES: [&str; 4] = ["note", "task", "project", "daily"];
+
+/// Markdown rendered to sanitised HTML
+#[derive(Debug, Serialize)]
+pub struct Rendered {
+    pub html: String,
+    /// Headings in document order, with the anchors given to them
+    pub headings: Vec<Heading>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Heading {
+    pub level: u8,
+    pub text: String,
+    /// The heading's `id`, unique within the document
+    pub anchor: String,
+}
+
+/// A document rendered by the render endpoint
+#[derive(Debug, Serialize)]
+pub struct RenderedDoc {
+    pub id: String,
+    pub path: String,
+    pub doc_type: String,
+    pub title: String,
+    pub project_id: Option<String>,
+    #[serde(flatten)]
+    pub rendered: Rendered,
+}
+
+/// A document internal links can resolve to
+#[derive(Debug, Clone)]
+pub struct Target {
+    pub path: String,
+    pub id: String,
+    pub doc_type: String,
+    pub project_id: Option<String>,
+}
+
+/// Link keys (see `links::target_keys`) → the documents they resolve to.
+/// Built once and shared by every document rendered from the same vault state.
+#[derive(Debug, Default)]
+pub struct Targets {
+    by_key: HashMap<String, Target>,
+}
+
+impl Targets {
+    /// Targets for the renderable documents among `docs`. A key shared by
+    /// several documents resolves to the first by path.
+    pub fn new<'a>(docs: impl IntoIterator<Item = &'a IndexedDoc>) -> Self {
+        let mut docs: Vec<&IndexedDoc> = docs
+            .into_iter()
+            .filter(|doc| RENDER_TYPES.contains(&doc.doc_type.as_str()))
+            .collect();
+        docs.sort_by(|a, b| a.path.cmp(&b.path));
+
+        let mut by_key = HashMap::new();
+        for doc in docs {
+            let target = Target {
+                path: doc.path.clone(),
+                id: doc.id.clone(),
+                doc_type: doc.doc_type.clone(),
+                project_id: doc.project_id(),
+            };
+            for key in links::target_keys(doc) {
+                by_key.entry(key).or_insert_with(|| target.clone());
+            }
+        }
+
+        Targets { by_key }
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Target> {
+        self.by_key.get(&links::normalize_key(key))
+    }
+}
+
+/// Where rendered links point. Both functions are given the data-relative
+/// path of the document being rendered first.
+pub struct Urls<'a> {
+    /// URL of a linked document
+    pub doc: &'a dyn Fn(&str, &Target) -> String,
+    /// URL of an asset, by data-relative path (`notes/assets/a.png` or
+    /// `projects/{id}/assets/a.png`)
+    pub asset: &'a dyn Fn(&str, &str) -> String,
+}
+
+/// Links into the API: documents to their render endpoint, assets to `/api/assets/`.
+pub const API_URLS: Urls<'static> = Urls {
+    doc: &api_doc_url,
+    asset: &api_asset_url,
+};
+
+fn api_doc_url(_source: &str, target: &Target) -> String {
+    let project = target.project_id.as_deref().unwrap_or_default();
+    match target.doc_type.as_str() {
+        "daily" => format!("/api/render/daily/{}", file_stem(&target.path)),
+        "project" => format!("/api/render/project/{}", project),
+        doc_type if project.is_empty() => format!("/api/render/{}/{}", doc_type, target.id),
+        doc_type => format!("/api/render/{}/{}?project={}", doc_type, target.id, project),
+    }
+}
+
+fn api_asset_url(_source: &str, asset: &str) -> String {
+    let filename = file_name(asset);
+    match asset.split('/').collect::<Vec<_>>().as_slice() {
+        ["projects", project, ..] => format!("/api/assets/{}/{}", project, filename),
+        _ => format!("/api/assets/notes/{}", filename),
+    }
+}
+
+/// Render the note, task, project or daily note with this id, linking into
+/// the API. Tasks and project notes can be told apart by `project`; daily
+/// notes are found by date (`2026-01-31` or `daily-2026-01-31`).
+pub fn render_doc(doc_type: &str, id: &str, project: Option<&str>) -> Result<RenderedDoc, String> {
+    if !RENDER_TYPES.contains(&doc_type) {
+        return Err(format!("Invalid type: {}", doc_type));
+    }
+
+    index::read(|idx| {
+        let doc = find_doc(idx, doc_type, id, project)?;
+        let targets = Targets::new(idx.docs());
+        Ok(RenderedDoc {
+            id: doc.id.clone(),
+            path: doc.path.clone(),
+            doc_type: doc.doc_type.clone(),
+            title: doc.title.clone(),
+            project_id: doc.project_id(),
+            rendered: render(&doc.path, &doc.content, &targets, &API_URLS)?,
+        })
+    })
+}
+
+/// The note, task, project or daily note with this id (see `render_doc`).
+pub fn find_doc<'a>(
+    idx: &'a SearchIndex,
+    doc_type: &str,
+    id: &str,
+    project: Option<&str>,
+) -> Result<&'a IndexedDoc, String> {
+    match doc_type {
+        "daily" => idx.get(&format!(
+            "daily/{}.md",
+            id.strip_prefix("daily-").unwrap_or(id)
+        )),
+        "project" => idx.get(&format!("projects/{}/index.md", id)),
+        _ => idx.docs_with_id(id).find(|doc| {
+            doc.doc_type == doc_type
+                && project.is_none_or(|p| doc.project_id().as_deref() == Some(p))
+        }),
+    }
+    .ok_or_else(|| format!("Document not found: {}/{}", doc_type, id))
+}
+
+/// Render a document (frontmatter is skipped) at data-relative `path` to HTML.
+/// Raw HTML and dangerous URLs (`javascript:`) are escaped, GFM tables, task
+/// lists, footnotes and strikethrough are supported, headings get anchors,
+/// and wiki links, `.md` links and asset URLs are rewritten with `urls`.
+pub fn render(
+    path: &str,
+    content: &str,
+    targets: &Targets,
+    urls: &Urls,
+) -> Result<Rendered, String> {
+    let body = frontmatter::parse(content).body;
+    let body = resolve_links(path, &body, targets, urls);
+
+    let html = markdown::to_html_with_options(&body, &markdown::Options::gfm())
+        .map_err(|e| format!("Failed to render markdown: {}", e))?;
+
+    Ok(anchor_headings(&html))
+}
+
+/// Turn internal links into plain markdown links to their URLs: `.md` and
+/// asset destinations are rewritten in place, wiki links become `[label](url)`
+/// (or plain text if they don't resolve) and `![[image.png]]` an image.
+pub fn resolve_links(path: &str, body: &str, targets: &Targets, urls: &Urls) -> String {
+    let (body, _) = links::rewrite_links(body, |target| {
+        let LinkRef::Markdown(dest) = target else {
+            return None;
+        };
+        if let Some(asset) = asset_path(path, dest) {
+            return Some(link_dest(&(urls.asset)(path, &asset)));
+        }
+        let resolved = links::resolve_path(path, dest).filter(|p| p.ends_with(".md"))?;
+        let target = targets.get(&resolved)?;
+        Some(link_dest(&(urls.doc)(path, target)))
+    });
+
+    links::replace_wiki_links(&body, |link| {
+        Some(wiki_to_markdown(path, link, targets, urls))
+    })
+}
+
+fn wiki_to_markdown(path: &str, link: &WikiLink, targets: &Targets, urls: &Urls) -> String {
+    let label = escape_label(&link.label());
+
+    if link.embed && is_asset_name(link.target) {
+        let asset = format!("{}/{}", assets_dir(path), link.target);
+        return format!("![{}]({})", label, link_dest(&(urls.asset)(path, &asset)));
+    }
+
+    let anchor = link
+        .heading
+        .map(|h| format!("#{}", filesystem::slugify(h)))
+        .unwrap_or_default();
+
+    if link.target.is_empty() {
+        return format!("[{}]({})", label, link_dest(&anchor));
+    }
+    match targets.get(link.target) {
+        Some(target) => {
+            let url = format!("{}{}", (urls.doc)(path, target), anchor);
+            format!("[{}]({})", label, link_dest(&url))
+        }
+        None => label,
+    }
+}
+
+/// Data-relative path of an asset referenced from `source`: `/api/assets/`
+/// URLs and relative paths into a `notes/assets` or project `assets` folder.
+pub fn asset_path(source: &str, dest: &str) -> Option<String> {
+    let path = match dest.strip_prefix("/api/assets/") {
+        Some(rest) => match rest.split_once('/') {
+            Some(("notes", file)) => format!("notes/assets/{}", file),
+            Some((project, file)) => format!("projects/{}/assets/{}", project, file),
+            None => return None,
+        },
+        None => links::resolve_path(source, dest)?,
+    };
+
+    let parts: Vec<&str> = path.split('/').collect();
+    match parts.as_slice() {
+        ["notes", "assets", file] | ["projects", _, "assets", file] if !file.is_empty() => {
+            Some(path)
+        }
+        _ => None,
+    }
+}
+
+/// Assets folder for files embedded by name from `source`: its project's,
+/// else `notes/assets`.
+fn assets_dir(source: &str) -> String {
+    match source.split('/').collect::<Vec<_>>().as_slice() {
+        ["projects", project, ..] => format!("projects/{}/assets", project),
+        _ => "notes/assets".to_string(),
+    }
+}
+
+fn is_asset_name(target: &str) -> bool {
+    match target.rsplit_once('.') {
+        Some((_, ext)) => !ext.eq_ignore_ascii_case("md") && !ext.contains('/'),
+        None => false,
+    }
+}
+
+fn file_name(path: &str) -> &str {
+    path.rsplit('/').next().unwrap_or(path)
+}
+
+fn file_stem(path: &str) -> &str {
+    file_name(path).trim_end_matches(".md")
+}
+
+/// A link destination, with spaces encoded so it stays one destination.
+fn link_dest(url: &str) -> String {
+    url.replace(' ', "%20")
+}
+
+/// Escape characters that would end or nest a link label.
+fn escape_label(label: &str) -> String {
+    let mut escaped = String::with_capacity(label.len());
+    for c in label.chars() {
+        if matches!(c, '[' | ']' | '\\' | '*' | '_' | '`' | '<') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Give every `<h1>`..`<h6>` an `id` from its text (suffixed `-1`, `-2`, ...
+/// when repeated) and collect the headings.
+fn anchor_headings(html: &str) -> Rendered {
+    let mut output = String::with_capacity(html.len());
+    let mut headings = Vec::new();
+    let mut used: HashMap<String, usize> = HashMap::new();
+    let mut rest = html;
+
+    while let Some(pos) = rest.find("<h") {
+        let tag = &rest.as_bytes()[pos..];
+        let level = match (tag.get(2), tag.get(3)) {
+            (Some(digit @ b'1'..=b'6'), Some(b'>')) => digit - b'0',
+            _ => {
+                output.push_str(&rest[..pos + 2]);
+                rest = &rest[pos + 2..];
+                continue;
+            }
+        };
+        let close = format!("</h{}>", level);
+        let inner_start = pos + 4;
+        let Some(len) = rest[inner_start..].find(&close) else {
+            break;
+        };
+        let inner = &rest[inner_start..inner_start + len];
+
+        let text = unescape(&strip_tags(inner));
+        let base = match filesystem::slugify(&text) {
+            slug if slug.is_empty() => "section".to_string(),
+            slug => slug,
+        };
+        let anchor = match used.get(&base).copied() {
+            Some(n) => format!("{}-{}", base, n),
+            None => base.clone(),
+        };
+        *used.entry(base).or_insert(0) += 1;
+
+        output.push_str(&rest[..pos]);
+        output.push_str(&format!("<h{} id=\"{}\">{}{}", level, anchor, inner, close));
+        headings.push(Heading {
+            level,
+            text: text.trim().to_string(),
+            anchor,
+        });
+        rest = &rest[inner_start + len + close.len()..];
+    }
+    output.push_str(rest);
+
+    Rendered {
+        html: output,
+        headings,
+    }
+}
+
+fn strip_tags(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            c if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    text
+}
+
+/// The text of rendered HTML, without tags.
+pub fn html_to_text(html: &str) -> String {
+    unescape(&strip_tags(html))
+}
+
+/// Escape text for HTML element content and quoted attributes.
+pub fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#x27;", "'")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::index::SearchIndex;
+    use std::path::Path;
+
+    fn vault() -> SearchIndex {
+        let mut idx = SearchIndex::default();
+        idx.insert(
+            Path::new("data/notes/pg.md"),
+            "---\nid: pg\ntitle: Postgres Guide\n---\n# Setup\n",
+        );
+        idx.insert(
+            Path::new("data/projects/ops/tasks/task-1.md"),
+            "---\nid: task-1\ntitle: Upgrade\n---\n",
+        );
+        idx
+    }
+
+    #[test]
+    fn test_render_links_and_assets() {
+        let idx = vault();
+        let targets = Targets::new(idx.docs());
+        let content = "---\ntitle: A\n---\nSee [[Postgres Guide#Setup|the guide]], [[task-1]], \
+                       [[missing]] and [pg](pg.md).\n\n![[chart.png]] ![x](assets/a%20b.png)\n";
+        let rendered = render("notes/a.md", content, &targets, &API_URLS).unwrap();
+
+        assert!(rendered
+            .html
+            .contains(r#"<a href="/api/render/note/pg#setup">the guide</a>"#));
+        assert!(rendered
+            .html
+            .contains(r#"<a href="/api/render/task/task-1?project=ops">task-1</a>"#));
+        assert!(rendered.html.contains(" missing and "));
+        assert!(rendered
+            .html
+            .contains(r#"<a href="/api/render/note/pg">pg</a>"#));
+        assert!(rendered
+            .html
+            .contains(r#"<img src="/api/assets/notes/chart.png" alt="chart.png" />"#));
+        assert!(rendered
+            .html
+            .contains(r#"<img src="/api/assets/notes/a%20b.png" alt="x" />"#));
+        assert!(!rendered.html.contains("title: A"));
+    }
+
+    #[test]
+    fn test_render_sanitises_and_supports_gfm() {
+        let content = "<script>alert(1)</script>\n\n[x](javascript:alert(1))\n\n\
+                       - [x] done\n- [ ] todo\n\n| a | b |\n|---|---|\n| 1 | 2 |\n\n\
+                       Note[^1]\n\n[^1]: Footnote\n";
+        let rendered = render("notes/a.md", content, &Targets::default(), &API_URLS).unwrap();
+
+        assert!(!rendered.html.contains("<script>"));
+        assert!(!rendered.html.contains("javascript:"));
+        assert!(rendered.html.contains(r#"type="checkbox""#));
+        assert!(rendered.html.contains("<table>"));
+        assert!(rendered.html.contains("Footnote"));
+    }
+
+    #[test]
+    fn test_anchor_headings() {
+        let rendered = anchor_headings(
+            "<h1>Hello &amp; <em>World</em></h1><p>x</p><h2>Hello &amp; World</h2><h3></h3>",
+        );
+
+        assert_eq!(
+            rendered.html,
+            "<h1 id=\"hello-world\">Hello &amp; <em>World</em></h1><p>x</p>\
+             <h2 id=\"hello-world-1\">Hello &amp; World</h2><h3 id=\"section\"></h3>"
+        );
+        assert_eq!(
+            rendered.headings[0],
+            Heading {
+                level: 1,
+                text: "Hello & World".to_string(),
+                anchor: "hello-world".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_asset_path() {
+        assert_eq!(
+            asset_path("notes/a.md", "/api/assets/ops/x.png").as_deref(),
+            Some("projects/ops/assets/x.png")
+        );
+        assert_eq!(
+            asset_path("projects/ops/notes/n.md", "../assets/x.png").as_deref(),
+            Some("projects/ops/assets/x.png")
+        );
+        assert_eq!(asset_path("notes/a.md", "b.md"), None);
+        assert_eq!(asset_path("notes/a.md", "https://x.org/assets/x.png"), None);
+    }
+}
@@ -0,0 +1,444 @@
+use grep::matcher::{Captures, Matcher};
+use grep::regex::{RegexMatcher, RegexMatcherBuilder};
+use grep::searcher::{sinks::UTF8, SearcherBuilder};
+use serde::{Deserialize, Serialize};
+use unicode_normalization::char::{decompose_canonical, is_combining_mark};
+
+/// How text is matched. Shared by search, replace and anything else that
+/// needs to find text the same way the search box does.
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(default)]
+pub struct MatchOptions {
+    /// Match case exactly (default: case-insensitive)
+    pub case_sensitive: bool,
+    /// Only match whole words
+    pub whole_word: bool,
+    /// Treat accented letters as their base letter (`cafe` matches `café`)
+    pub fold_diacritics: bool,
+}
+
+/// Something to look for: a literal string or a regular expression.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Needle {
+    Literal(String),
+    Regex(String),
+}
+
+/// A matching line with the byte ranges of each match.
+#[derive(Debug, Clone, Serialize)]
+pub struct LineMatch {
+    pub line_number: u32,
+    pub line_content: String,
+    /// Byte ranges within `line_content` to highlight
+    pub highlights: Vec<Highlight>,
+}
+
+/// Byte range of a match (`start..end`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Highlight {
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Compiled needles plus the options they were built with.
+pub struct TextMatcher {
+    matcher: RegexMatcher,
+    fold_diacritics: bool,
+    /// Whether replacements expand `$1` / `${name}` (only for regex needles)
+    expand_captures: bool,
+}
+
+impl TextMatcher {
+    /// Build a matcher that finds any of the needles.
+    /// Errors (e.g. an invalid regex) are prefixed with "Invalid query".
+    pub fn new(needles: &[Needle], options: &MatchOptions) -> Result<Self, String> {
+        let pattern = needles
+            .iter()
+            .map(|needle| {
+                let raw = match needle {
+                    Needle::Literal(text) => escape(text),
+                    Needle::Regex(pattern) => pattern.clone(),
+                };
+                let raw = if options.fold_diacritics {
+                    fold_diacritics(&raw)
+                } else {
+                    raw
+                };
+                format!("(?:{})", raw)
+            })
+            .collect::<Vec<_>>()
+            .join("|");
+
+        let matcher = RegexMatcherBuilder::new()
+            .case_insensitive(!options.case_sensitive)
+            .word(options.whole_word)
+            .build(&pattern)
+            .map_err(|e| format!("Invalid query: {}", e))?;
+
+        Ok(Self {
+            matcher,
+            fold_diacritics: options.fold_diacritics,
+            expand_captures: needles.iter().any(|n| matches!(n, Needle::Regex(_))),
+        })
+    }
+
+    /// Whether the text contains at least one match.
+    pub fn is_match(&self, text: &str) -> bool {
+        let haystack = self.prepare(text);
+        self.matcher.is_match(haystack.as_bytes()).unwrap_or(false)
+    }
+
+    /// Run the searcher over `content` and collect matching lines
+    /// (1-based line numbers, trimmed, with highlight ranges).
+    pub fn search_lines(&self, content: &str, max_matches: Option<u64>) -> Vec<LineMatch> {
+        let haystack = self.prepare(content);
+        let original_lines: Vec<&str> = content.lines().collect();
+        let mut results = Vec::new();
+
+        let mut searcher = SearcherBuilder::new()
+            .line_number(true)
+            .max_matches(max_matches)
+            .build();
+
+        let outcome = searcher.search_slice(
+            &self.matcher,
+            haystack.as_bytes(),
+            UTF8(|line_number, line| {
+                let idx = line_number as usize - 1;
+                let Some(original) = original_lines.get(idx) else {
+                    return Ok(true);
+                };
+                let searched = line.trim_end_matches(['\n', '\r']);
+
+                let mut ranges = Vec::new();
+                self.matcher
+                    .find_iter(searched.as_bytes(), |m| {
+                        ranges.push((m.start(), m.end()));
+                        true
+                    })
+                    .ok();
+
+                let (line_content, highlights) = trim_with_ranges(original, searched, &ranges);
+                results.push(LineMatch {
+                    line_number: line_number as u32,
+                    line_content,
+                    highlights,
+                });
+                Ok(true)
+            }),
+        );
+
+        if let Err(err) = outcome {
+            tracing::warn!("Search failed: {}", err);
+        }
+
+        results
+    }
+
+    /// Replace every match in `text`, returning the new text and the number
+    /// of replacements. For regex needles, `$1`, `${1}` and `${name}` in the
+    /// replacement expand to capture groups and `$$` is a literal `$`.
+    pub fn replace_all(&self, text: &str, replacement: &str) -> (String, usize) {
+        let haystack = self.prepare(text);
+        let offsets = OffsetMap::new(text, &haystack);
+        let Ok(mut caps) = self.matcher.new_captures() else {
+            return (text.to_string(), 0);
+        };
+
+        let mut result = String::with_capacity(text.len());
+        let mut last = 0;
+        let mut count = 0;
+
+        self.matcher
+            .captures_iter(haystack.as_bytes(), &mut caps, |caps| {
+                let Some(m) = caps.get(0) else {
+                    return true;
+                };
+                let (start, end) = (offsets.map(m.start()), offsets.map(m.end()));
+                result.push_str(&text[last..start]);
+                if self.expand_captures {
+                    self.expand(replacement, caps, text, &offsets, &mut result);
+                } else {
+                    result.push_str(replacement);
+                }
+                last = end;
+                count += 1;
+                true
+            })
+            .ok();
+
+        result.push_str(&text[last..]);
+        (result, count)
+    }
+
+    /// Append `replacement` to `dst`, substituting capture group references.
+    fn expand(
+        &self,
+        replacement: &str,
+        caps: &grep::regex::RegexCaptures,
+        text: &str,
+        offsets: &OffsetMap,
+        dst: &mut String,
+    ) {
+        let group = |index: Option<usize>, dst: &mut String| {
+            if let Some(m) = index.and_then(|i| caps.get(i)) {
+                dst.push_str(&text[offsets.map(m.start())..offsets.map(m.end())]);
+            }
+        };
+
+        let mut rest = replacement;
+        while let Some(pos) = rest.find('$') {
+            dst.push_str(&rest[..pos]);
+            rest = &rest[pos + 1..];
+
+            if let Some(after) = rest.strip_prefix('$') {
+                dst.push('$');
+                rest = after;
+            } else if let Some(braced) = rest.strip_prefix('{') {
+                match braced.find('}') {
+                    Some(close) => {
+                        let name = &braced[..close];
+                        let index = name
+                            .parse::<usize>()
+                            .ok()
+                            .or_else(|| self.matcher.capture_index(name));
+                        group(index, dst);
+                        rest = &braced[close + 1..];
+                    }
+                    None => dst.push('$'),
+                }
+            } else {
+                let digits =
+                    rest.len() - rest.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+                if digits == 0 {
+                    dst.push('$');
+                } else {
+                    group(rest[..digits].parse().ok(), dst);
+                    rest = &rest[digits..];
+                }
+            }
+        }
+        dst.push_str(rest);
+    }
+
+    fn prepare<'a>(&self, text: &'a str) -> std::borrow::Cow<'a, str> {
+        if self.fold_diacritics {
+            std::borrow::Cow::Owned(fold_diacritics(text))
+        } else {
+            std::borrow::Cow::Borrowed(text)
+        }
+    }
+}
+
+/// Maps byte offsets in searched (possibly diacritic-folded) text back onto
+/// the original. Folding maps one char to one char, so char indices line up.
+struct OffsetMap {
+    /// (searched offset, original offset) of every char boundary, when they differ
+    boundaries: Option<Vec<(usize, usize)>>,
+    len: usize,
+}
+
+impl OffsetMap {
+    fn new(original: &str, searched: &str) -> Self {
+        let boundaries = (original.len() != searched.len()).then(|| {
+            searched
+                .char_indices()
+                .map(|(i, _)| i)
+                .zip(original.char_indices().map(|(i, _)| i))
+                .collect()
+        });
+        Self {
+            boundaries,
+            len: original.len(),
+        }
+    }
+
+    fn map(&self, offset: usize) -> usize {
+        match &self.boundaries {
+            None => offset,
+            Some(boundaries) => match boundaries.binary_search_by_key(&offset, |&(s, _)| s) {
+                Ok(i) => boundaries[i].1,
+                Err(_) => self.len,
+            },
+        }
+    }
+}
+
+/// Map highlight ranges found in `searched` back onto `original`, then trim
+/// surrounding whitespace and shift the ranges accordingly.
+fn trim_with_ranges(
+    original: &str,
+    searched: &str,
+    ranges: &[(usize, usize)],
+) -> (String, Vec<Highlight>) {
+    let offsets = OffsetMap::new(original, searched);
+    let leading = original.len() - original.trim_start().len();
+    let trimmed = original.trim();
+
+    let highlights = ranges
+        .iter()
+        .map(|&(start, end)| {
+            let start = offsets
+                .map(start)
+                .saturating_sub(leading)
+                .min(trimmed.len());
+            let end = offsets.map(end).saturating_sub(leading).min(trimmed.len());
+            Highlight { start, end }
+        })
+        .filter(|h| h.end > h.start)
+        .collect();
+
+    (trimmed.to_string(), highlights)
+}
+
+/// Escape regex metacharacters so text is matched literally.
+pub fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if "\\.+*?()|[]{}^$#&-~".contains(c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Replace accented letters with their base letter, one char for one char
+/// (so line numbers and char positions are preserved).
+pub fn fold_diacritics(text: &str) -> String {
+    text.chars().map(fold_char).collect()
+}
+
+/// Fold a single char (see `fold_diacritics`).
+pub fn fold_char(c: char) -> char {
+    if c.is_ascii() {
+        return c;
+    }
+
+    // Letters that don't decompose canonically
+    match c {
+        'ø' => return 'o',
+        'Ø' => return 'O',
+        'ł' => return 'l',
+        'Ł' => return 'L',
+        'đ' => return 'd',
+        'Đ' => return 'D',
+        'ı' => return 'i',
+        _ => {}
+    }
+
+    let mut base = None;
+    let mut only_marks = true;
+    decompose_canonical(c, |d| {
+        if base.is_none() {
+            base = Some(d);
+        } else if !is_combining_mark(d) {
+            only_marks = false;
+        }
+    });
+
+    match base {
+        Some(b) if only_marks && !is_combining_mark(b) => b,
+        _ => c,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matcher(needles: &[Needle], options: MatchOptions) -> TextMatcher {
+        TextMatcher::new(needles, &options).unwrap()
+    }
+
+    #[test]
+    fn test_fold_diacritics() {
+        assert_eq!(fold_diacritics("Café Ørsted naïve"), "Cafe Orsted naive");
+        assert_eq!(fold_diacritics("Straße"), "Straße");
+    }
+
+    #[test]
+    fn test_literal_escaping() {
+        let m = matcher(
+            &[Needle::Literal("a.b (c)".into())],
+            MatchOptions::default(),
+        );
+        assert!(m.is_match("x a.b (c) y"));
+        assert!(!m.is_match("axb c"));
+    }
+
+    #[test]
+    fn test_case_and_word_options() {
+        let needles = [Needle::Literal("post".into())];
+        assert!(matcher(&needles, MatchOptions::default()).is_match("Postgres"));
+
+        let strict = MatchOptions {
+            case_sensitive: true,
+            ..Default::default()
+        };
+        assert!(!matcher(&needles, strict).is_match("Postgres"));
+
+        let word = MatchOptions {
+            whole_word: true,
+            ..Default::default()
+        };
+        assert!(!matcher(&needles, word).is_match("postgres"));
+        assert!(matcher(&needles, word).is_match("a post here"));
+    }
+
+    #[test]
+    fn test_search_lines_with_folding() {
+        let options = MatchOptions {
+            fold_diacritics: true,
+            ..Default::default()
+        };
+        let m = matcher(&[Needle::Literal("cafe".into())], options);
+        let lines = m.search_lines("first\n   Le Café est ouvert\nCAFE", None);
+
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].line_number, 2);
+        let h = lines[0].highlights[0];
+        assert_eq!(&lines[0].line_content[h.start..h.end], "Café");
+        assert_eq!(lines[1].line_number, 3);
+    }
+
+    #[test]
+    fn test_regex_and_max_matches() {
+        let m = matcher(
+            &[Needle::Regex(r"v\d+\.\d+".into())],
+            MatchOptions::default(),
+        );
+        let lines = m.search_lines("v1.2\nnothing\nv3.4\nv5.6", Some(2));
+        assert_eq!(lines.len(), 2);
+        assert!(TextMatcher::new(&[Needle::Regex("(".into())], &MatchOptions::default()).is_err());
+    }
+
+    #[test]
+    fn test_replace_all() {
+        let literal = matcher(&[Needle::Literal("$old".into())], MatchOptions::default());
+        assert_eq!(
+            literal.replace_all("$OLD and $old", "$1"),
+            ("$1 and $1".to_string(), 2)
+        );
+
+        let regex = matcher(
+            &[Needle::Regex(r"(?P<major>\d+)\.(\d+)".into())],
+            MatchOptions::default(),
+        );
+        assert_eq!(
+            regex.replace_all("v1.2, v3.4", "${major}-$2$$"),
+            ("v1-2$, v3-4$".to_string(), 2)
+        );
+
+        let folded = MatchOptions {
+            fold_diacritics: true,
+            whole_word: true,
+            ..Default::default()
+        };
+        let m = matcher(&[Needle::Literal("cafe".into())], folded);
+        assert_eq!(
+            m.replace_all("Le café, cafés", "bar"),
+            ("Le bar, cafés".to_string(), 1)
+        );
+    }
+}
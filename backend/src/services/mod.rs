@@ -1,6 +1,25 @@
+pub mod archive;
+pub mod etag;
 pub mod filesystem;
+pub mod folders;
 pub mod frontmatter;
+pub mod fuzzy;
 pub mod git;
+pub mod graph;
+pub mod import;
+pub mod index;
+pub mod links;
 pub mod locks;
 pub mod markdown;
+pub mod matcher;
+pub mod pdf;
+pub mod query;
+pub mod recent;
+pub mod related;
+pub mod rename;
+pub mod replace;
+pub mod schemas;
 pub mod search;
+pub mod site;
+pub mod tags;
+pub mod templates;
@@ -0,0 +1,1064 @@
+use std::fs;
+
+use ::markdown::mdast::{self, Node};
+use ::markdown::ParseOptions;
+use chrono::NaiveDate;
+use printpdf::image_crate::{self, DynamicImage};
+use printpdf::path::PaintMode;
+use printpdf::{
+    BuiltinFont, Color, Image, ImageTransform, IndirectFontRef, Line, Mm, PdfDocument,
+    PdfDocumentReference, PdfLayerReference, Point, Rect, Rgb,
+};
+use serde::Deserialize;
+
+use crate::config;
+use crate::services::frontmatter;
+use crate::services::index::{self, IndexedDoc};
+use crate::services::markdown::{self, Target, Targets, Urls};
+use crate::services::tags;
+
+/// A4 portrait, in millimetres
+const PAGE_WIDTH: f32 = 210.0;
+const PAGE_HEIGHT: f32 = 297.0;
+const MARGIN: f32 = 20.0;
+/// Millimetres per typographic point
+const PT: f32 = 0.352_778;
+
+const BODY_SIZE: f32 = 10.5;
+const SMALL_SIZE: f32 = 9.0;
+const CODE_SIZE: f32 = 9.0;
+const HEADING_SIZES: [f32; 6] = [20.0, 16.0, 13.5, 12.0, 11.0, 10.5];
+
+const BLOCK_GAP: f32 = 2.5;
+const LIST_GAP: f32 = 0.8;
+const INDENT: f32 = 6.0;
+const CELL_PADDING: f32 = 1.5;
+/// Images are laid out at this resolution unless that makes them larger than the page
+const IMAGE_DPI: f32 = 96.0;
+
+const BLACK: (f32, f32, f32) = (0.0, 0.0, 0.0);
+const GRAY: (f32, f32, f32) = (0.4, 0.4, 0.4);
+const CODE_BACKGROUND: (f32, f32, f32) = (0.95, 0.95, 0.95);
+const HEADER_BACKGROUND: (f32, f32, f32) = (0.9, 0.9, 0.9);
+const RULE: (f32, f32, f32) = (0.75, 0.75, 0.75);
+
+/// Which tasks a task list export includes (all given conditions must match)
+#[derive(Debug, Default, Deserialize)]
+pub struct TaskFilter {
+    pub project: Option<String>,
+    pub section: Option<String>,
+    /// `open` or `completed`
+    pub status: Option<String>,
+    /// Tasks with this tag or one nested under it
+    pub tag: Option<String>,
+    pub priority: Option<String>,
+    /// Only tasks due on or before this date (`YYYY-MM-DD`)
+    pub due_before: Option<String>,
+}
+
+/// A note (standalone, or a project note when `project` is given) as PDF.
+pub fn note_pdf(id: &str, project: Option<&str>) -> Result<Vec<u8>, String> {
+    let doc = index::read(|idx| markdown::find_doc(idx, "note", id, project).cloned())
+        .map_err(|_| format!("Note not found: {}", id))?;
+
+    let mut writer = Writer::new(&doc.title)?;
+    writer.document(&doc.path, &doc.content, None)?;
+    writer.finish()
+}
+
+/// A project as PDF: its index page, then its notes, then its tasks grouped
+/// by `section` (Active first, Completed last).
+pub fn project_pdf(project_id: &str) -> Result<Vec<u8>, String> {
+    let (project, mut notes, tasks) = index::read(|idx| {
+        let project = markdown::find_doc(idx, "project", project_id, None)
+            .cloned()
+            .map_err(|_| format!("Project not found: {}", project_id))?;
+        let in_project = |doc: &&IndexedDoc, doc_type: &str| {
+            doc.doc_type == doc_type && doc.project_id().as_deref() == Some(project_id)
+        };
+        let notes: Vec<IndexedDoc> = idx
+            .docs()
+            .filter(|doc| in_project(doc, "note"))
+            .cloned()
+            .collect();
+        let tasks: Vec<TaskItem> = idx
+            .docs()
+            .filter(|doc| in_project(doc, "task"))
+            .map(TaskItem::from_doc)
+            .collect();
+        Ok::<_, String>((project, notes, tasks))
+    })?;
+    notes.sort_by_key(|note| note.title.to_lowercase());
+
+    let mut writer = Writer::new(&project.title)?;
+    writer.document(&project.path, &project.content, Some((1, &project.title)))?;
+
+    if !notes.is_empty() {
+        writer.heading(1, &[Span::new("Notes", Face::Bold)]);
+        for note in &notes {
+            writer.document(&note.path, &note.content, Some((2, &note.title)))?;
+        }
+    }
+
+    if !tasks.is_empty() {
+        writer.heading(1, &[Span::new("Tasks", Face::Bold)]);
+        for (section, tasks) in group_by_section(tasks) {
+            writer.heading(2, &[Span::new(&section, Face::Bold)]);
+            for task in &tasks {
+                let checkbox = if task.completed { "[x] " } else { "[ ] " };
+                writer.heading(
+                    4,
+                    &[
+                        Span::new(checkbox, Face::Mono),
+                        Span::new(&task.title, Face::Bold),
+                    ],
+                );
+                writer.meta(&task.meta());
+                writer.document(&task.path, &task.content, None)?;
+            }
+        }
+    }
+
+    writer.finish()
+}
+
+/// Tasks matching `filter` as a PDF table, ordered by project, section and due date.
+pub fn tasks_pdf(filter: &TaskFilter) -> Result<Vec<u8>, String> {
+    if let Some(status) = filter.status.as_deref() {
+        if !matches!(status, "open" | "completed") {
+            return Err(format!(
+                "Invalid status: {} (expected open or completed)",
+                status
+            ));
+        }
+    }
+    if let Some(date) = filter.due_before.as_deref() {
+        NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .map_err(|_| format!("Invalid date: {} (expected YYYY-MM-DD)", date))?;
+    }
+    if let Some(project) = filter.project.as_deref() {
+        index::read(|idx| markdown::find_doc(idx, "project", project, None).map(|_| ()))
+            .map_err(|_| format!("Project not found: {}", project))?;
+    }
+
+    let mut tasks: Vec<TaskItem> = index::read(|idx| {
+        idx.docs()
+            .filter(|doc| doc.doc_type == "task")
+            .map(TaskItem::from_doc)
+            .filter(|task| filter.matches(task))
+            .collect()
+    });
+    // Tasks without a due date go after those with one
+    tasks.sort_by_cached_key(|task| {
+        (
+            task.project_id.clone(),
+            section_rank(&task.section),
+            task.section.clone(),
+            task.due_date.is_none(),
+            task.due_date.clone(),
+            task.title.to_lowercase(),
+        )
+    });
+
+    let mut writer = Writer::new("Tasks")?;
+    writer.heading(1, &[Span::new("Tasks", Face::Bold)]);
+    let mut summary = filter.describe();
+    summary.push(match tasks.len() {
+        1 => "1 task".to_string(),
+        n => format!("{} tasks", n),
+    });
+    writer.meta(&summary.join(" · "));
+    writer.gap(BLOCK_GAP);
+
+    let header = ["", "Title", "Project", "Section", "Priority", "Due"];
+    let mut rows = vec![header
+        .iter()
+        .map(|h| vec![Span::new(h, Face::Bold)])
+        .collect()];
+    for task in &tasks {
+        let checkbox = if task.completed { "[x]" } else { "[ ]" };
+        rows.push(vec![
+            vec![Span::new(checkbox, Face::Mono)],
+            vec![Span::new(&task.title, Face::Regular)],
+            vec![Span::new(
+                task.project_id.as_deref().unwrap_or(""),
+                Face::Regular,
+            )],
+            vec![Span::new(&task.section, Face::Regular)],
+            vec![Span::new(
+                task.priority.as_deref().unwrap_or(""),
+                Face::Regular,
+            )],
+            vec![Span::new(
+                task.due_date.as_deref().unwrap_or(""),
+                Face::Regular,
+            )],
+        ]);
+    }
+    writer.table(&rows, &[0.6, 3.0, 1.3, 1.2, 1.0, 1.2]);
+
+    writer.finish()
+}
+
+impl TaskFilter {
+    fn matches(&self, task: &TaskItem) -> bool {
+        let eq = |filter: &Option<String>, value: Option<&str>| match filter {
+            Some(filter) => value.is_some_and(|v| v.eq_ignore_ascii_case(filter)),
+            None => true,
+        };
+        let status = match self.status.as_deref() {
+            Some("open") => !task.completed,
+            Some("completed") => task.completed,
+            _ => true,
+        };
+        let tag = match self.tag.as_deref() {
+            Some(tag) => {
+                let tag = tags::normalize(tag);
+                task.tags.iter().any(|t| tags::has_tag(t, &tag))
+            }
+            None => true,
+        };
+        let due = match self.due_before.as_deref() {
+            Some(date) => task.due_date.as_deref().is_some_and(|due| due <= date),
+            None => true,
+        };
+
+        status
+            && tag
+            && due
+            && eq(&self.project, task.project_id.as_deref())
+            && eq(&self.section, Some(&task.section))
+            && eq(&self.priority, task.priority.as_deref())
+    }
+
+    /// The conditions in effect, for the export's subtitle
+    fn describe(&self) -> Vec<String> {
+        [
+            ("Project", &self.project),
+            ("Section", &self.section),
+            ("Status", &self.status),
+            ("Tag", &self.tag),
+            ("Priority", &self.priority),
+            ("Due by", &self.due_before),
+        ]
+        .into_iter()
+        .filter_map(|(label, value)| value.as_ref().map(|v| format!("{} {}", label, v)))
+        .collect()
+    }
+}
+
+/// A task as the exports need it
+#[derive(Debug, Clone)]
+struct TaskItem {
+    path: String,
+    title: String,
+    completed: bool,
+    section: String,
+    priority: Option<String>,
+    due_date: Option<String>,
+    project_id: Option<String>,
+    tags: Vec<String>,
+    content: String,
+}
+
+impl TaskItem {
+    fn from_doc(doc: &IndexedDoc) -> Self {
+        let fm = &doc.frontmatter;
+        TaskItem {
+            path: doc.path.clone(),
+            title: doc.title.clone(),
+            completed: frontmatter::get_bool_or(fm, "completed", false),
+            section: frontmatter::get_str_or(fm, "section", "Active"),
+            priority: frontmatter::get_str(fm, "priority"),
+            due_date: frontmatter::get_str(fm, "due_date"),
+            project_id: doc.project_id(),
+            tags: doc.tags.clone(),
+            content: doc.content.clone(),
+        }
+    }
+
+    /// Status, due date, priority and tags on one line
+    fn meta(&self) -> String {
+        let mut parts = vec![if self.completed { "Done" } else { "Open" }.to_string()];
+        if let Some(due) = &self.due_date {
+            parts.push(format!("Due {}", due));
+        }
+        if let Some(priority) = &self.priority {
+            parts.push(format!("Priority {}", priority));
+        }
+        if !self.tags.is_empty() {
+            let tags: Vec<String> = self.tags.iter().map(|t| format!("#{}", t)).collect();
+            parts.push(tags.join(" "));
+        }
+        parts.join(" · ")
+    }
+}
+
+/// Tasks grouped by section: Active first, Completed last, others by name
+/// in between. Tasks keep title order within a section.
+fn group_by_section(mut tasks: Vec<TaskItem>) -> Vec<(String, Vec<TaskItem>)> {
+    tasks.sort_by(|a, b| {
+        (section_rank(&a.section), &a.section, a.title.to_lowercase()).cmp(&(
+            section_rank(&b.section),
+            &b.section,
+            b.title.to_lowercase(),
+        ))
+    });
+
+    let mut groups: Vec<(String, Vec<TaskItem>)> = Vec::new();
+    for task in tasks {
+        match groups.last_mut() {
+            Some((section, group)) if *section == task.section => group.push(task),
+            _ => groups.push((task.section.clone(), vec![task])),
+        }
+    }
+    groups
+}
+
+fn section_rank(section: &str) -> u8 {
+    match section {
+        "Active" => 0,
+        "Completed" => 2,
+        _ => 1,
+    }
+}
+
+/// Wiki links render as their label (no targets resolve) and embedded
+/// assets as data-relative paths the writer loads images from.
+const PDF_URLS: Urls<'static> = Urls {
+    doc: &pdf_doc_url,
+    asset: &pdf_asset_url,
+};
+
+fn pdf_doc_url(_source: &str, target: &Target) -> String {
+    format!("/{}", target.path)
+}
+
+fn pdf_asset_url(_source: &str, asset: &str) -> String {
+    format!("/{}", asset)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Face {
+    Regular,
+    Bold,
+    Italic,
+    BoldItalic,
+    Mono,
+}
+
+impl Face {
+    fn bold(self) -> Face {
+        match self {
+            Face::Regular | Face::Bold => Face::Bold,
+            Face::Italic | Face::BoldItalic => Face::BoldItalic,
+            Face::Mono => Face::Mono,
+        }
+    }
+
+    fn italic(self) -> Face {
+        match self {
+            Face::Regular | Face::Italic => Face::Italic,
+            Face::Bold | Face::BoldItalic => Face::BoldItalic,
+            Face::Mono => Face::Mono,
+        }
+    }
+}
+
+/// A run of text in one face
+#[derive(Debug, Clone, PartialEq)]
+struct Span {
+    text: String,
+    face: Face,
+}
+
+impl Span {
+    fn new(text: &str, face: Face) -> Self {
+        Span {
+            text: encodable(text),
+            face,
+        }
+    }
+}
+
+/// Lays out blocks top to bottom, starting new pages as they fill up.
+struct Writer {
+    doc: PdfDocumentReference,
+    /// Indexed by `Face`
+    fonts: Vec<IndirectFontRef>,
+    layer: PdfLayerReference,
+    /// Top of the next line, from the bottom of the page
+    y: f32,
+    /// Left edge of the current block (lists and quotes indent it)
+    left: f32,
+    color: (f32, f32, f32),
+    /// Data-relative path of the document being written; images resolve against it
+    source: String,
+}
+
+impl Writer {
+    fn new(title: &str) -> Result<Self, String> {
+        let (doc, page, layer) =
+            PdfDocument::new(encodable(title), Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Content");
+        let fonts = [
+            BuiltinFont::Helvetica,
+            BuiltinFont::HelveticaBold,
+            BuiltinFont::HelveticaOblique,
+            BuiltinFont::HelveticaBoldOblique,
+            BuiltinFont::Courier,
+        ]
+        .into_iter()
+        .map(|font| doc.add_builtin_font(font).map_err(|e| e.to_string()))
+        .collect::<Result<Vec<_>, _>>()?;
+        let layer = doc.get_page(page).get_layer(layer);
+
+        Ok(Writer {
+            doc,
+            fonts,
+            layer,
+            y: PAGE_HEIGHT - MARGIN,
+            left: MARGIN,
+            color: BLACK,
+            source: String::new(),
+        })
+    }
+
+    fn finish(self) -> Result<Vec<u8>, String> {
+        self.doc
+            .save_to_bytes()
+            .map_err(|e| format!("Failed to write PDF: {}", e))
+    }
+
+    fn width(&self) -> f32 {
+        PAGE_WIDTH - MARGIN - self.left
+    }
+
+    fn at_page_top(&self) -> bool {
+        self.y >= PAGE_HEIGHT - MARGIN
+    }
+
+    /// Start a new page unless `height` still fits on this one
+    fn reserve(&mut self, height: f32) {
+        if self.y - height < MARGIN && !self.at_page_top() {
+            let (page, layer) = self
+                .doc
+                .add_page(Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Content");
+            self.layer = self.doc.get_page(page).get_layer(layer);
+            self.layer.set_fill_color(rgb(self.color));
+            self.y = PAGE_HEIGHT - MARGIN;
+        }
+    }
+
+    /// Vertical space between blocks (dropped at the top of a page)
+    fn gap(&mut self, height: f32) {
+        if !self.at_page_top() {
+            self.y -= height;
+        }
+    }
+
+    fn set_color(&mut self, color: (f32, f32, f32)) {
+        self.color = color;
+        self.layer.set_fill_color(rgb(color));
+    }
+
+    /// Write a document's body (frontmatter is skipped). With `title`, the
+    /// body is preceded by that heading unless it already starts with it.
+    fn document(
+        &mut self,
+        path: &str,
+        content: &str,
+        title: Option<(u8, &str)>,
+    ) -> Result<(), String> {
+        let body = frontmatter::parse(content).body;
+        let body = markdown::resolve_links(path, &body, &Targets::default(), &PDF_URLS);
+        let tree = ::markdown::to_mdast(&body, &ParseOptions::gfm())
+            .map_err(|e| format!("Failed to parse markdown: {}", e))?;
+        let mut blocks = tree.children().map(Vec::as_slice).unwrap_or_default();
+
+        if let Some((level, title)) = title {
+            match blocks.first() {
+                Some(Node::Heading(h)) if plain_text(&h.children).trim() == title.trim() => {
+                    self.heading(level, &inline(&h.children, Face::Bold));
+                    blocks = &blocks[1..];
+                }
+                _ => self.heading(level, &[Span::new(title, Face::Bold)]),
+            }
+        }
+
+        self.source = path.to_string();
+        self.blocks(blocks, BLOCK_GAP);
+        Ok(())
+    }
+
+    fn blocks(&mut self, nodes: &[Node], gap: f32) {
+        for node in nodes {
+            self.block(node);
+            self.gap(gap);
+        }
+    }
+
+    fn block(&mut self, node: &Node) {
+        match node {
+            Node::Heading(heading) => {
+                self.heading(heading.depth, &inline(&heading.children, Face::Bold))
+            }
+            Node::Paragraph(paragraph) => self.paragraph(&paragraph.children),
+            Node::List(list) => self.list(list),
+            Node::Code(code) => self.code(&code.value),
+            Node::Math(math) => self.code(&math.value),
+            Node::Html(html) => self.code(&html.value),
+            Node::Blockquote(quote) => {
+                let color = self.color;
+                self.left += INDENT;
+                self.set_color(GRAY);
+                self.blocks(&quote.children, BLOCK_GAP);
+                self.y += BLOCK_GAP;
+                self.set_color(color);
+                self.left -= INDENT;
+            }
+            Node::Table(table) => {
+                let rows: Vec<Vec<Vec<Span>>> = table
+                    .children
+                    .iter()
+                    .enumerate()
+                    .map(|(i, row)| {
+                        let face = if i == 0 { Face::Bold } else { Face::Regular };
+                        let cells = row.children().map(Vec::as_slice).unwrap_or_default();
+                        cells
+                            .iter()
+                            .map(|cell| {
+                                inline(cell.children().map(Vec::as_slice).unwrap_or_default(), face)
+                            })
+                            .collect()
+                    })
+                    .collect();
+                let columns = rows.iter().map(Vec::len).max().unwrap_or(0);
+                self.table(&rows, &vec![1.0; columns]);
+            }
+            Node::ThematicBreak(_) => self.rule(),
+            Node::FootnoteDefinition(definition) => {
+                let label = definition
+                    .label
+                    .as_deref()
+                    .unwrap_or(&definition.identifier);
+                let mut spans = vec![Span::new(&format!("[{}] ", label), Face::Regular)];
+                for child in &definition.children {
+                    spans.extend(inline(
+                        child.children().map(Vec::as_slice).unwrap_or_default(),
+                        Face::Regular,
+                    ));
+                }
+                self.text(&spans, SMALL_SIZE);
+            }
+            Node::Definition(_) | Node::Yaml(_) | Node::Toml(_) => {}
+            other => {
+                if let Some(children) = other.children() {
+                    self.blocks(children, BLOCK_GAP);
+                }
+            }
+        }
+    }
+
+    fn heading(&mut self, depth: u8, spans: &[Span]) {
+        let size = HEADING_SIZES[(depth.clamp(1, 6) - 1) as usize];
+        self.gap(size * PT * 0.6);
+        // Keep the heading on the same page as the line after it
+        self.reserve(line_height(size) + line_height(BODY_SIZE));
+        self.text(spans, size);
+        self.gap(1.0);
+    }
+
+    /// Gray small text on its own line
+    fn meta(&mut self, text: &str) {
+        let color = self.color;
+        self.set_color(GRAY);
+        self.text(&[Span::new(text, Face::Regular)], SMALL_SIZE);
+        self.set_color(color);
+    }
+
+    /// Paragraph text, with images laid out as blocks between the text around them
+    fn paragraph(&mut self, children: &[Node]) {
+        let mut start = 0;
+        for (i, child) in children.iter().enumerate() {
+            if let Node::Image(image) = child {
+                self.inline_text(&children[start..i]);
+                self.image(&image.url, &image.alt);
+                start = i + 1;
+            }
+        }
+        self.inline_text(&children[start..]);
+    }
+
+    fn inline_text(&mut self, nodes: &[Node]) {
+        let spans = inline(nodes, Face::Regular);
+        if spans.iter().any(|span| !span.text.trim().is_empty()) {
+            self.text(&spans, BODY_SIZE);
+        }
+    }
+
+    /// Wrap `spans` to the current width and write them line by line
+    fn text(&mut self, spans: &[Span], size: f32) {
+        for line in wrap(spans, size, self.width()) {
+            self.reserve(line_height(size));
+            self.write_line(&line, size, self.left, self.y);
+            self.y -= line_height(size);
+        }
+    }
+
+    /// One line of text whose line box starts at `top`
+    fn write_line(&self, line: &[Span], size: f32, x: f32, top: f32) {
+        let baseline = top - size * PT;
+        let mut x = x;
+        for span in line {
+            let font = &self.fonts[span.face as usize];
+            self.layer
+                .use_text(span.text.clone(), size, Mm(x), Mm(baseline), font);
+            x += text_width(&span.text, span.face, size);
+        }
+    }
+
+    fn list(&mut self, list: &mdast::List) {
+        let start = list.start.unwrap_or(1);
+        let gap = if list.spread { BLOCK_GAP } else { LIST_GAP };
+
+        for (i, item) in list.children.iter().enumerate() {
+            let Node::ListItem(item) = item else {
+                continue;
+            };
+            let (marker, face) = match item.checked {
+                Some(true) => ("[x]".to_string(), Face::Mono),
+                Some(false) => ("[ ]".to_string(), Face::Mono),
+                None if list.ordered => (format!("{}.", start as usize + i), Face::Regular),
+                None => ("\u{2022}".to_string(), Face::Regular),
+            };
+            let marker_width = text_width(&marker, face, BODY_SIZE) + 1.5;
+            let indent = marker_width.max(INDENT);
+
+            self.reserve(line_height(BODY_SIZE));
+            self.write_line(&[Span::new(&marker, face)], BODY_SIZE, self.left, self.y);
+            self.left += indent;
+            self.blocks(&item.children, gap);
+            self.y += gap;
+            self.left -= indent;
+            if i + 1 < list.children.len() {
+                self.gap(gap);
+            }
+        }
+    }
+
+    /// A code block: monospace lines on a gray background, broken at the width
+    fn code(&mut self, code: &str) {
+        let height = line_height(CODE_SIZE);
+        let char_width = text_width(" ", Face::Mono, CODE_SIZE);
+        let max_chars = (((self.width() - 2.0 * CELL_PADDING) / char_width) as usize).max(1);
+
+        for line in code.trim_end_matches('\n').lines() {
+            let chars: Vec<char> = encodable(&line.replace('\t', "    ")).chars().collect();
+            let chunks: Vec<String> = if chars.is_empty() {
+                vec![String::new()]
+            } else {
+                chars
+                    .chunks(max_chars)
+                    .map(|c| c.iter().collect())
+                    .collect()
+            };
+            for chunk in chunks {
+                self.reserve(height);
+                self.fill_rect(
+                    self.left,
+                    self.y - height,
+                    PAGE_WIDTH - MARGIN,
+                    self.y,
+                    CODE_BACKGROUND,
+                );
+                let span = Span {
+                    text: chunk,
+                    face: Face::Mono,
+                };
+                self.write_line(&[span], CODE_SIZE, self.left + CELL_PADDING, self.y);
+                self.y -= height;
+            }
+        }
+    }
+
+    /// A bordered table; the first row is drawn as the header. Column widths
+    /// are the current width shared out in proportion to `weights`.
+    fn table(&mut self, rows: &[Vec<Vec<Span>>], weights: &[f32]) {
+        let total: f32 = weights.iter().sum();
+        if rows.is_empty() || total <= 0.0 {
+            return;
+        }
+        let widths: Vec<f32> = weights.iter().map(|w| self.width() * w / total).collect();
+        let line = line_height(SMALL_SIZE);
+
+        for (i, row) in rows.iter().enumerate() {
+            let cells: Vec<Vec<Vec<Span>>> = widths
+                .iter()
+                .enumerate()
+                .map(|(column, width)| match row.get(column) {
+                    Some(spans) => wrap(spans, SMALL_SIZE, width - 2.0 * CELL_PADDING),
+                    None => Vec::new(),
+                })
+                .collect();
+            let lines = cells.iter().map(Vec::len).max().unwrap_or(0).max(1);
+            let height = lines as f32 * line + 2.0 * CELL_PADDING;
+
+            self.reserve(height);
+            if i == 0 {
+                self.fill_rect(
+                    self.left,
+                    self.y - height,
+                    PAGE_WIDTH - MARGIN,
+                    self.y,
+                    HEADER_BACKGROUND,
+                );
+            }
+            let mut x = self.left;
+            for (cell, width) in cells.iter().zip(&widths) {
+                self.stroke_rect(x, self.y - height, x + width, self.y);
+                let mut top = self.y - CELL_PADDING;
+                for cell_line in cell {
+                    self.write_line(cell_line, SMALL_SIZE, x + CELL_PADDING, top);
+                    top -= line;
+                }
+                x += width;
+            }
+            self.y -= height;
+        }
+    }
+
+    fn rule(&mut self) {
+        self.reserve(2.0);
+        let y = self.y - 1.0;
+        self.layer.set_outline_color(rgb(RULE));
+        self.layer.set_outline_thickness(0.5);
+        self.layer.add_line(Line {
+            points: vec![
+                (Point::new(Mm(self.left), Mm(y)), false),
+                (Point::new(Mm(PAGE_WIDTH - MARGIN), Mm(y)), false),
+            ],
+            is_closed: false,
+        });
+        self.y -= 2.0;
+    }
+
+    /// An image from an assets folder, scaled down to fit the page; images
+    /// that can't be loaded (missing, or a format like SVG) become their alt text.
+    fn image(&mut self, url: &str, alt: &str) {
+        let Some(image) = self.load_image(url) else {
+            let label = if alt.is_empty() { url } else { alt };
+            self.text(
+                &[Span::new(&format!("[image: {}]", label), Face::Italic)],
+                BODY_SIZE,
+            );
+            return;
+        };
+
+        let (px_width, px_height) = (image.width() as f32, image.height() as f32);
+        let natural_width = px_width / IMAGE_DPI * 25.4;
+        let natural_height = px_height / IMAGE_DPI * 25.4;
+        let scale = 1f32
+            .min(self.width() / natural_width)
+            .min((PAGE_HEIGHT - 2.0 * MARGIN) / natural_height);
+        let (width, height) = (natural_width * scale, natural_height * scale);
+
+        self.reserve(height);
+        Image::from_dynamic_image(&image).add_to_layer(
+            self.layer.clone(),
+            ImageTransform {
+                translate_x: Some(Mm(self.left)),
+                translate_y: Some(Mm(self.y - height)),
+                dpi: Some(px_width * 25.4 / width),
+                ..Default::default()
+            },
+        );
+        self.y -= height;
+    }
+
+    fn load_image(&self, url: &str) -> Option<DynamicImage> {
+        let path = markdown::asset_path(&self.source, url)?;
+        let bytes = fs::read(config::data_dir().join(&path)).ok()?;
+        let image = match image_crate::load_from_memory(&bytes) {
+            Ok(image) => image,
+            Err(e) => {
+                tracing::warn!("PDF export: can't embed image {}: {}", path, e);
+                return None;
+            }
+        };
+        // PDF images are RGB(A) with 8 bits per channel
+        Some(if image.color().has_alpha() {
+            DynamicImage::ImageRgba8(image.to_rgba8())
+        } else {
+            DynamicImage::ImageRgb8(image.to_rgb8())
+        })
+    }
+
+    fn fill_rect(&self, x1: f32, y1: f32, x2: f32, y2: f32, color: (f32, f32, f32)) {
+        self.layer.set_fill_color(rgb(color));
+        self.layer
+            .add_rect(Rect::new(Mm(x1), Mm(y1), Mm(x2), Mm(y2)).with_mode(PaintMode::Fill));
+        self.layer.set_fill_color(rgb(self.color));
+    }
+
+    fn stroke_rect(&self, x1: f32, y1: f32, x2: f32, y2: f32) {
+        self.layer.set_outline_color(rgb(RULE));
+        self.layer.set_outline_thickness(0.5);
+        self.layer
+            .add_rect(Rect::new(Mm(x1), Mm(y1), Mm(x2), Mm(y2)).with_mode(PaintMode::Stroke));
+    }
+}
+
+fn rgb((r, g, b): (f32, f32, f32)) -> Color {
+    Color::Rgb(Rgb::new(r, g, b, None))
+}
+
+fn line_height(size: f32) -> f32 {
+    size * PT * 1.35
+}
+
+/// Inline content as spans; emphasis changes the face, links keep their label.
+fn inline(nodes: &[Node], face: Face) -> Vec<Span> {
+    let mut spans = Vec::new();
+    collect_inline(nodes, face, &mut spans);
+    spans
+}
+
+fn collect_inline(nodes: &[Node], face: Face, spans: &mut Vec<Span>) {
+    for node in nodes {
+        match node {
+            Node::Text(text) => push_span(spans, &text.value.replace('\n', " "), face),
+            Node::InlineCode(code) => push_span(spans, &code.value, Face::Mono),
+            Node::InlineMath(math) => push_span(spans, &math.value, Face::Mono),
+            Node::Html(html) => push_span(spans, &html.value, face),
+            Node::Strong(strong) => collect_inline(&strong.children, face.bold(), spans),
+            Node::Emphasis(emphasis) => collect_inline(&emphasis.children, face.italic(), spans),
+            Node::Break(_) => push_span(spans, "\n", face),
+            Node::Image(image) => push_span(spans, &format!("[image: {}]", image.alt), face),
+            Node::FootnoteReference(reference) => {
+                let label = reference.label.as_deref().unwrap_or(&reference.identifier);
+                push_span(spans, &format!("[{}]", label), face);
+            }
+            other => {
+                if let Some(children) = other.children() {
+                    collect_inline(children, face, spans);
+                }
+            }
+        }
+    }
+}
+
+fn push_span(spans: &mut Vec<Span>, text: &str, face: Face) {
+    let text = encodable(text);
+    match spans.last_mut() {
+        Some(last) if last.face == face => last.text.push_str(&text),
+        _ => spans.push(Span { text, face }),
+    }
+}
+
+fn plain_text(nodes: &[Node]) -> String {
+    inline(nodes, Face::Regular)
+        .into_iter()
+        .map(|span| span.text)
+        .collect()
+}
+
+/// Break spans into lines no wider than `width` (mm) at spaces and hard
+/// breaks (`\n`); words longer than a line are broken between characters.
+fn wrap(spans: &[Span], size: f32, width: f32) -> Vec<Vec<Span>> {
+    let mut lines = Vec::new();
+    let mut line: Vec<Span> = Vec::new();
+    let mut line_width = 0.0;
+
+    for span in spans {
+        for (i, part) in span.text.split('\n').enumerate() {
+            if i > 0 {
+                lines.push(trim_line(std::mem::take(&mut line)));
+                line_width = 0.0;
+            }
+            for piece in part.split_inclusive(' ') {
+                let word = piece.trim_end_matches(' ');
+                let word_width = text_width(word, span.face, size);
+                if line_width > 0.0 && line_width + word_width > width {
+                    lines.push(trim_line(std::mem::take(&mut line)));
+                    line_width = 0.0;
+                }
+                if line_width == 0.0 && word.is_empty() {
+                    continue;
+                }
+
+                if word_width <= width {
+                    push_span(&mut line, piece, span.face);
+                    line_width += text_width(piece, span.face, size);
+                    continue;
+                }
+                for c in piece.chars() {
+                    let c_width = char_width(c, span.face) * size / 1000.0 * PT;
+                    if line_width > 0.0 && line_width + c_width > width {
+                        lines.push(std::mem::take(&mut line));
+                        line_width = 0.0;
+                    }
+                    push_span(&mut line, c.encode_utf8(&mut [0; 4]), span.face);
+                    line_width += c_width;
+                }
+            }
+        }
+    }
+    if !line.is_empty() {
+        lines.push(trim_line(line));
+    }
+    lines
+}
+
+fn trim_line(mut line: Vec<Span>) -> Vec<Span> {
+    if let Some(last) = line.last_mut() {
+        last.text.truncate(last.text.trim_end_matches(' ').len());
+    }
+    line.retain(|span| !span.text.is_empty());
+    line
+}
+
+/// Text as the built-in fonts can show it (WinAnsi encoding): Latin-1 and
+/// common typographic punctuation are kept, anything else becomes `?`.
+fn encodable(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            '\t' => ' ',
+            ' '..='~' | '\n' | '\u{a0}'..='\u{ff}' => c,
+            '\u{2018}' | '\u{2019}' | '\u{201c}' | '\u{201d}' | '\u{2013}' | '\u{2014}'
+            | '\u{2022}' | '\u{2026}' | '\u{20ac}' | '\u{2122}' => c,
+            '\u{2192}' => '>',
+            '\u{2713}' | '\u{2714}' => 'x',
+            _ => '?',
+        })
+        .collect()
+}
+
+fn text_width(text: &str, face: Face, size: f32) -> f32 {
+    let units: f32 = text.chars().map(|c| char_width(c, face)).sum();
+    units * size / 1000.0 * PT
+}
+
+/// Advance width in 1/1000 em (Adobe font metrics; the oblique faces share
+/// the upright widths, Courier is fixed-width)
+fn char_width(c: char, face: Face) -> f32 {
+    let table = match face {
+        Face::Mono => return 600.0,
+        Face::Regular | Face::Italic => &HELVETICA_WIDTHS,
+        Face::Bold | Face::BoldItalic => &HELVETICA_BOLD_WIDTHS,
+    };
+    match c {
+        ' '..='~' => table[c as usize - 32] as f32,
+        '\u{2014}' | '\u{2026}' | '\u{2122}' => 1000.0,
+        '\u{2018}' | '\u{2019}' => 222.0,
+        '\u{201c}' | '\u{201d}' => 333.0,
+        '\u{2022}' => 350.0,
+        _ => 556.0,
+    }
+}
+
+/// Helvetica widths of ' ' through '~'
+const HELVETICA_WIDTHS: [u16; 95] = [
+    278, 278, 355, 556, 556, 889, 667, 191, 333, 333, 389, 584, 278, 333, 278,
+    278, // ' '..'/'
+    556, 556, 556, 556, 556, 556, 556, 556, 556, 556, // '0'..'9'
+    278, 278, 584, 584, 584, 556, 1015, // ':'..'@'
+    667, 667, 722, 722, 667, 611, 778, 722, 278, 500, 667, 556, 833, // 'A'..'M'
+    722, 778, 667, 778, 722, 667, 611, 722, 667, 944, 667, 667, 611, // 'N'..'Z'
+    278, 278, 278, 469, 556, 333, // '['..'`'
+    556, 556, 500, 556, 556, 278, 556, 556, 222, 222, 500, 222, 833, // 'a'..'m'
+    556, 556, 556, 556, 333, 500, 278, 556, 500, 722, 500, 500, 500, // 'n'..'z'
+    334, 260, 334, 584, // '{'..'~'
+];
+
+/// Helvetica-Bold widths of ' ' through '~'
+const HELVETICA_BOLD_WIDTHS: [u16; 95] = [
+    278, 333, 474, 556, 556, 889, 722, 238, 333, 333, 389, 584, 278, 333, 278,
+    278, // ' '..'/'
+    556, 556, 556, 556, 556, 556, 556, 556, 556, 556, // '0'..'9'
+    333, 333, 584, 584, 584, 611, 975, // ':'..'@'
+    722, 722, 722, 722, 667, 611, 778, 722, 278, 556, 722, 611, 833, // 'A'..'M'
+    722, 778, 667, 778, 722, 667, 611, 722, 667, 944, 667, 667, 611, // 'N'..'Z'
+    333, 278, 333, 584, 556, 333, // '['..'`'
+    556, 611, 556, 611, 556, 333, 611, 611, 278, 278, 556, 278, 889, // 'a'..'m'
+    611, 611, 611, 611, 389, 556, 333, 611, 556, 778, 556, 556, 500, // 'n'..'z'
+    389, 280, 389, 584, // '{'..'~'
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn texts(lines: &[Vec<Span>]) -> Vec<String> {
+        lines
+            .iter()
+            .map(|line| line.iter().map(|span| span.text.as_str()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_wrap() {
+        let spans = [Span::new("aaa bbb ccc", Face::Regular)];
+        // "aaa bbb" is 12.75mm at 10pt
+        assert_eq!(texts(&wrap(&spans, 10.0, 13.0)), ["aaa bbb", "ccc"]);
+        assert_eq!(texts(&wrap(&spans, 10.0, 100.0)), ["aaa bbb ccc"]);
+
+        let spans = [
+            Span::new("one ", Face::Regular),
+            Span::new("two", Face::Bold),
+            Span::new("\nthree", Face::Regular),
+        ];
+        let lines = wrap(&spans, 10.0, 100.0);
+        assert_eq!(texts(&lines), ["one two", "three"]);
+        assert_eq!(lines[0][1].face, Face::Bold);
+
+        // A word longer than the line is broken between characters
+        let spans = [Span::new("abcdefghij", Face::Mono)];
+        let lines = wrap(&spans, 10.0, 10.0);
+        assert_eq!(texts(&lines), ["abcd", "efgh", "ij"]);
+    }
+
+    #[test]
+    fn test_encodable() {
+        assert_eq!(encodable("Café – “ok” …"), "Café – “ok” …");
+        assert_eq!(encodable("日本 → done ✓"), "?? > done x");
+        assert_eq!(text_width("ab", Face::Mono, 10.0), 2.0 * 6.0 * PT);
+    }
+
+    #[test]
+    fn test_group_by_section() {
+        let task = |title: &str, section: &str| TaskItem {
+            path: format!("projects/p/tasks/{}.md", title),
+            title: title.to_string(),
+            completed: section == "Completed",
+            section: section.to_string(),
+            priority: None,
+            due_date: None,
+            project_id: Some("p".to_string()),
+            tags: Vec::new(),
+            content: String::new(),
+        };
+        let groups = group_by_section(vec![
+            task("b", "Completed"),
+            task("c", "Backlog"),
+            task("z", "Active"),
+            task("a", "Active"),
+        ]);
+        let groups: Vec<(&str, Vec<&str>)> = groups
+            .iter()
+            .map(|(section, tasks)| {
+                (
+                    section.as_str(),
+                    tasks.iter().map(|t| t.title.as_str()).collect(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            groups,
+            [
+                ("Active", vec!["a", "z"]),
+                ("Backlog", vec!["c"]),
+                ("Completed", vec!["b"])
+            ]
+        );
+    }
+}
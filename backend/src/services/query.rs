@@ -0,0 +1,631 @@
+use std::cmp::Ordering;
+use std::collections::HashSet;
+
+use chrono::{Duration, Utc};
+use serde_yaml::Mapping;
+
+use crate::services::frontmatter;
+use crate::services::index::{IndexedDoc, SearchIndex};
+use crate::services::matcher::{MatchOptions, Needle, TextMatcher};
+use crate::services::tags;
+
+/// Parsed search query.
+///
+/// Syntax:
+/// - `word` / `"quoted phrase"` — free text (words match by prefix)
+/// - `key:value` — frontmatter filter (`tag:`, `type:`, `project:`, `due:`,
+///   `status:`, `priority:`, `updated:`, or any user-defined key)
+/// - `key:<v`, `key:<=v`, `key:>v`, `key:>=v`, `key:a..b` — comparisons and ranges
+/// - `key:*` — field is present
+/// - `AND` (implicit), `OR`, `NOT` / `-prefix`, and `( ... )` grouping
+///
+/// `key:value` with an unknown key or an empty value (`https://host/x`, `note:`)
+/// is free text.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Query {
+    Term(String),
+    Phrase(String),
+    /// Regular expression (only produced by the `regex` search mode)
+    Regex(String),
+    Field {
+        key: String,
+        op: FieldOp,
+    },
+    And(Vec<Query>),
+    Or(Vec<Query>),
+    Not(Box<Query>),
+}
+
+/// Comparison applied to a frontmatter field.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FieldOp {
+    Eq(String),
+    Lt(String),
+    Le(String),
+    Gt(String),
+    Ge(String),
+    Range(String, String),
+    Exists,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    LParen,
+    RParen,
+    And,
+    Or,
+    Not,
+    Word(String),
+    Quoted(String),
+    Field(String, String),
+}
+
+/// Keys that can be filtered on whether or not any document sets them
+const BUILT_IN_FIELDS: &[&str] = &[
+    "tags",
+    "type",
+    "project_id",
+    "status",
+    "due_date",
+    "parent_id",
+    "priority",
+    "created",
+    "updated",
+    "id",
+    "title",
+];
+
+/// Parse a query string. Errors are prefixed with "Invalid query".
+/// `is_field` says whether a (normalized) key other than the built-in ones
+/// is a frontmatter field, i.e. whether `key:value` filters on it.
+pub fn parse(input: &str, is_field: impl Fn(&str) -> bool) -> Result<Query, String> {
+    let tokens = lex(input, &is_field)?;
+    let mut parser = Parser { tokens, pos: 0 };
+
+    if parser.tokens.is_empty() {
+        return Ok(Query::And(Vec::new()));
+    }
+
+    let query = parser.parse_or()?;
+    if parser.pos < parser.tokens.len() {
+        return Err(format!(
+            "Invalid query: unexpected {:?}",
+            parser.tokens[parser.pos]
+        ));
+    }
+
+    Ok(query)
+}
+
+fn lex(input: &str, is_field: &impl Fn(&str) -> bool) -> Result<Vec<Token>, String> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+
+        match c {
+            '(' => {
+                tokens.push(Token::LParen);
+                i += 1;
+            }
+            ')' => {
+                tokens.push(Token::RParen);
+                i += 1;
+            }
+            '"' => {
+                let (text, next) = read_quoted(&chars, i)?;
+                tokens.push(Token::Quoted(text));
+                i = next;
+            }
+            '-' if i + 1 < chars.len() && !chars[i + 1].is_whitespace() => {
+                tokens.push(Token::Not);
+                i += 1;
+            }
+            _ => {
+                let start = i;
+                while i < chars.len()
+                    && !chars[i].is_whitespace()
+                    && chars[i] != '('
+                    && chars[i] != ')'
+                    && chars[i] != '"'
+                    && chars[i] != ':'
+                {
+                    i += 1;
+                }
+                let word: String = chars[start..i].iter().collect();
+
+                // key:value (value may be quoted)
+                if i < chars.len() && chars[i] == ':' && !word.is_empty() {
+                    i += 1;
+                    let value = if i < chars.len() && chars[i] == '"' {
+                        let (text, next) = read_quoted(&chars, i)?;
+                        i = next;
+                        text
+                    } else {
+                        let value_start = i;
+                        while i < chars.len()
+                            && !chars[i].is_whitespace()
+                            && chars[i] != '('
+                            && chars[i] != ')'
+                        {
+                            i += 1;
+                        }
+                        chars[value_start..i].iter().collect()
+                    };
+
+                    let key = normalize_key(&word);
+                    if value.is_empty()
+                        || !(BUILT_IN_FIELDS.contains(&key.as_str()) || is_field(&key))
+                    {
+                        // Not a filter: search for the text as written
+                        tokens.push(Token::Word(format!("{}:{}", word, value)));
+                    } else {
+                        tokens.push(Token::Field(word, value));
+                    }
+                    continue;
+                }
+
+                if word.is_empty() {
+                    // Stray ':' — treat it as whitespace
+                    i += 1;
+                    continue;
+                }
+
+                tokens.push(match word.as_str() {
+                    "AND" => Token::And,
+                    "OR" => Token::Or,
+                    "NOT" => Token::Not,
+                    _ => Token::Word(word),
+                });
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// Read a double-quoted string starting at `start`; returns (text, next index).
+fn read_quoted(chars: &[char], start: usize) -> Result<(String, usize), String> {
+    let mut i = start + 1;
+    let mut text = String::new();
+
+    while i < chars.len() {
+        match chars[i] {
+            '"' => return Ok((text, i + 1)),
+            '\\' if i + 1 < chars.len() => {
+                text.push(chars[i + 1]);
+                i += 2;
+            }
+            c => {
+                text.push(c);
+                i += 1;
+            }
+        }
+    }
+
+    Err("Invalid query: unterminated quote".to_string())
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn parse_or(&mut self) -> Result<Query, String> {
+        let mut branches = vec![self.parse_and()?];
+
+        while self.peek() == Some(&Token::Or) {
+            self.pos += 1;
+            branches.push(self.parse_and()?);
+        }
+
+        Ok(if branches.len() == 1 {
+            branches.remove(0)
+        } else {
+            Query::Or(branches)
+        })
+    }
+
+    fn parse_and(&mut self) -> Result<Query, String> {
+        let mut clauses = vec![self.parse_unary()?];
+
+        loop {
+            match self.peek() {
+                Some(Token::And) => {
+                    self.pos += 1;
+                    clauses.push(self.parse_unary()?);
+                }
+                Some(Token::Or) | Some(Token::RParen) | None => break,
+                Some(_) => clauses.push(self.parse_unary()?),
+            }
+        }
+
+        Ok(if clauses.len() == 1 {
+            clauses.remove(0)
+        } else {
+            Query::And(clauses)
+        })
+    }
+
+    fn parse_unary(&mut self) -> Result<Query, String> {
+        if self.peek() == Some(&Token::Not) {
+            self.pos += 1;
+            return Ok(Query::Not(Box::new(self.parse_unary()?)));
+        }
+        self.parse_primary()
+    }
+
+    fn parse_primary(&mut self) -> Result<Query, String> {
+        let token = self
+            .tokens
+            .get(self.pos)
+            .cloned()
+            .ok_or_else(|| "Invalid query: unexpected end of input".to_string())?;
+        self.pos += 1;
+
+        match token {
+            Token::LParen => {
+                let inner = self.parse_or()?;
+                if self.peek() != Some(&Token::RParen) {
+                    return Err("Invalid query: missing ')'".to_string());
+                }
+                self.pos += 1;
+                Ok(inner)
+            }
+            Token::Word(word) => Ok(Query::Term(word)),
+            Token::Quoted(text) => Ok(Query::Phrase(text)),
+            Token::Field(key, value) => Ok(Query::Field {
+                key: normalize_key(&key),
+                op: parse_field_op(&value),
+            }),
+            other => Err(format!("Invalid query: unexpected {:?}", other)),
+        }
+    }
+}
+
+/// Map query shorthands onto frontmatter keys.
+fn normalize_key(key: &str) -> String {
+    match key.to_lowercase().as_str() {
+        "tag" => "tags".to_string(),
+        "project" => "project_id".to_string(),
+        "due" => "due_date".to_string(),
+        "parent" => "parent_id".to_string(),
+        lower => lower.to_string(),
+    }
+}
+
+fn parse_field_op(value: &str) -> FieldOp {
+    if value == "*" {
+        return FieldOp::Exists;
+    }
+    if let Some(v) = value.strip_prefix("<=") {
+        return FieldOp::Le(resolve_value(v));
+    }
+    if let Some(v) = value.strip_prefix(">=") {
+        return FieldOp::Ge(resolve_value(v));
+    }
+    if let Some(v) = value.strip_prefix('<') {
+        return FieldOp::Lt(resolve_value(v));
+    }
+    if let Some(v) = value.strip_prefix('>') {
+        return FieldOp::Gt(resolve_value(v));
+    }
+    if let Some((from, to)) = value.split_once("..") {
+        return FieldOp::Range(resolve_value(from), resolve_value(to));
+    }
+    FieldOp::Eq(resolve_value(value.strip_prefix('=').unwrap_or(value)))
+}
+
+/// Resolve relative date keywords (today, tomorrow, yesterday).
+fn resolve_value(value: &str) -> String {
+    let today = Utc::now().date_naive();
+    let date = match value.to_lowercase().as_str() {
+        "today" => today,
+        "tomorrow" => today + Duration::days(1),
+        "yesterday" => today - Duration::days(1),
+        _ => return value.to_string(),
+    };
+    date.format("%Y-%m-%d").to_string()
+}
+
+// ============ Evaluation ============
+
+/// Paths of all indexed documents matching the query.
+/// The index narrows down candidates; free text is then verified with
+/// `services::matcher` so the match options (case, whole word, diacritics) apply.
+pub fn evaluate<'a>(
+    query: &Query,
+    idx: &'a SearchIndex,
+    options: &MatchOptions,
+) -> Result<HashSet<&'a str>, String> {
+    Ok(match query {
+        Query::Term(text) | Query::Phrase(text) => {
+            let matcher = TextMatcher::new(&[Needle::Literal(text.clone())], options)?;
+            // Pure punctuation has no tokens, so every document is a candidate
+            idx.candidates(text)
+                .into_iter()
+                .filter(|doc| matcher.is_match(&doc.content))
+                .map(|doc| doc.path.as_str())
+                .collect()
+        }
+        Query::Regex(pattern) => {
+            let matcher = TextMatcher::new(&[Needle::Regex(pattern.clone())], options)?;
+            idx.docs()
+                .filter(|doc| matcher.is_match(&doc.content))
+                .map(|doc| doc.path.as_str())
+                .collect()
+        }
+        Query::Field { key, op } => idx
+            .docs()
+            .filter(|doc| field_matches(doc, key, op))
+            .map(|doc| doc.path.as_str())
+            .collect(),
+        Query::And(clauses) => {
+            // Positive clauses narrow the set; negated clauses subtract from it
+            let (negated, positive): (Vec<&Query>, Vec<&Query>) =
+                clauses.iter().partition(|q| matches!(q, Query::Not(_)));
+
+            let mut result: HashSet<&str> = match positive.split_first() {
+                Some((first, rest)) => {
+                    let mut set = evaluate(first, idx, options)?;
+                    for clause in rest {
+                        let other = evaluate(clause, idx, options)?;
+                        set.retain(|p| other.contains(p));
+                    }
+                    set
+                }
+                None => idx.docs().map(|doc| doc.path.as_str()).collect(),
+            };
+
+            for clause in negated {
+                if let Query::Not(inner) = clause {
+                    let excluded = evaluate(inner, idx, options)?;
+                    result.retain(|p| !excluded.contains(p));
+                }
+            }
+
+            result
+        }
+        Query::Or(branches) => {
+            let mut result = HashSet::new();
+            for branch in branches {
+                result.extend(evaluate(branch, idx, options)?);
+            }
+            result
+        }
+        Query::Not(inner) => {
+            let excluded = evaluate(inner, idx, options)?;
+            idx.docs()
+                .map(|doc| doc.path.as_str())
+                .filter(|p| !excluded.contains(p))
+                .collect()
+        }
+    })
+}
+
+/// Free-text needles that are not negated (used to pick matching lines and rank).
+pub fn positive_needles(query: &Query) -> Vec<Needle> {
+    match query {
+        Query::Term(text) | Query::Phrase(text) => vec![Needle::Literal(text.clone())],
+        Query::Regex(pattern) => vec![Needle::Regex(pattern.clone())],
+        Query::And(children) | Query::Or(children) => {
+            children.iter().flat_map(positive_needles).collect()
+        }
+        Query::Field { .. } | Query::Not(_) => Vec::new(),
+    }
+}
+
+fn field_matches(doc: &IndexedDoc, key: &str, op: &FieldOp) -> bool {
+    let values = match key {
+        "type" => vec![doc.doc_type.clone()],
+        "project_id" => doc.project_id().into_iter().collect(),
+        "status" => status_values(&doc.frontmatter),
+        "tags" => {
+            // Frontmatter and inline tags; `tag:area` also matches `area/sub`
+            if let FieldOp::Eq(expected) = op {
+                let expected = tags::normalize(expected);
+                return doc.tags.iter().any(|tag| tags::has_tag(tag, &expected));
+            }
+            doc.tags.clone()
+        }
+        _ => field_values(&doc.frontmatter, key),
+    };
+
+    if let FieldOp::Exists = op {
+        return !values.is_empty();
+    }
+
+    values.iter().any(|actual| value_matches(actual, op))
+}
+
+/// `status:` is shorthand for task completion, and falls back to a
+/// user-defined `status` field for other documents.
+fn status_values(fm: &Mapping) -> Vec<String> {
+    let mut values = field_values(fm, "status");
+    if let Some(completed) = frontmatter::get_bool(fm, "completed") {
+        if completed {
+            values.extend(["done".to_string(), "completed".to_string()]);
+        } else {
+            values.push("open".to_string());
+        }
+    }
+    values
+}
+
+/// Frontmatter values as strings, for any key (sequences yield one value per item).
+fn field_values(fm: &Mapping, key: &str) -> Vec<String> {
+    let seq = frontmatter::get_string_seq(fm, key);
+    if !seq.is_empty() {
+        return seq;
+    }
+    if let Some(s) = frontmatter::get_str(fm, key) {
+        return vec![s];
+    }
+    if let Some(b) = frontmatter::get_bool(fm, key) {
+        return vec![b.to_string()];
+    }
+    if let Some(n) = frontmatter::get_u64(fm, key) {
+        return vec![n.to_string()];
+    }
+    Vec::new()
+}
+
+fn value_matches(actual: &str, op: &FieldOp) -> bool {
+    match op {
+        FieldOp::Eq(expected) => compare(actual, expected) == Ordering::Equal,
+        FieldOp::Lt(expected) => compare(actual, expected) == Ordering::Less,
+        FieldOp::Le(expected) => compare(actual, expected) != Ordering::Greater,
+        FieldOp::Gt(expected) => compare(actual, expected) == Ordering::Greater,
+        FieldOp::Ge(expected) => compare(actual, expected) != Ordering::Less,
+        FieldOp::Range(from, to) => {
+            compare(actual, from) != Ordering::Less && compare(actual, to) != Ordering::Greater
+        }
+        FieldOp::Exists => true,
+    }
+}
+
+/// Compare a frontmatter value against a query value.
+/// Numbers compare numerically, dates by their `YYYY-MM-DD` prefix
+/// (so RFC 3339 timestamps work), everything else case-insensitively.
+fn compare(actual: &str, expected: &str) -> Ordering {
+    if let (Ok(a), Ok(b)) = (actual.parse::<f64>(), expected.parse::<f64>()) {
+        return a.partial_cmp(&b).unwrap_or(Ordering::Equal);
+    }
+
+    if is_date(expected) {
+        if let Some(date) = actual.get(..10).filter(|prefix| is_date(prefix)) {
+            return date.cmp(expected);
+        }
+    }
+
+    actual.to_lowercase().cmp(&expected.to_lowercase())
+}
+
+fn is_date(value: &str) -> bool {
+    chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d").is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_terms_and_fields() {
+        let query = parse("tag:infra project:ops postgres", |_| false).unwrap();
+        assert_eq!(
+            query,
+            Query::And(vec![
+                Query::Field {
+                    key: "tags".to_string(),
+                    op: FieldOp::Eq("infra".to_string()),
+                },
+                Query::Field {
+                    key: "project_id".to_string(),
+                    op: FieldOp::Eq("ops".to_string()),
+                },
+                Query::Term("postgres".to_string()),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_parse_boolean_operators() {
+        let query = parse("(\"load balancer\" OR nginx) -draft", |_| false).unwrap();
+        assert_eq!(
+            query,
+            Query::And(vec![
+                Query::Or(vec![
+                    Query::Phrase("load balancer".to_string()),
+                    Query::Term("nginx".to_string()),
+                ]),
+                Query::Not(Box::new(Query::Term("draft".to_string()))),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_parse_ranges() {
+        let query = parse("due:2026-01-01..2026-02-01 updated:>=2026-01-15", |_| false).unwrap();
+        assert_eq!(
+            query,
+            Query::And(vec![
+                Query::Field {
+                    key: "due_date".to_string(),
+                    op: FieldOp::Range("2026-01-01".to_string(), "2026-02-01".to_string()),
+                },
+                Query::Field {
+                    key: "updated".to_string(),
+                    op: FieldOp::Ge("2026-01-15".to_string()),
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(parse("\"unterminated", |_| false).is_err());
+        assert!(parse("(foo", |_| false).is_err());
+    }
+
+    #[test]
+    fn test_parse_unknown_fields_as_terms() {
+        let query = parse("https://example.com/x owner:alice note: tag:", |key| {
+            key == "owner"
+        })
+        .unwrap();
+        assert_eq!(
+            query,
+            Query::And(vec![
+                Query::Term("https://example.com/x".to_string()),
+                Query::Field {
+                    key: "owner".to_string(),
+                    op: FieldOp::Eq("alice".to_string()),
+                },
+                Query::Term("note:".to_string()),
+                Query::Term("tag:".to_string()),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_compare_dates_and_numbers() {
+        assert_eq!(
+            compare("2026-01-20T10:00:00+00:00", "2026-01-20"),
+            Ordering::Equal
+        );
+        assert_eq!(compare("2026-01-19", "2026-01-20"), Ordering::Less);
+        assert_eq!(compare("10", "9"), Ordering::Greater);
+        assert_eq!(compare("High", "high"), Ordering::Equal);
+        // Byte 10 falls inside "é"; must not panic
+        assert_eq!(compare("Réunion équipe", "2026-01-01"), Ordering::Greater);
+    }
+
+    #[test]
+    fn test_tag_filter_uses_indexed_tags() {
+        let mut idx = SearchIndex::default();
+        idx.insert(
+            std::path::Path::new("data/notes/a.md"),
+            "---\ntags: [Ops]\n---\nMigrate #infra/db tonight",
+        );
+        let doc = idx.get("notes/a.md").unwrap();
+        let tag = |value: &str| field_matches(doc, "tags", &FieldOp::Eq(value.to_string()));
+
+        assert!(tag("ops"));
+        assert!(tag("infra"));
+        assert!(tag("#Infra/DB"));
+        assert!(!tag("infr"));
+        assert!(!tag("db"));
+    }
+}
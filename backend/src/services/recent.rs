@@ -0,0 +1,25 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use chrono::{DateTime, Utc};
+
+lazy_static::lazy_static! {
+    /// Last time each document was opened, keyed by data-relative path.
+    /// Kept in memory only; it just needs to reflect the current session.
+    static ref LAST_ACCESS: Mutex<HashMap<String, DateTime<Utc>>> = Mutex::new(HashMap::new());
+}
+
+/// Record that a document was opened.
+pub fn record_access(path: &str) {
+    if let Ok(mut access) = LAST_ACCESS.lock() {
+        access.insert(path.to_string(), Utc::now());
+    }
+}
+
+/// Snapshot of the last access time of every opened document.
+pub fn last_access() -> HashMap<String, DateTime<Utc>> {
+    LAST_ACCESS
+        .lock()
+        .map(|access| access.clone())
+        .unwrap_or_default()
+}
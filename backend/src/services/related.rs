@@ -0,0 +1,237 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::Serialize;
+
+use crate::services::frontmatter;
+use crate::services::index::{self, IndexedDoc, SearchIndex};
+
+/// A document similar to the one being read
+#[derive(Debug, Serialize)]
+pub struct RelatedNote {
+    pub id: String,
+    pub path: String,
+    pub title: String,
+    pub note_type: String,
+    pub project_id: Option<String>,
+    /// Cosine similarity of the TF-IDF vectors (0..1)
+    pub score: f64,
+    /// Terms contributing most to the similarity
+    pub shared_terms: Vec<String>,
+}
+
+/// Number of shared terms reported per result
+const MAX_SHARED_TERMS: usize = 5;
+
+/// Terms shorter than this carry little meaning
+const MIN_TERM_LEN: usize = 3;
+
+/// Documents most similar to `path`, using TF-IDF vectors over the search index.
+/// Only notes (including project notes) and tasks are considered.
+/// The index is kept current by the watcher, so results always reflect the vault.
+pub fn related_notes(path: &str, limit: usize) -> Result<Vec<RelatedNote>, String> {
+    index::read(|idx| {
+        let doc = idx
+            .get(path)
+            .ok_or_else(|| format!("Note not found: {}", path))?;
+        Ok(related_in(idx, doc, limit))
+    })
+}
+
+fn related_in(idx: &SearchIndex, doc: &IndexedDoc, limit: usize) -> Vec<RelatedNote> {
+    let n = idx.len() as f64;
+    let idf = |term: &str| -> f64 {
+        let df = idx.postings(term).map_or(0, HashMap::len) as f64;
+        if df == 0.0 {
+            0.0
+        } else {
+            (n / df).ln()
+        }
+    };
+
+    let vector = weights(doc, &idf);
+    let norm = vector.values().map(|w| w * w).sum::<f64>().sqrt();
+    if norm == 0.0 {
+        return Vec::new();
+    }
+
+    // Every document containing one of the terms; the postings cover the whole
+    // file, so this is a superset of the documents sharing a weighted term
+    let candidates: HashSet<&str> = vector
+        .keys()
+        .filter_map(|term| idx.postings(term))
+        .flat_map(HashMap::keys)
+        .map(String::as_str)
+        .filter(|other| *other != doc.path)
+        .collect();
+
+    let mut related: Vec<(&IndexedDoc, f64, Vec<String>)> = candidates
+        .into_iter()
+        .filter_map(|path| idx.get(path))
+        .filter(|other| is_comparable(other))
+        .filter_map(|other| {
+            let other_vector = weights(other, &idf);
+            let other_norm = other_vector.values().map(|w| w * w).sum::<f64>().sqrt();
+            let dot: f64 = vector
+                .iter()
+                .filter_map(|(term, w)| other_vector.get(term).map(|o| w * o))
+                .sum();
+            (dot > 0.0 && other_norm > 0.0).then(|| {
+                let shared = shared_terms(&vector, &other_vector);
+                (other, dot / (norm * other_norm), shared)
+            })
+        })
+        .collect();
+
+    related.sort_by(|(a, a_score, _), (b, b_score, _)| {
+        b_score
+            .partial_cmp(a_score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| a.path.cmp(&b.path))
+    });
+
+    related
+        .into_iter()
+        .take(limit)
+        .map(|(other, score, shared_terms)| RelatedNote {
+            id: other.id.clone(),
+            path: other.path.clone(),
+            title: other.title.clone(),
+            note_type: other.doc_type.clone(),
+            project_id: other.project_id(),
+            score,
+            shared_terms,
+        })
+        .collect()
+}
+
+/// TF-IDF weights of the terms in a document's body, title and tags.
+/// Other frontmatter (ids, timestamps, status) says nothing about the topic.
+fn weights(doc: &IndexedDoc, idf: &impl Fn(&str) -> f64) -> HashMap<String, f64> {
+    let body = frontmatter::parse(&doc.content).body;
+    let tags = frontmatter::get_string_seq(&doc.frontmatter, "tags");
+
+    let mut terms: HashMap<String, u32> = HashMap::new();
+    for text in [body.as_str(), doc.title.as_str()]
+        .into_iter()
+        .chain(tags.iter().map(String::as_str))
+    {
+        for term in index::tokenize(text).filter(|t| is_meaningful(t)) {
+            *terms.entry(term).or_insert(0) += 1;
+        }
+    }
+
+    terms
+        .into_iter()
+        .map(|(term, tf)| {
+            let weight = tf_weight(tf) * idf(&term);
+            (term, weight)
+        })
+        .filter(|(_, w)| *w > 0.0)
+        .collect()
+}
+
+/// Shared terms ordered by how much they contribute to the similarity.
+fn shared_terms(vector: &HashMap<String, f64>, other: &HashMap<String, f64>) -> Vec<String> {
+    let mut shared: Vec<(&String, f64)> = vector
+        .iter()
+        .filter_map(|(term, w)| other.get(term).map(|o| (term, w * o)))
+        .collect();
+
+    shared.sort_by(|(a, a_w), (b, b_w)| {
+        b_w.partial_cmp(a_w)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| a.cmp(b))
+    });
+
+    shared
+        .into_iter()
+        .take(MAX_SHARED_TERMS)
+        .map(|(term, _)| term.clone())
+        .collect()
+}
+
+/// Sublinear term frequency, so one long note can't dominate
+fn tf_weight(tf: u32) -> f64 {
+    1.0 + (tf as f64).ln()
+}
+
+/// Skip short tokens and numbers
+fn is_meaningful(term: &str) -> bool {
+    term.chars().count() >= MIN_TERM_LEN && !term.chars().all(|c| c.is_ascii_digit())
+}
+
+/// Notes (project notes under `projects/{id}/notes/` included, as they are
+/// typed `note`) and tasks. Daily notes, project index files and views are not.
+fn is_comparable(doc: &IndexedDoc) -> bool {
+    matches!(doc.doc_type.as_str(), "note" | "task")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    #[test]
+    fn test_related_ranking() {
+        let mut idx = SearchIndex::default();
+        idx.insert(
+            Path::new("data/notes/pg.md"),
+            "---\ntitle: Postgres\n---\nPostgres replication and postgres backups",
+        );
+        idx.insert(
+            Path::new("data/notes/backup.md"),
+            "---\ntitle: Nightly backups\n---\nPostgres backups to object storage",
+        );
+        idx.insert(
+            Path::new("data/notes/other.md"),
+            "---\ntitle: Other\n---\nUnrelated",
+        );
+        idx.insert(Path::new("data/notes/garden.md"), "Tomatoes need sun");
+        idx.insert(
+            Path::new("data/daily/2026-01-01.md"),
+            "postgres backups postgres replication",
+        );
+
+        let doc = idx.get("notes/pg.md").unwrap();
+        let related = related_in(&idx, doc, 10);
+
+        assert_eq!(related.len(), 1);
+        assert_eq!(related[0].path, "notes/backup.md");
+        assert!(related[0].score > 0.0 && related[0].score <= 1.0);
+        assert!(related[0].shared_terms.contains(&"backups".to_string()));
+    }
+
+    #[test]
+    fn test_related_ignores_backend_frontmatter() {
+        let mut idx = SearchIndex::default();
+        let fm = "---\nid: x\ntype: task\nstatus: active\nsection: Active\ncreated: 2026-01-01T10:00:00Z\n---\n";
+        idx.insert(
+            Path::new("data/projects/p/tasks/a.md"),
+            &format!("{}Paint fence", fm),
+        );
+        idx.insert(
+            Path::new("data/projects/p/tasks/b.md"),
+            &format!("{}Water tomatoes", fm),
+        );
+        idx.insert(Path::new("data/notes/c.md"), "Unrelated");
+
+        let doc = idx.get("projects/p/tasks/a.md").unwrap();
+        assert!(related_in(&idx, doc, 10).is_empty());
+    }
+
+    #[test]
+    fn test_related_score_at_most_one() {
+        let mut idx = SearchIndex::default();
+        let content = "---\ntitle: Postgres backups\ntags: [postgres]\nstatus: active\n---\nPostgres backups with replication";
+        idx.insert(Path::new("data/notes/a.md"), content);
+        idx.insert(Path::new("data/notes/b.md"), content);
+        idx.insert(Path::new("data/notes/c.md"), "Tomatoes need sun");
+
+        let doc = idx.get("notes/a.md").unwrap();
+        let related = related_in(&idx, doc, 10);
+
+        assert_eq!(related.len(), 1);
+        assert!(related[0].score <= 1.0);
+        assert!((related[0].score - 1.0).abs() < 1e-9);
+    }
+}
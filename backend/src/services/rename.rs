@@ -0,0 +1,535 @@
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::Path;
+
+use serde::Serialize;
+use serde_yaml::{Mapping, Value};
+
+use crate::config;
+use crate::services::filesystem;
+use crate::services::frontmatter;
+use crate::services::git::{self, CommitInfo};
+use crate::services::index;
+use crate::services::links::{self, LinkRef};
+
+/// Outcome of moving or renaming a note
+#[derive(Debug, Serialize)]
+pub struct MoveResult {
+    pub id: String,
+    pub from: String,
+    pub to: String,
+    /// Other files whose links were rewritten
+    pub updated_files: Vec<String>,
+    /// Links rewritten across the vault, including relative links in the moved note
+    pub links_rewritten: usize,
+    /// The commit recording the move (None if git is unavailable)
+    pub commit: Option<CommitInfo>,
+}
+
+/// Outcome of moving or renaming a folder of notes
+#[derive(Debug, Serialize)]
+pub struct FolderMoveResult {
+    pub from: String,
+    pub to: String,
+    /// New paths of the notes that moved with the folder
+    pub moved: Vec<String>,
+    pub updated_files: Vec<String>,
+    pub links_rewritten: usize,
+    pub commit: Option<CommitInfo>,
+}
+
+/// One note changing path (and possibly id), for rewriting links to it
+#[derive(Debug, Clone)]
+struct Renamed {
+    from: String,
+    to: String,
+    old_id: String,
+    new_id: String,
+    title: String,
+}
+
+impl Renamed {
+    fn is_path(&self, path: &str) -> bool {
+        links::normalize_key(path) == links::normalize_key(&self.from)
+    }
+
+    /// Wiki links by title keep working; ids, filenames and paths follow the move.
+    fn wiki(&self, target: &str) -> Option<String> {
+        let key = links::normalize_key(target);
+        let stem = |path: &str| {
+            let path = path.trim_end_matches(".md");
+            path.rsplit('/').next().unwrap_or(path).to_string()
+        };
+
+        if key == links::normalize_key(&self.title) {
+            None
+        } else if key == links::normalize_key(&self.from) {
+            let path = self.to.trim_end_matches(".md");
+            Some(if target.ends_with(".md") {
+                self.to.clone()
+            } else {
+                path.to_string()
+            })
+        } else if key == links::normalize_key(&self.old_id) {
+            (self.new_id != self.old_id).then(|| self.new_id.clone())
+        } else if key == links::normalize_key(&stem(&self.from)) {
+            let new_stem = stem(&self.to);
+            (new_stem != stem(&self.from)).then_some(new_stem)
+        } else {
+            None
+        }
+    }
+}
+
+/// New target for a link in a file moving from `source_from` to `source_to`
+/// (the same path if it stays put), or None if the link still works.
+/// Relative links are rebased when either end moved.
+fn rewrite_target(
+    moves: &[Renamed],
+    source_from: &str,
+    source_to: &str,
+    target: LinkRef,
+) -> Option<String> {
+    match target {
+        LinkRef::Wiki(target) => moves.iter().find_map(|m| m.wiki(target)),
+        LinkRef::Markdown(dest) => {
+            let resolved = links::resolve_path(source_from, dest)?;
+            let moved = moves.iter().find(|m| m.is_path(&resolved));
+
+            if dest.starts_with('/') {
+                return moved.map(|m| format!("/{}", m.to.replace(' ', "%20")));
+            }
+            if moved.is_none() && source_from == source_to {
+                return None;
+            }
+
+            let target = moved.map_or(resolved, |m| m.to.clone());
+            let rebased = links::relative_path(source_to, &target);
+            (rebased != dest).then_some(rebased)
+        }
+    }
+}
+
+/// A moved note's new content
+struct MovedFile {
+    renamed: Renamed,
+    /// Content as read, so untouched frontmatter is written back as it was
+    original: String,
+    frontmatter: Mapping,
+    body: String,
+    /// Whether anything but the path changed (otherwise the file isn't rewritten)
+    changed: bool,
+}
+
+/// Pending writes for a set of moves
+struct Plan {
+    files: Vec<MovedFile>,
+    /// Other files with rewritten links: (path, new content)
+    edits: Vec<(String, String)>,
+    links_rewritten: usize,
+}
+
+/// Work out the new content of every moved note and every file linking to one.
+/// Moves are (from, to, new id) with data-relative paths. Ids missing from
+/// frontmatter are written down, so they no longer depend on the path.
+fn plan(moves: &[(String, String, Option<String>)]) -> Result<Plan, String> {
+    let root = config::data_dir();
+    let mut files = Vec::new();
+
+    let mut pinned = BTreeSet::new();
+
+    for (from, to, new_id) in moves {
+        let from_abs = root.join(from);
+        let content = fs::read_to_string(&from_abs).map_err(|e| e.to_string())?;
+        let (mut fm, body, has_fm) = frontmatter::parse_frontmatter(&content);
+        let mut changed = false;
+
+        // Path-derived ids can collide across folders (`a/b-c` and `a-b/c`)
+        if frontmatter::get_str(&fm, "id").is_none() {
+            let id = unique_id(&frontmatter::derive_id_from_path(&from_abs), from, &pinned);
+            pinned.insert(id.clone());
+            fm.insert(Value::from("id"), Value::from(id));
+        }
+        if !has_fm || !frontmatter::is_frontmatter_complete(&fm) {
+            frontmatter::ensure_frontmatter(&mut fm, &from_abs);
+            changed = true;
+        }
+        let old_id = frontmatter::get_str(&fm, "id")
+            .unwrap_or_else(|| frontmatter::derive_id_from_path(&from_abs));
+        let new_id = new_id.clone().unwrap_or_else(|| old_id.clone());
+        if new_id != old_id {
+            fm.insert(Value::from("id"), Value::from(new_id.clone()));
+            changed = true;
+        }
+
+        // Moving into a project sets project_id; moving out of one clears it
+        match (note_location(from), note_location(to)) {
+            (_, Some(Some(project_id)))
+                if frontmatter::get_str(&fm, "project_id").as_ref() != Some(&project_id) =>
+            {
+                fm.insert(Value::from("project_id"), Value::from(project_id));
+                changed = true;
+            }
+            (_, Some(Some(_))) => {}
+            (Some(Some(_)), _) => {
+                changed |= fm.remove(Value::from("project_id")).is_some();
+            }
+            _ => {}
+        }
+
+        let title = frontmatter::get_str(&fm, "title").unwrap_or_else(|| {
+            from_abs
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or_default()
+                .to_string()
+        });
+
+        files.push(MovedFile {
+            renamed: Renamed {
+                from: from.clone(),
+                to: to.clone(),
+                old_id,
+                new_id,
+                title,
+            },
+            original: content,
+            frontmatter: fm,
+            body,
+            changed,
+        });
+    }
+
+    let renames: Vec<Renamed> = files.iter().map(|f| f.renamed.clone()).collect();
+    let mut links_rewritten = 0;
+
+    for file in &mut files {
+        let (from, to) = (&file.renamed.from, &file.renamed.to);
+        let (body, count) =
+            links::rewrite_links(&file.body, |t| rewrite_target(&renames, from, to, t));
+        if count > 0 {
+            file.body = body;
+            file.changed = true;
+            links_rewritten += count;
+        }
+    }
+
+    let moved: BTreeSet<&str> = renames.iter().map(|r| r.from.as_str()).collect();
+    let sources: BTreeSet<String> = renames
+        .iter()
+        .flat_map(|r| linking_documents(&r.from))
+        .filter(|source| !moved.contains(source.as_str()))
+        .collect();
+
+    let mut edits = Vec::new();
+    for source in sources {
+        let old = fs::read_to_string(root.join(&source)).map_err(|e| e.to_string())?;
+        let (new, count) =
+            links::rewrite_links(&old, |t| rewrite_target(&renames, &source, &source, t));
+        if count > 0 {
+            links_rewritten += count;
+            edits.push((source, new));
+        }
+    }
+
+    Ok(Plan {
+        files,
+        edits,
+        links_rewritten,
+    })
+}
+
+/// Write the planned content (moved notes must already be at their new paths)
+/// and record everything as one commit, along with the `moved` folders.
+fn finish(plan: &mut Plan, moved: &[&str], message: &str) -> Result<Option<CommitInfo>, String> {
+    let root = config::data_dir();
+
+    for file in &mut plan.files {
+        let to_abs = root.join(&file.renamed.to);
+        index::remove_file(&root.join(&file.renamed.from));
+
+        if file.changed {
+            frontmatter::update_frontmatter(&mut file.frontmatter);
+            let content = frontmatter::rewrite(&file.original, &file.frontmatter, &file.body)?;
+            filesystem::atomic_write(&to_abs, content.as_bytes())?;
+        } else {
+            crate::watcher::mark_file_saved(&file.renamed.to);
+            index::refresh_file(&to_abs);
+        }
+    }
+
+    for (path, content) in &plan.edits {
+        filesystem::atomic_write(&root.join(path), content.as_bytes())?;
+    }
+
+    let mut paths: Vec<&str> = moved.to_vec();
+    for file in &plan.files {
+        paths.push(&file.renamed.from);
+        paths.push(&file.renamed.to);
+    }
+    paths.extend(plan.edits.iter().map(|(path, _)| path.as_str()));
+
+    match git::commit_paths(&paths, message) {
+        Ok(info) => Ok(Some(info)),
+        Err(err) => {
+            tracing::warn!("Move applied but not committed: {}", err);
+            Ok(None)
+        }
+    }
+}
+
+/// Move a note or project note to data-relative `to`, keeping its id
+/// (or remapping it to `new_id`), and rewrite every link to it across the vault.
+/// Everything is recorded as a single commit.
+pub fn move_note(
+    from: &Path,
+    to: &str,
+    new_id: Option<&str>,
+    message: Option<&str>,
+) -> Result<MoveResult, String> {
+    let root = config::data_dir();
+    let from_rel = filesystem::normalize_path(from);
+
+    if note_location(&from_rel).is_none() {
+        return Err(format!(
+            "Invalid source: only notes and project notes can be moved ({})",
+            from_rel
+        ));
+    }
+    let to_rel = validate_destination(to)?;
+    if to_rel == from_rel {
+        return Err("Invalid destination: same as the current path".to_string());
+    }
+    let to_abs = root.join(&to_rel);
+    if to_abs.exists() {
+        return Err(format!("Note already exists: {}", to_rel));
+    }
+    if let Some(id) = new_id {
+        if id.is_empty()
+            || !id
+                .chars()
+                .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+        {
+            return Err(format!("Invalid id: {}", id));
+        }
+        let taken = index::read(|idx| idx.docs_with_id(id).any(|doc| doc.path != from_rel));
+        if taken {
+            return Err(format!("Note id already exists: {}", id));
+        }
+    }
+
+    let mut plan = plan(&[(from_rel.clone(), to_rel.clone(), new_id.map(String::from))])?;
+
+    if let Some(parent) = to_abs.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    crate::watcher::mark_file_saved(&from_rel);
+    fs::rename(from, &to_abs).map_err(|e| e.to_string())?;
+
+    let default_message = format!("Move {} to {}", from_rel, to_rel);
+    let commit = finish(&mut plan, &[], message.unwrap_or(&default_message))?;
+
+    Ok(MoveResult {
+        id: plan.files[0].renamed.new_id.clone(),
+        from: from_rel,
+        to: to_rel,
+        updated_files: plan.edits.into_iter().map(|(path, _)| path).collect(),
+        links_rewritten: plan.links_rewritten,
+        commit,
+    })
+}
+
+/// Move (or rename) a folder with everything in it, keeping note ids and
+/// rewriting links to the moved notes. Paths are data-relative and validated
+/// by the caller. `extra` files (data-relative path, content) are written after
+/// the move and committed with it.
+pub fn move_folder(
+    from: &str,
+    to: &str,
+    extra: &[(String, String)],
+    message: Option<&str>,
+) -> Result<FolderMoveResult, String> {
+    let root = config::data_dir();
+    let (from_abs, to_abs) = (root.join(from), root.join(to));
+
+    if !from_abs.is_dir() {
+        return Err(format!("Folder not found: {}", from));
+    }
+    if to_abs.exists() {
+        return Err(format!("Folder already exists: {}", to));
+    }
+    if Path::new(to).starts_with(from) {
+        return Err("Invalid destination: a folder can't move into itself".to_string());
+    }
+
+    let moves: Vec<(String, String, Option<String>)> = index::read(|idx| {
+        idx.docs()
+            .filter(|doc| Path::new(&doc.path).starts_with(from))
+            .map(|doc| {
+                let rest = &doc.path[from.len()..];
+                (doc.path.clone(), format!("{}{}", to, rest), None)
+            })
+            .collect()
+    });
+
+    let mut plan = plan(&moves)?;
+
+    if let Some(parent) = to_abs.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    for (from_rel, _, _) in &moves {
+        crate::watcher::mark_file_saved(from_rel);
+    }
+    fs::rename(&from_abs, &to_abs).map_err(|e| e.to_string())?;
+    for (path, content) in extra {
+        filesystem::atomic_write(&root.join(path), content.as_bytes())?;
+    }
+
+    let mut committed = vec![from, to];
+    committed.extend(extra.iter().map(|(path, _)| path.as_str()));
+    let default_message = format!("Move {} to {}", from, to);
+    let commit = finish(&mut plan, &committed, message.unwrap_or(&default_message))?;
+
+    let mut moved: Vec<String> = moves.into_iter().map(|(_, to, _)| to).collect();
+    moved.sort();
+
+    Ok(FolderMoveResult {
+        from: from.to_string(),
+        to: to.to_string(),
+        moved,
+        updated_files: plan.edits.into_iter().map(|(path, _)| path).collect(),
+        links_rewritten: plan.links_rewritten,
+        commit,
+    })
+}
+
+/// `id`, or `id-2`, `id-3`, ... if another document (or an id pinned
+/// earlier in the same move) already has it.
+fn unique_id(id: &str, path: &str, pinned: &BTreeSet<String>) -> String {
+    let taken = |candidate: &str| {
+        pinned.contains(candidate)
+            || index::read(|idx| idx.docs_with_id(candidate).any(|doc| doc.path != path))
+    };
+    let mut candidate = id.to_string();
+    let mut n = 2;
+    while taken(&candidate) {
+        candidate = format!("{}-{}", id, n);
+        n += 1;
+    }
+    candidate
+}
+
+/// Paths of the other documents linking to the one at `path`.
+fn linking_documents(path: &str) -> BTreeSet<String> {
+    index::read(|idx| {
+        let Some(doc) = idx.get(path) else {
+            return BTreeSet::new();
+        };
+        links::target_keys(doc)
+            .iter()
+            .flat_map(|key| idx.docs_linking_to(key))
+            .filter(|source| source.path != path)
+            .map(|source| source.path.clone())
+            .collect()
+    })
+}
+
+/// Where notes may live: `notes/**` (Some(None)) or
+/// `projects/{id}/notes/**` (Some(Some(id))). Assets folders are excluded.
+fn note_location(path: &str) -> Option<Option<String>> {
+    let parts: Vec<&str> = path.split('/').collect();
+    if !path.ends_with(".md") || parts.contains(&"assets") {
+        return None;
+    }
+    match parts.as_slice() {
+        ["notes", _, ..] => Some(None),
+        ["projects", project, "notes", _, ..] => Some(Some(project.to_string())),
+        _ => None,
+    }
+}
+
+/// Normalise a data-relative destination (adding `.md`) and check it is a note location.
+fn validate_destination(to: &str) -> Result<String, String> {
+    let mut to = to.trim().trim_start_matches('/').replace('\\', "/");
+    if !to.ends_with(".md") {
+        to.push_str(".md");
+    }
+
+    let valid_parts = to
+        .split('/')
+        .all(|part| !part.is_empty() && part != "." && part != ".." && !part.starts_with('.'));
+    if !valid_parts {
+        return Err(format!("Invalid destination: {}", to));
+    }
+
+    match note_location(&to) {
+        None => Err(format!(
+            "Invalid destination: {} (must be under notes/ or projects/<id>/notes/)",
+            to
+        )),
+        Some(Some(project_id))
+            if !config::data_dir()
+                .join("projects")
+                .join(&project_id)
+                .exists() =>
+        {
+            Err(format!(
+                "Invalid destination: project not found: {}",
+                project_id
+            ))
+        }
+        Some(_) => Ok(to),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn renamed(from: &str, to: &str) -> Renamed {
+        Renamed {
+            from: from.to_string(),
+            to: to.to_string(),
+            old_id: "pg".to_string(),
+            new_id: "pg".to_string(),
+            title: "Postgres Guide".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_rewrite_inbound_links() {
+        let moves = [renamed("notes/pg.md", "projects/ops/notes/postgres.md")];
+        let content =
+            "[[pg]] [[Postgres Guide]] [[notes/pg|guide]] [g](pg.md#setup) [o](other.md)\n";
+        let (new, count) = links::rewrite_links(content, |t| {
+            rewrite_target(&moves, "notes/backup.md", "notes/backup.md", t)
+        });
+
+        assert_eq!(count, 2);
+        assert_eq!(
+            new,
+            "[[pg]] [[Postgres Guide]] [[projects/ops/notes/postgres|guide]] \
+             [g](../projects/ops/notes/postgres.md#setup) [o](other.md)\n"
+        );
+    }
+
+    #[test]
+    fn test_rebase_moved_links() {
+        // A folder move: links between notes moving together stay as they are
+        let moves = [
+            renamed("notes/db/pg.md", "notes/infra/db/pg.md"),
+            renamed("notes/db/backup.md", "notes/infra/db/backup.md"),
+        ];
+        let content =
+            "![img](../assets/a.png) [b](backup.md) [web](https://x.org) [abs](/daily/d.md)\n";
+        let (new, count) = links::rewrite_links(content, |t| {
+            rewrite_target(&moves, "notes/db/pg.md", "notes/infra/db/pg.md", t)
+        });
+
+        assert_eq!(count, 1);
+        assert_eq!(
+            new,
+            "![img](../../assets/a.png) [b](backup.md) [web](https://x.org) [abs](/daily/d.md)\n"
+        );
+    }
+}
@@ -0,0 +1,235 @@
+use std::fs;
+
+use serde::Serialize;
+use serde_yaml::{Mapping, Value};
+
+use crate::config;
+use crate::services::filesystem;
+use crate::services::frontmatter;
+use crate::services::git::{self, CommitInfo, DiffInfo, DiffStats};
+use crate::services::matcher::TextMatcher;
+use crate::services::query;
+use crate::services::search::{self, SearchOptions};
+
+/// Frontmatter fields a replacement may change; the rest (`id`, `type`,
+/// `created`, `section`, ...) belongs to the backend
+const REPLACEABLE_FIELDS: [&str; 3] = ["title", "tags", "aliases"];
+
+/// A search-and-replace across the vault
+pub struct Replacement<'a> {
+    pub query: &'a str,
+    pub replacement: &'a str,
+    pub options: SearchOptions,
+    /// Only touch these files (e.g. the ones confirmed in the preview)
+    pub paths: Option<&'a [String]>,
+}
+
+/// Outcome of an applied replacement
+#[derive(Debug, Serialize)]
+pub struct ReplaceResult {
+    pub files_changed: usize,
+    pub replacements: usize,
+    pub files: Vec<String>,
+    /// The commit recording the change (None if git is unavailable)
+    pub commit: Option<CommitInfo>,
+}
+
+/// A pending edit to one file
+struct FileEdit {
+    path: String,
+    old: String,
+    new: String,
+    replacements: usize,
+}
+
+/// Show what a replacement would change, as a diff per file.
+pub fn preview(replacement: &Replacement) -> Result<DiffInfo, String> {
+    let edits = plan(replacement)?;
+
+    let files = edits
+        .iter()
+        .map(|edit| git::diff_text(&edit.path, &edit.old, &edit.new))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let stats = DiffStats {
+        files_changed: files.len(),
+        insertions: files.iter().map(|f| f.additions).sum(),
+        deletions: files.iter().map(|f| f.deletions).sum(),
+    };
+
+    Ok(DiffInfo { files, stats })
+}
+
+/// Apply a replacement to every matching file and record it as one commit.
+pub fn apply(replacement: &Replacement, message: Option<&str>) -> Result<ReplaceResult, String> {
+    let edits = plan(replacement)?;
+    let root = config::data_dir();
+
+    for edit in &edits {
+        filesystem::atomic_write(&root.join(&edit.path), edit.new.as_bytes())?;
+    }
+
+    let commit = if edits.is_empty() {
+        None
+    } else {
+        let default_message = format!(
+            "Replace \"{}\" with \"{}\"",
+            replacement.query, replacement.replacement
+        );
+        let paths: Vec<&str> = edits.iter().map(|edit| edit.path.as_str()).collect();
+        match git::commit_paths(&paths, message.unwrap_or(&default_message)) {
+            Ok(info) => Some(info),
+            Err(err) => {
+                tracing::warn!("Replacement applied but not committed: {}", err);
+                None
+            }
+        }
+    };
+
+    Ok(ReplaceResult {
+        files_changed: edits.len(),
+        replacements: edits.iter().map(|e| e.replacements).sum(),
+        files: edits.into_iter().map(|e| e.path).collect(),
+        commit,
+    })
+}
+
+/// Work out the new content of every file the search matches.
+fn plan(replacement: &Replacement) -> Result<Vec<FileEdit>, String> {
+    let parsed = search::parse_query(replacement.query, replacement.options.mode)?;
+    let needles = query::positive_needles(&parsed);
+    if needles.is_empty() {
+        return Err("Invalid query: nothing to replace".to_string());
+    }
+    let matcher = TextMatcher::new(&needles, &replacement.options.matching)?;
+
+    let page = search::search_notes(replacement.query, &replacement.options, usize::MAX, 0)?;
+    let root = config::data_dir();
+    let mut edits = Vec::new();
+
+    for result in page.results {
+        if let Some(paths) = replacement.paths {
+            if !paths.contains(&result.path) {
+                continue;
+            }
+        }
+
+        // Read from disk rather than the index so we never write back stale content
+        let old = fs::read_to_string(root.join(&result.path)).map_err(|e| e.to_string())?;
+        let (new, count) = replace_content(&old, &matcher, replacement.replacement)
+            .map_err(|err| format!("{} in {}", err, result.path))?;
+        if count == 0 {
+            continue;
+        }
+
+        edits.push(FileEdit {
+            path: result.path,
+            old,
+            new,
+            replacements: count,
+        });
+    }
+
+    edits.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(edits)
+}
+
+/// Replace in the body and in the user-facing frontmatter fields
+/// (`REPLACEABLE_FIELDS`); ids, types and timestamps are never touched.
+/// Returns the new content and the number of replacements.
+fn replace_content(
+    content: &str,
+    matcher: &TextMatcher,
+    replacement: &str,
+) -> Result<(String, usize), String> {
+    let (mut fm, body, has_fm) = frontmatter::parse_frontmatter(content);
+    let (new_body, body_count) = matcher.replace_all(&body, replacement);
+
+    if !has_fm {
+        return Ok((new_body, body_count));
+    }
+
+    let fm_count = replace_in_mapping(&mut fm, matcher, replacement);
+    let count = body_count + fm_count;
+    if count == 0 {
+        return Ok((content.to_string(), 0));
+    }
+
+    frontmatter::update_frontmatter(&mut fm);
+    let rebuilt = frontmatter::rewrite(content, &fm, &new_body)?;
+    Ok((rebuilt, count))
+}
+
+fn replace_in_mapping(fm: &mut Mapping, matcher: &TextMatcher, replacement: &str) -> usize {
+    let mut count = 0;
+    for (key, value) in fm.iter_mut() {
+        if key
+            .as_str()
+            .is_some_and(|key| REPLACEABLE_FIELDS.contains(&key))
+        {
+            count += replace_in_value(value, matcher, replacement);
+        }
+    }
+    count
+}
+
+fn replace_in_value(value: &mut Value, matcher: &TextMatcher, replacement: &str) -> usize {
+    match value {
+        Value::String(s) => {
+            let (new, count) = matcher.replace_all(s, replacement);
+            *s = new;
+            count
+        }
+        Value::Sequence(items) => items
+            .iter_mut()
+            .map(|item| replace_in_value(item, matcher, replacement))
+            .sum(),
+        _ => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::matcher::{MatchOptions, Needle};
+
+    #[test]
+    fn test_replace_content_keeps_ids() {
+        let matcher =
+            TextMatcher::new(&[Needle::Literal("alpha".into())], &MatchOptions::default()).unwrap();
+        let content = "---\nid: alpha-notes\nproject_id: alpha\ntitle: Alpha notes\ntags:\n- alpha\n---\n\nAlpha is great\n";
+
+        let (new, count) = replace_content(content, &matcher, "beta").unwrap();
+        let (fm, body, _) = frontmatter::parse_frontmatter(&new);
+
+        assert_eq!(count, 3);
+        assert_eq!(frontmatter::get_str(&fm, "id").unwrap(), "alpha-notes");
+        assert_eq!(frontmatter::get_str(&fm, "project_id").unwrap(), "alpha");
+        assert_eq!(frontmatter::get_str(&fm, "title").unwrap(), "beta notes");
+        assert_eq!(frontmatter::get_string_seq(&fm, "tags"), vec!["beta"]);
+        assert_eq!(body.trim(), "beta is great");
+    }
+
+    #[test]
+    fn test_replace_content_keeps_backend_fields() {
+        let matcher =
+            TextMatcher::new(&[Needle::Literal("2026".into())], &MatchOptions::default()).unwrap();
+        let content = "---\nid: n\ntype: note\ncreated: 2026-01-01T00:00:00Z\ndate: 2026-01-01\ntitle: Plan 2026\n---\n\n2026 goals\n";
+        let (new, count) = replace_content(content, &matcher, "2027").unwrap();
+        let (fm, body, _) = frontmatter::parse_frontmatter(&new);
+        assert_eq!(count, 2);
+        assert_eq!(frontmatter::get_str(&fm, "title").unwrap(), "Plan 2027");
+        assert_eq!(
+            frontmatter::get_str(&fm, "created").unwrap(),
+            "2026-01-01T00:00:00Z"
+        );
+        assert_eq!(frontmatter::get_str(&fm, "date").unwrap(), "2026-01-01");
+        assert_eq!(body.trim(), "2027 goals");
+
+        let matcher =
+            TextMatcher::new(&[Needle::Literal("note".into())], &MatchOptions::default()).unwrap();
+        let (new, _) = replace_content(content, &matcher, "task").unwrap();
+        let (fm, _, _) = frontmatter::parse_frontmatter(&new);
+        assert_eq!(frontmatter::get_str(&fm, "type").unwrap(), "note");
+    }
+}
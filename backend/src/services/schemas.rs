@@ -0,0 +1,392 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, NaiveDate};
+use serde::{Deserialize, Serialize};
+use serde_yaml::{Mapping, Value};
+
+use crate::config;
+use crate::services::filesystem;
+use crate::services::frontmatter;
+use crate::services::index;
+
+/// Frontmatter schema for one document type, from `data/schemas/{type}.yml`:
+///
+/// ```yaml
+/// fields:
+///   priority:
+///     type: enum
+///     values: [low, medium, high]
+///     default: medium
+///   due_date:
+///     type: date
+///   title:
+///     type: string
+///     required: true
+/// ```
+///
+/// Fields not listed are left alone.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Schema {
+    #[serde(default)]
+    pub fields: BTreeMap<String, FieldSpec>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FieldSpec {
+    #[serde(rename = "type", default)]
+    pub kind: FieldType,
+    #[serde(default)]
+    pub required: bool,
+    /// Allowed values of an enum, or of a list's items
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub values: Vec<String>,
+    /// Value filled in when a new document doesn't set the field
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<Value>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FieldType {
+    #[default]
+    String,
+    Number,
+    Integer,
+    Boolean,
+    /// `YYYY-MM-DD`
+    Date,
+    /// RFC 3339, e.g. `2026-02-05T12:34:56Z`
+    Datetime,
+    List,
+    Enum,
+}
+
+/// A frontmatter field that doesn't match its schema
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Violation {
+    pub field: String,
+    pub message: String,
+}
+
+/// Every violation in one document
+#[derive(Debug, Serialize)]
+pub struct DocViolations {
+    pub path: String,
+    pub doc_type: String,
+    pub violations: Vec<Violation>,
+}
+
+/// Why a write that validates frontmatter failed
+#[derive(Debug)]
+pub enum WriteError {
+    /// The frontmatter breaks the vault's schema
+    Invalid(DocViolations),
+    /// Any other failure, with the usual message prefixes (`Invalid`, `Conflict`, ...)
+    Other(String),
+}
+
+impl From<DocViolations> for WriteError {
+    fn from(report: DocViolations) -> Self {
+        WriteError::Invalid(report)
+    }
+}
+
+impl From<String> for WriteError {
+    fn from(err: String) -> Self {
+        WriteError::Other(err)
+    }
+}
+
+impl From<&str> for WriteError {
+    fn from(err: &str) -> Self {
+        WriteError::Other(err.to_string())
+    }
+}
+
+impl fmt::Display for WriteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WriteError::Invalid(report) => {
+                let violations: Vec<String> = report
+                    .violations
+                    .iter()
+                    .map(|v| format!("{} {}", v.field, v.message))
+                    .collect();
+                write!(
+                    f,
+                    "Invalid frontmatter in {}: {}",
+                    report.path,
+                    violations.join("; ")
+                )
+            }
+            WriteError::Other(err) => f.write_str(err),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct SchemaInfo {
+    pub doc_type: String,
+    /// Data-relative path
+    pub path: String,
+    pub fields: BTreeMap<String, FieldSpec>,
+}
+
+/// Schemas live in `data/schemas/{type}.yml`
+pub fn schemas_dir() -> PathBuf {
+    config::data_dir().join("schemas")
+}
+
+/// The schema for `doc_type`, if the vault has one. Invalid schema files
+/// are logged and ignored, so a typo there can't block every write.
+pub fn load(doc_type: &str) -> Option<Schema> {
+    if doc_type.is_empty() || doc_type.contains(['/', '\\', '.']) {
+        return None;
+    }
+    let path = schemas_dir().join(format!("{}.yml", doc_type));
+    let content = fs::read_to_string(&path).ok()?;
+    serde_yaml::from_str(&content)
+        .map_err(|err| tracing::warn!("Ignoring invalid schema {:?}: {}", path, err))
+        .ok()
+}
+
+/// Schemas in the vault, by type.
+pub fn list() -> Result<Vec<SchemaInfo>, String> {
+    let dir = schemas_dir();
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut schemas = Vec::new();
+    for entry in fs::read_dir(&dir).map_err(|e| e.to_string())? {
+        let path = entry.map_err(|e| e.to_string())?.path();
+        let Some(doc_type) = path
+            .file_name()
+            .and_then(|s| s.to_str())
+            .and_then(|name| name.strip_suffix(".yml"))
+        else {
+            continue;
+        };
+        if let Some(schema) = load(doc_type) {
+            schemas.push(SchemaInfo {
+                doc_type: doc_type.to_string(),
+                path: format!("schemas/{}.yml", doc_type),
+                fields: schema.fields,
+            });
+        }
+    }
+
+    schemas.sort_by(|a, b| a.doc_type.cmp(&b.doc_type));
+    Ok(schemas)
+}
+
+/// Document type of the file at `path`: frontmatter `type`, else inferred
+/// from its location.
+fn doc_type_of(path: &Path, fm: &Mapping) -> String {
+    frontmatter::get_str(fm, "type")
+        .unwrap_or_else(|| index::detect_doc_type(&filesystem::normalize_path(path)).to_string())
+}
+
+/// Fill in schema defaults for a new document at `path`, then validate it.
+pub fn check_new(path: &Path, fm: &mut Mapping) -> Result<(), DocViolations> {
+    apply_defaults(path, fm);
+    check(path, fm)
+}
+
+/// Fill in the schema's defaults for fields a new document doesn't set.
+/// Call before applying built-in defaults, which the vault's schema overrides.
+pub fn apply_defaults(path: &Path, fm: &mut Mapping) {
+    let Some(schema) = load(&doc_type_of(path, fm)) else {
+        return;
+    };
+    for (field, spec) in &schema.fields {
+        if let Some(default) = &spec.default {
+            if is_missing(fm, field) {
+                fm.insert(Value::from(field.as_str()), default.clone());
+            }
+        }
+    }
+}
+
+/// Validate the frontmatter a write is about to store at `path`.
+pub fn check(path: &Path, fm: &Mapping) -> Result<(), DocViolations> {
+    let doc_type = doc_type_of(path, fm);
+    let Some(schema) = load(&doc_type) else {
+        return Ok(());
+    };
+    let violations = validate(&schema, fm);
+    if violations.is_empty() {
+        return Ok(());
+    }
+    Err(DocViolations {
+        path: filesystem::normalize_path(path),
+        doc_type,
+        violations,
+    })
+}
+
+fn is_missing(fm: &Mapping, field: &str) -> bool {
+    fm.get(Value::from(field)).is_none_or(Value::is_null)
+}
+
+/// Check frontmatter against a schema. A null value counts as missing.
+pub fn validate(schema: &Schema, fm: &Mapping) -> Vec<Violation> {
+    let mut violations = Vec::new();
+
+    for (field, spec) in &schema.fields {
+        let message = match fm.get(Value::from(field.as_str())) {
+            None | Some(Value::Null) => spec.required.then(|| "is required".to_string()),
+            Some(value) => check_value(spec, value),
+        };
+        if let Some(message) = message {
+            violations.push(Violation {
+                field: field.clone(),
+                message,
+            });
+        }
+    }
+
+    violations
+}
+
+fn check_value(spec: &FieldSpec, value: &Value) -> Option<String> {
+    let one_of = || format!("must be one of {}", spec.values.join(", "));
+
+    match spec.kind {
+        FieldType::String => (!value.is_string()).then(|| "must be text".to_string()),
+        FieldType::Number => (!value.is_number()).then(|| "must be a number".to_string()),
+        FieldType::Integer => {
+            (!(value.is_i64() || value.is_u64())).then(|| "must be a whole number".to_string())
+        }
+        FieldType::Boolean => (!value.is_bool()).then(|| "must be true or false".to_string()),
+        FieldType::Date => {
+            let valid = value
+                .as_str()
+                .is_some_and(|s| NaiveDate::parse_from_str(s, "%Y-%m-%d").is_ok());
+            (!valid).then(|| "must be a date (YYYY-MM-DD)".to_string())
+        }
+        FieldType::Datetime => {
+            let valid = value
+                .as_str()
+                .is_some_and(|s| DateTime::parse_from_rfc3339(s).is_ok());
+            (!valid).then(|| "must be a date and time (RFC 3339)".to_string())
+        }
+        FieldType::Enum => {
+            let valid = value
+                .as_str()
+                .is_some_and(|s| spec.values.is_empty() || spec.values.iter().any(|v| v == s));
+            (!valid).then(one_of)
+        }
+        FieldType::List => {
+            let Some(items) = value.as_sequence() else {
+                return Some("must be a list".to_string());
+            };
+            if spec.values.is_empty() {
+                return None;
+            }
+            items
+                .iter()
+                .find(|item| {
+                    !item
+                        .as_str()
+                        .is_some_and(|s| spec.values.iter().any(|v| v == s))
+                })
+                .map(|_| format!("items {}", one_of()))
+        }
+    }
+}
+
+/// Every indexed document that breaks its type's schema, by path.
+/// Frontmatter that doesn't parse at all is reported as a `frontmatter`
+/// violation, schema or not.
+pub fn report() -> Vec<DocViolations> {
+    let mut schemas: BTreeMap<String, Option<Schema>> = BTreeMap::new();
+    let mut report: Vec<DocViolations> = index::read(|idx| {
+        idx.docs()
+            .filter_map(|doc| {
+                let violations = match &doc.frontmatter_error {
+                    Some(error) => vec![Violation {
+                        field: "frontmatter".to_string(),
+                        message: error.clone(),
+                    }],
+                    None => {
+                        let schema = schemas
+                            .entry(doc.doc_type.clone())
+                            .or_insert_with(|| load(&doc.doc_type))
+                            .as_ref()?;
+                        validate(schema, &doc.frontmatter)
+                    }
+                };
+                (!violations.is_empty()).then(|| DocViolations {
+                    path: doc.path.clone(),
+                    doc_type: doc.doc_type.clone(),
+                    violations,
+                })
+            })
+            .collect()
+    });
+
+    report.sort_by(|a, b| a.path.cmp(&b.path));
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate() {
+        let schema: Schema = serde_yaml::from_str(
+            "fields:\n  title: {type: string, required: true}\n  priority: {type: enum, values: [low, medium, high]}\n  due_date: {type: date}\n  estimate: {type: integer}\n  tags: {type: list}\n  done: {type: boolean}",
+        )
+        .unwrap();
+        let fm: Mapping = serde_yaml::from_str(
+            "priority: hgih\ndue_date: next week\nestimate: 3\ntags: [a]\ndone: ~",
+        )
+        .unwrap();
+
+        let fields: Vec<(String, String)> = validate(&schema, &fm)
+            .into_iter()
+            .map(|v| (v.field, v.message))
+            .collect();
+        assert_eq!(
+            fields,
+            vec![
+                ("due_date".into(), "must be a date (YYYY-MM-DD)".into()),
+                ("priority".into(), "must be one of low, medium, high".into()),
+                ("title".into(), "is required".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_write_error_message() {
+        let err = WriteError::from(DocViolations {
+            path: "projects/ops/tasks/task-1.md".to_string(),
+            doc_type: "task".to_string(),
+            violations: vec![
+                Violation {
+                    field: "priority".to_string(),
+                    message: "must be one of low, high".to_string(),
+                },
+                Violation {
+                    field: "title".to_string(),
+                    message: "is required".to_string(),
+                },
+            ],
+        });
+
+        assert_eq!(
+            err.to_string(),
+            "Invalid frontmatter in projects/ops/tasks/task-1.md: \
+             priority must be one of low, high; title is required"
+        );
+        assert!(matches!(
+            WriteError::from("Invalid tag: x"),
+            WriteError::Other(_)
+        ));
+    }
+}
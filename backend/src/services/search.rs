@@ -1,193 +1,183 @@
-use std::fs;
-use std::path::Path;
-use std::process::{Command, Stdio};
+use std::cmp::Ordering;
 
-use serde::Serialize;
-use walkdir::WalkDir;
+use serde::{Deserialize, Serialize};
 
-use crate::config;
+use crate::services::index;
+use crate::services::matcher::{LineMatch, MatchOptions, Needle, TextMatcher};
+use crate::services::query::{self, Query};
 
 /// Search result item
 #[derive(Debug, Serialize)]
 pub struct SearchResult {
+    pub id: String,
     pub path: String,
     pub title: String,
-    pub matches: Vec<SearchMatch>,
+    pub note_type: String,
+    pub project_id: Option<String>,
+    pub updated: Option<String>,
+    pub score: f64,
+    pub matches: Vec<LineMatch>,
 }
 
-/// Individual match within a file
-#[derive(Debug, Serialize)]
-pub struct SearchMatch {
-    pub line_number: u32,
-    pub line_content: String,
+/// How the `q` string is interpreted
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchMode {
+    /// Query syntax (terms, phrases, filters, boolean operators)
+    #[default]
+    Query,
+    /// The whole string is matched literally
+    Literal,
+    /// The whole string is a regular expression
+    Regex,
 }
 
-/// Search notes using simple string matching
-/// Falls back to manual search if ripgrep is not available
-pub fn search_notes(query: &str) -> Result<Vec<SearchResult>, String> {
-    if query.trim().is_empty() {
-        return Ok(Vec::new());
-    }
+/// Options controlling how a search is run
+#[derive(Debug, Clone, Copy)]
+pub struct SearchOptions {
+    pub mode: SearchMode,
+    pub matching: MatchOptions,
+    /// Maximum number of matching lines reported per file
+    pub max_matches_per_file: u64,
+}
 
-    // Try ripgrep first (faster)
-    match search_with_ripgrep(query) {
-        Ok(results) => return Ok(results),
-        Err(e) => {
-            tracing::debug!(
-                "ripgrep not available, falling back to manual search: {}",
-                e
-            );
+impl Default for SearchOptions {
+    fn default() -> Self {
+        Self {
+            mode: SearchMode::Query,
+            matching: MatchOptions::default(),
+            max_matches_per_file: DEFAULT_MATCHES_PER_FILE,
         }
     }
-
-    // Fallback to manual search
-    search_manual(query)
 }
 
-/// Search using ripgrep (rg)
-fn search_with_ripgrep(query: &str) -> Result<Vec<SearchResult>, String> {
-    let data_dir_str = config::data_dir().to_string_lossy();
-    let output = Command::new("rg")
-        .args([
-            "--json",        // JSON output for parsing
-            "--ignore-case", // Case insensitive
-            "--type",
-            "md", // Only markdown files
-            "--max-count",
-            "5", // Max 5 matches per file
-            query,
-            &data_dir_str,
-        ])
-        .stdout(Stdio::piped())
-        .stderr(Stdio::null())
-        .output()
-        .map_err(|e| format!("Failed to run ripgrep: {}", e))?;
-
-    if !output.status.success() && output.stdout.is_empty() {
-        // No matches found or error
-        return Ok(Vec::new());
-    }
-
-    parse_ripgrep_output(&output.stdout)
+/// One page of ranked results
+#[derive(Debug)]
+pub struct SearchPage {
+    pub results: Vec<SearchResult>,
+    /// Number of matching documents before pagination
+    pub total: usize,
 }
 
-/// Parse ripgrep JSON output
-fn parse_ripgrep_output(output: &[u8]) -> Result<Vec<SearchResult>, String> {
-    use std::collections::HashMap;
-
-    let output_str = String::from_utf8_lossy(output);
-    let mut results_map: HashMap<String, SearchResult> = HashMap::new();
-
-    for line in output_str.lines() {
-        if let Ok(json) = serde_json::from_str::<serde_json::Value>(line) {
-            if json["type"] == "match" {
-                let data = &json["data"];
-                let path_str = data["path"]["text"].as_str().unwrap_or("");
-                let line_number = data["line_number"].as_u64().unwrap_or(0) as u32;
-                let line_content = data["lines"]["text"]
-                    .as_str()
-                    .unwrap_or("")
-                    .trim()
-                    .to_string();
-
-                let normalized_path = normalize_path(path_str);
-                let title = extract_title_from_path(&normalized_path);
-
-                let result = results_map
-                    .entry(normalized_path.clone())
-                    .or_insert_with(|| SearchResult {
-                        path: normalized_path,
-                        title,
-                        matches: Vec::new(),
-                    });
-
-                result.matches.push(SearchMatch {
-                    line_number,
-                    line_content,
-                });
-            }
-        }
+/// Default number of matching lines reported per file
+pub const DEFAULT_MATCHES_PER_FILE: u64 = 5;
+
+/// Score added per query term found in the title
+const TITLE_BOOST: f64 = 3.0;
+
+/// Interpret a search string according to the mode. In `query` mode, `key:value`
+/// only filters on keys some indexed document has in its frontmatter.
+pub fn parse_query(query: &str, mode: SearchMode) -> Result<Query, String> {
+    match mode {
+        SearchMode::Query => query::parse(query, |key| {
+            index::read(|idx| idx.docs().any(|doc| doc.frontmatter.contains_key(key)))
+        }),
+        SearchMode::Literal => Ok(Query::Phrase(query.to_string())),
+        SearchMode::Regex => Ok(Query::Regex(query.to_string())),
     }
-
-    Ok(results_map.into_values().collect())
 }
 
-/// Manual search fallback (no external dependencies)
-fn search_manual(query: &str) -> Result<Vec<SearchResult>, String> {
-    let query_lower = query.to_lowercase();
-    let root = config::data_dir();
-    let mut results = Vec::new();
-
-    for entry in WalkDir::new(root)
-        .into_iter()
-        .filter_entry(|e| !is_ignored(e.path()))
-        .filter_map(Result::ok)
-    {
-        let path = entry.path();
-
-        if path.extension().and_then(|s| s.to_str()) != Some("md") {
-            continue;
-        }
-
-        let content = match fs::read_to_string(path) {
-            Ok(c) => c,
-            Err(_) => continue,
-        };
-
-        let mut matches = Vec::new();
-        for (i, line) in content.lines().enumerate() {
-            if line.to_lowercase().contains(&query_lower) {
-                matches.push(SearchMatch {
-                    line_number: (i + 1) as u32,
-                    line_content: line.trim().to_string(),
-                });
-
-                // Limit matches per file
-                if matches.len() >= 5 {
-                    break;
-                }
-            }
-        }
-
-        if !matches.is_empty() {
-            let normalized_path = normalize_path(&path.to_string_lossy());
-            let title = extract_title_from_path(&normalized_path);
-
-            results.push(SearchResult {
-                path: normalized_path,
-                title,
-                matches,
-            });
-        }
+/// Search notes using the in-process index.
+/// In `query` mode the string is parsed with `services::query` (free text, phrases,
+/// frontmatter filters and boolean operators); `literal` and `regex` mode match
+/// the whole string. Text matching honours the case, whole-word and diacritic options.
+/// Results are ranked by BM25 relevance with title matches boosted;
+/// ties are broken by most recently updated.
+pub fn search_notes(
+    query: &str,
+    options: &SearchOptions,
+    limit: usize,
+    offset: usize,
+) -> Result<SearchPage, String> {
+    if query.trim().is_empty() {
+        return Ok(SearchPage {
+            results: Vec::new(),
+            total: 0,
+        });
     }
 
-    Ok(results)
-}
-
-fn is_ignored(path: &Path) -> bool {
-    path.components().any(|c| {
-        matches!(
-            c.as_os_str().to_str(),
-            Some(".git") | Some("assets") | Some("archive")
-        )
+    let parsed = parse_query(query, options.mode)?;
+
+    let needles = query::positive_needles(&parsed);
+    let line_matcher = if needles.is_empty() {
+        None
+    } else {
+        Some(TextMatcher::new(&needles, &options.matching)?)
+    };
+    let title_matchers = needles
+        .iter()
+        .map(|needle| TextMatcher::new(std::slice::from_ref(needle), &options.matching))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // Regex needles can't be mapped onto index terms, so only literals feed BM25
+    let tokens: Vec<String> = needles
+        .iter()
+        .filter_map(|needle| match needle {
+            Needle::Literal(text) => Some(text),
+            Needle::Regex(_) => None,
+        })
+        .flat_map(|text| index::tokenize(text))
+        .collect();
+
+    index::read(|idx| {
+        let relevance = idx.bm25(&tokens);
+
+        let mut ranked: Vec<(&index::IndexedDoc, f64)> =
+            query::evaluate(&parsed, idx, &options.matching)?
+                .into_iter()
+                .filter_map(|path| idx.get(path))
+                .map(|doc| {
+                    let title_hits = title_matchers
+                        .iter()
+                        .filter(|m| m.is_match(&doc.title))
+                        .count();
+                    let score = relevance.get(doc.path.as_str()).copied().unwrap_or(0.0)
+                        + title_hits as f64 * TITLE_BOOST;
+                    (doc, score)
+                })
+                .collect();
+
+        ranked.sort_by(|(a, a_score), (b, b_score)| {
+            b_score
+                .partial_cmp(a_score)
+                .unwrap_or(Ordering::Equal)
+                .then_with(|| b.updated.cmp(&a.updated))
+                .then_with(|| a.path.cmp(&b.path))
+        });
+
+        let total = ranked.len();
+        let results = ranked
+            .into_iter()
+            .skip(offset)
+            .take(limit)
+            .map(|(doc, score)| SearchResult {
+                id: doc.id.clone(),
+                path: doc.path.clone(),
+                title: doc.title.clone(),
+                note_type: doc.doc_type.clone(),
+                project_id: doc.project_id(),
+                updated: doc.updated.clone(),
+                score,
+                // Line numbers are 1-based and include the frontmatter
+                matches: line_matcher
+                    .as_ref()
+                    .map(|m| m.search_lines(&doc.content, Some(options.max_matches_per_file)))
+                    .unwrap_or_default(),
+            })
+            .collect();
+
+        Ok(SearchPage { results, total })
     })
 }
 
-fn normalize_path(path: &str) -> String {
-    if let Some(idx) = path.find("data") {
-        let stripped = &path[idx + 5..];
-        return stripped
-            .replace('\\', "/")
-            .trim_start_matches('/')
-            .to_string();
-    }
-    path.replace('\\', "/")
-}
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-fn extract_title_from_path(path: &str) -> String {
-    Path::new(path)
-        .file_stem()
-        .and_then(|s| s.to_str())
-        .unwrap_or("Untitled")
-        .to_string()
+    #[test]
+    fn test_search_mode_deserialize() {
+        let mode: SearchMode = serde_json::from_str("\"regex\"").unwrap();
+        assert_eq!(mode, SearchMode::Regex);
+        assert_eq!(SearchOptions::default().mode, SearchMode::Query);
+    }
 }
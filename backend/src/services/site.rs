@@ -0,0 +1,592 @@
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::config;
+use crate::services::frontmatter;
+use crate::services::index::{self, IndexedDoc};
+use crate::services::links;
+use crate::services::markdown::{self, escape_html, Target, Targets, Urls, RENDER_TYPES};
+
+/// Sections of the vault a site can include
+pub const SITE_SECTIONS: [&str; 3] = ["note", "project", "daily"];
+
+/// Marks a directory as an exported site, so a later export may replace it
+const SITE_MARKER: &str = ".ironpad-site";
+
+const STYLE_CSS: &str = include_str!("site/style.css");
+const SEARCH_JS: &str = include_str!("site/search.js");
+
+/// Characters of a page's text shown with its search result
+const EXCERPT_LEN: usize = 160;
+
+#[derive(Debug, Default, Deserialize)]
+pub struct SiteOptions {
+    /// Only this project: its index, notes and tasks
+    pub project: Option<String>,
+    /// Sections to include: note, project, daily (default: all)
+    pub include: Option<Vec<String>>,
+    /// Site title (defaults to the project's title, else "Ironpad")
+    pub title: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SiteExport {
+    /// Directory the site was written to
+    pub out: String,
+    pub pages: usize,
+    pub assets: usize,
+    /// Documents left out because of `publish: false`
+    pub unpublished: Vec<String>,
+}
+
+/// A document included in the site
+struct Page {
+    doc: IndexedDoc,
+    /// note, project or daily (see `SITE_SECTIONS`)
+    section: &'static str,
+    /// For project documents
+    project: Option<String>,
+}
+
+/// A line of the navigation: a section heading or a link to a page
+struct NavItem {
+    level: usize,
+    /// Data-relative path of the page, None for headings
+    path: Option<String>,
+    label: String,
+}
+
+/// Prebuilt search index loaded by `search.js`
+#[derive(Debug, Default, Serialize)]
+struct SiteSearch {
+    docs: Vec<SearchDoc>,
+    /// Term → positions in `docs`
+    terms: BTreeMap<String, Vec<usize>>,
+}
+
+#[derive(Debug, Serialize)]
+struct SearchDoc {
+    url: String,
+    title: String,
+    excerpt: String,
+}
+
+/// Default output folder: `exports/` next to the data folder.
+pub fn default_out_dir(options: &SiteOptions) -> PathBuf {
+    let name = match &options.project {
+        Some(project) => format!("{}-site", project),
+        None => "vault-site".to_string(),
+    };
+    config::data_dir().join("..").join("exports").join(name)
+}
+
+/// Render the selected notes, projects and daily notes to a static site in
+/// `out`, with navigation, a client-side search index and the assets pages use.
+/// Documents with `publish: false` (or in a project whose index has it) are
+/// left out, and links to them become plain text. `out` must be empty, or a
+/// site exported before, which is replaced.
+pub fn export(options: &SiteOptions, out: &Path) -> Result<SiteExport, String> {
+    let sections = sections(options)?;
+    let out = absolute(out)?;
+    let (pages, unpublished) = select(options, &sections)?;
+
+    let title = options
+        .title
+        .clone()
+        .or_else(|| {
+            pages
+                .iter()
+                .find(|page| page.doc.doc_type == "project" && options.project.is_some())
+                .map(|page| page.doc.title.clone())
+        })
+        .unwrap_or_else(|| "Ironpad".to_string());
+
+    prepare_out_dir(&out)?;
+
+    let targets = Targets::new(pages.iter().map(|page| &page.doc));
+    let assets = RefCell::new(BTreeSet::new());
+    let doc_url = |source: &str, target: &Target| {
+        links::relative_path(&page_path(source), &page_path(&target.path))
+    };
+    let asset_url = |source: &str, asset: &str| {
+        assets.borrow_mut().insert(asset.to_string());
+        links::relative_path(&page_path(source), asset)
+    };
+    let urls = Urls {
+        doc: &doc_url,
+        asset: &asset_url,
+    };
+
+    let nav = nav_items(&pages);
+    let mut search = SiteSearch::default();
+
+    for page in &pages {
+        let rendered = markdown::render(&page.doc.path, &page.doc.content, &targets, &urls)?;
+        let path = page_path(&page.doc.path);
+        let root = "../".repeat(path.matches('/').count());
+
+        let content = format!("{}{}", task_meta(&page.doc), rendered.html);
+        let html = layout(
+            &title,
+            &page.doc.title,
+            &root,
+            &nav_html(&nav, &root, Some(&page.doc.path)),
+            &content,
+        );
+        write(&out.join(&path), html.as_bytes())?;
+        search.add(
+            &path,
+            &page.doc.title,
+            &markdown::html_to_text(&rendered.html),
+        );
+    }
+
+    let home = format!(
+        "<h1>{}</h1>\n{}",
+        escape_html(&title),
+        nav_html(&nav, "", None)
+    );
+    write(
+        &out.join("index.html"),
+        layout(&title, &title, "", &nav_html(&nav, "", None), &home).as_bytes(),
+    )?;
+
+    let search_js = format!(
+        "window.IRONPAD_SEARCH = {};\n",
+        serde_json::to_string(&search).map_err(|e| e.to_string())?
+    );
+    write(&out.join("search-index.js"), search_js.as_bytes())?;
+    write(&out.join("search.js"), SEARCH_JS.as_bytes())?;
+    write(&out.join("style.css"), STYLE_CSS.as_bytes())?;
+
+    let mut copied = 0;
+    for asset in assets.into_inner() {
+        let source = config::data_dir().join(&asset);
+        if !source.is_file() {
+            tracing::warn!("Site export: missing asset {}", asset);
+            continue;
+        }
+        let target = out.join(&asset);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        fs::copy(&source, &target).map_err(|e| format!("Failed to copy {}: {}", asset, e))?;
+        copied += 1;
+    }
+
+    write(&out.join(SITE_MARKER), b"")?;
+
+    tracing::info!("Exported {} pages to {}", pages.len(), out.display());
+    Ok(SiteExport {
+        out: out.to_string_lossy().to_string(),
+        pages: pages.len(),
+        assets: copied,
+        unpublished,
+    })
+}
+
+fn sections(options: &SiteOptions) -> Result<Vec<&'static str>, String> {
+    let Some(include) = &options.include else {
+        return Ok(SITE_SECTIONS.to_vec());
+    };
+
+    include
+        .iter()
+        .map(|section| {
+            let section = section.trim().to_lowercase();
+            SITE_SECTIONS
+                .iter()
+                .find(|s| **s == section)
+                .copied()
+                .ok_or_else(|| format!("Invalid section: {}", section))
+        })
+        .collect()
+}
+
+/// Site section of a data-relative path, and its project for project documents.
+fn section_of(path: &str) -> Option<(&'static str, Option<String>)> {
+    let parts: Vec<&str> = path.split('/').collect();
+    match parts.as_slice() {
+        ["notes", ..] => Some(("note", None)),
+        ["daily", ..] => Some(("daily", None)),
+        ["projects", project, _, ..] => Some(("project", Some(project.to_string()))),
+        _ => None,
+    }
+}
+
+fn is_published(doc: &IndexedDoc) -> bool {
+    frontmatter::get_bool(&doc.frontmatter, "publish") != Some(false)
+}
+
+/// Pages to export in path order, and the paths left out as unpublished.
+fn select(
+    options: &SiteOptions,
+    sections: &[&'static str],
+) -> Result<(Vec<Page>, Vec<String>), String> {
+    let (mut pages, mut unpublished) = index::read(|idx| {
+        if let Some(project) = &options.project {
+            if idx.get(&format!("projects/{}/index.md", project)).is_none() {
+                return Err(format!("Project not found: {}", project));
+            }
+        }
+
+        let hidden_projects: HashSet<String> = idx
+            .docs()
+            .filter(|doc| doc.doc_type == "project" && !is_published(doc))
+            .filter_map(|doc| doc.project_id())
+            .collect();
+
+        let mut pages = Vec::new();
+        let mut unpublished = Vec::new();
+        for doc in idx.docs() {
+            if !RENDER_TYPES.contains(&doc.doc_type.as_str()) {
+                continue;
+            }
+            let Some((section, project)) = section_of(&doc.path) else {
+                continue;
+            };
+            if !sections.contains(&section) {
+                continue;
+            }
+            if options.project.is_some() && project != options.project {
+                continue;
+            }
+            if !is_published(doc)
+                || project
+                    .as_ref()
+                    .is_some_and(|p| hidden_projects.contains(p))
+            {
+                unpublished.push(doc.path.clone());
+                continue;
+            }
+            pages.push(Page {
+                doc: doc.clone(),
+                section,
+                project,
+            });
+        }
+        Ok((pages, unpublished))
+    })?;
+
+    pages.sort_by(|a, b| a.doc.path.cmp(&b.doc.path));
+    unpublished.sort();
+    Ok((pages, unpublished))
+}
+
+/// Navigation for every page: projects (with their notes and tasks), notes,
+/// then daily notes, newest first.
+fn nav_items(pages: &[Page]) -> Vec<NavItem> {
+    let link = |level, page: &Page| NavItem {
+        level,
+        path: Some(page.doc.path.clone()),
+        label: page.doc.title.clone(),
+    };
+    let heading = |level, label: &str| NavItem {
+        level,
+        path: None,
+        label: label.to_string(),
+    };
+    let mut items = Vec::new();
+
+    let mut projects: BTreeMap<&str, Vec<&Page>> = BTreeMap::new();
+    for page in pages.iter().filter(|p| p.section == "project") {
+        if let Some(project) = &page.project {
+            projects.entry(project).or_default().push(page);
+        }
+    }
+    if !projects.is_empty() {
+        items.push(heading(0, "Projects"));
+    }
+    for (project, project_pages) in &projects {
+        match project_pages.iter().find(|p| p.doc.doc_type == "project") {
+            Some(index) => items.push(link(1, index)),
+            None => items.push(heading(1, project)),
+        }
+        let mut notes: Vec<&&Page> = project_pages
+            .iter()
+            .filter(|p| p.doc.doc_type == "note")
+            .collect();
+        notes.sort_by(|a, b| a.doc.title.cmp(&b.doc.title));
+        items.extend(notes.into_iter().map(|p| link(2, p)));
+
+        let tasks: Vec<&&Page> = project_pages
+            .iter()
+            .filter(|p| p.doc.doc_type == "task")
+            .collect();
+        if !tasks.is_empty() {
+            items.push(heading(2, "Tasks"));
+            items.extend(tasks.into_iter().map(|p| link(3, p)));
+        }
+    }
+
+    let notes: Vec<&Page> = pages.iter().filter(|p| p.section == "note").collect();
+    if !notes.is_empty() {
+        items.push(heading(0, "Notes"));
+        items.extend(notes.into_iter().map(|p| link(1, p)));
+    }
+
+    let daily: Vec<&Page> = pages
+        .iter()
+        .filter(|p| p.section == "daily")
+        .rev()
+        .collect();
+    if !daily.is_empty() {
+        items.push(heading(0, "Daily notes"));
+        items.extend(daily.into_iter().map(|p| link(1, p)));
+    }
+
+    items
+}
+
+fn nav_html(items: &[NavItem], root: &str, current: Option<&str>) -> String {
+    let mut html = String::from("<ul class=\"nav\">\n");
+    for item in items {
+        let label = escape_html(&item.label);
+        match &item.path {
+            Some(path) => {
+                let class = if current == Some(path.as_str()) {
+                    " class=\"current\""
+                } else {
+                    ""
+                };
+                html.push_str(&format!(
+                    "<li class=\"level-{}\"><a href=\"{}{}\"{}>{}</a></li>\n",
+                    item.level,
+                    root,
+                    escape_html(&page_path(path).replace(' ', "%20")),
+                    class,
+                    label
+                ));
+            }
+            None => html.push_str(&format!(
+                "<li class=\"level-{} heading\">{}</li>\n",
+                item.level, label
+            )),
+        }
+    }
+    html.push_str("</ul>");
+    html
+}
+
+/// Status line shown above a task.
+fn task_meta(doc: &IndexedDoc) -> String {
+    if doc.doc_type != "task" {
+        return String::new();
+    }
+    let fm = &doc.frontmatter;
+    let mut parts = vec![if frontmatter::get_bool_or(fm, "completed", false) {
+        "Completed".to_string()
+    } else {
+        "Open".to_string()
+    }];
+    parts.push(frontmatter::get_str_or(fm, "section", "Active"));
+    if let Some(due) = frontmatter::get_str(fm, "due_date") {
+        parts.push(format!("Due {}", due));
+    }
+    format!(
+        "<p class=\"meta\">{}</p>\n",
+        escape_html(&parts.join(" · "))
+    )
+}
+
+fn layout(site_title: &str, page_title: &str, root: &str, nav: &str, content: &str) -> String {
+    let title = if page_title == site_title {
+        escape_html(site_title)
+    } else {
+        format!("{} · {}", escape_html(page_title), escape_html(site_title))
+    };
+
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>{title}</title>
+<link rel="stylesheet" href="{root}style.css">
+</head>
+<body data-root="{root}">
+<nav class="sidebar">
+<a class="site-title" href="{root}index.html">{site}</a>
+<input id="search" type="search" placeholder="Search" autocomplete="off">
+<ul id="search-results"></ul>
+{nav}
+</nav>
+<main>
+{content}
+</main>
+<script src="{root}search-index.js"></script>
+<script src="{root}search.js"></script>
+</body>
+</html>
+"#,
+        site = escape_html(site_title),
+    )
+}
+
+impl SiteSearch {
+    fn add(&mut self, url: &str, title: &str, text: &str) {
+        let position = self.docs.len();
+
+        let mut terms: Vec<String> = index::tokenize(title)
+            .chain(index::tokenize(text))
+            .collect();
+        terms.sort();
+        terms.dedup();
+        for term in terms {
+            self.terms.entry(term).or_default().push(position);
+        }
+
+        let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        self.docs.push(SearchDoc {
+            url: url.to_string(),
+            title: title.to_string(),
+            excerpt: text.chars().take(EXCERPT_LEN).collect(),
+        });
+    }
+}
+
+/// Site path of a document: its data-relative path with `.html` for `.md`.
+fn page_path(path: &str) -> String {
+    format!("{}.html", path.strip_suffix(".md").unwrap_or(path))
+}
+
+/// `path` made absolute, with `.` and `..` resolved.
+fn absolute(path: &Path) -> Result<PathBuf, String> {
+    let path = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        std::env::current_dir()
+            .map_err(|e| e.to_string())?
+            .join(path)
+    };
+
+    let mut resolved = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                resolved.pop();
+            }
+            other => resolved.push(other),
+        }
+    }
+    Ok(resolved)
+}
+
+/// Refuse to write into the data folder or over a folder that isn't an
+/// earlier export; an earlier export is cleared so no stale page survives.
+fn prepare_out_dir(out: &Path) -> Result<(), String> {
+    let data = absolute(&fs::canonicalize(config::data_dir()).map_err(|e| e.to_string())?)?;
+    if out.starts_with(&data) || data.starts_with(out) {
+        return Err(format!(
+            "Invalid output directory: {} overlaps the data folder",
+            out.display()
+        ));
+    }
+
+    if out.exists() {
+        let has_entries = fs::read_dir(out)
+            .map_err(|e| e.to_string())?
+            .next()
+            .is_some();
+        if has_entries {
+            if !out.join(SITE_MARKER).exists() {
+                return Err(format!(
+                    "Invalid output directory: {} is not empty",
+                    out.display()
+                ));
+            }
+            fs::remove_dir_all(out).map_err(|e| e.to_string())?;
+        }
+    }
+
+    fs::create_dir_all(out).map_err(|e| e.to_string())
+}
+
+fn write(path: &Path, contents: &[u8]) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    fs::write(path, contents).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::index::SearchIndex;
+
+    fn page(idx: &SearchIndex, path: &str) -> Page {
+        let (section, project) = section_of(path).unwrap();
+        Page {
+            doc: idx.get(path).unwrap().clone(),
+            section,
+            project,
+        }
+    }
+
+    #[test]
+    fn test_nav_items() {
+        let mut idx = SearchIndex::default();
+        idx.insert(
+            Path::new("data/projects/ops/index.md"),
+            "---\nid: ops\ntitle: Ops\n---\n",
+        );
+        idx.insert(
+            Path::new("data/projects/ops/tasks/task-1.md"),
+            "---\nid: task-1\ntitle: Upgrade\n---\n",
+        );
+        idx.insert(Path::new("data/notes/pg.md"), "---\ntitle: Postgres\n---\n");
+        idx.insert(Path::new("data/daily/2026-01-01.md"), "");
+        idx.insert(Path::new("data/daily/2026-01-02.md"), "");
+
+        let pages: Vec<Page> = [
+            "daily/2026-01-01.md",
+            "daily/2026-01-02.md",
+            "notes/pg.md",
+            "projects/ops/index.md",
+            "projects/ops/tasks/task-1.md",
+        ]
+        .iter()
+        .map(|path| page(&idx, path))
+        .collect();
+
+        let items = nav_items(&pages);
+        let labels: Vec<(usize, &str)> = items
+            .iter()
+            .map(|item| (item.level, item.label.as_str()))
+            .collect();
+        assert_eq!(
+            labels,
+            vec![
+                (0, "Projects"),
+                (1, "Ops"),
+                (2, "Tasks"),
+                (3, "Upgrade"),
+                (0, "Notes"),
+                (1, "Postgres"),
+                (0, "Daily notes"),
+                (1, "2026-01-02"),
+                (1, "2026-01-01"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_paths() {
+        assert_eq!(page_path("notes/sub/a.md"), "notes/sub/a.html");
+        assert_eq!(
+            section_of("projects/ops/tasks/t.md"),
+            Some(("project", Some("ops".to_string())))
+        );
+        assert_eq!(section_of("views/v.md"), None);
+        assert_eq!(
+            absolute(Path::new("/a/b/../c/./d")).unwrap(),
+            PathBuf::from("/a/c/d")
+        );
+    }
+}
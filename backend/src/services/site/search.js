@@ -0,0 +1,61 @@
+// Client-side search over the index prebuilt by the site export
+// (window.IRONPAD_SEARCH from search-index.js). Every query word must
+// match the start of a word in the page.
+(function () {
+  var data = window.IRONPAD_SEARCH;
+  var input = document.getElementById('search');
+  var results = document.getElementById('search-results');
+  if (!data || !input || !results) return;
+
+  var root = document.body.getAttribute('data-root') || '';
+  var terms = Object.keys(data.terms).sort();
+  var MAX_RESULTS = 20;
+
+  // Same folding as the server: lowercase, no diacritics, letters and digits
+  function tokenize(text) {
+    return text
+      .toLowerCase()
+      .normalize('NFD')
+      .replace(/[\u0300-\u036f]/g, '')
+      .split(/[^\p{L}\p{N}]+/u)
+      .filter(Boolean);
+  }
+
+  function docsWithPrefix(prefix) {
+    var docs = new Set();
+    for (var i = 0; i < terms.length; i++) {
+      if (terms[i].lastIndexOf(prefix, 0) === 0) {
+        data.terms[terms[i]].forEach(function (doc) { docs.add(doc); });
+      }
+    }
+    return docs;
+  }
+
+  function search(query) {
+    var matching = null;
+    tokenize(query).forEach(function (token) {
+      var docs = docsWithPrefix(token);
+      matching = matching === null
+        ? docs
+        : new Set(Array.from(matching).filter(function (doc) { return docs.has(doc); }));
+    });
+    return matching === null ? [] : Array.from(matching);
+  }
+
+  input.addEventListener('input', function () {
+    results.textContent = '';
+    search(input.value).slice(0, MAX_RESULTS).forEach(function (position) {
+      var doc = data.docs[position];
+      var item = document.createElement('li');
+      var link = document.createElement('a');
+      link.href = root + doc.url;
+      link.textContent = doc.title;
+      var excerpt = document.createElement('span');
+      excerpt.className = 'excerpt';
+      excerpt.textContent = doc.excerpt;
+      item.appendChild(link);
+      item.appendChild(excerpt);
+      results.appendChild(item);
+    });
+  });
+})();
@@ -0,0 +1,454 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::ops::Range;
+
+use serde::Serialize;
+use serde_yaml::{Mapping, Value};
+
+use crate::config;
+use crate::services::filesystem;
+use crate::services::frontmatter;
+use crate::services::git::{self, CommitInfo};
+use crate::services::index::{self, IndexedDoc};
+use crate::services::links;
+
+/// Usage of a tag (including its sub-tags) across the vault
+#[derive(Debug, Serialize)]
+pub struct TagSummary {
+    /// Lowercase tag without `#`, e.g. `area/sub`
+    pub name: String,
+    /// `area` for `area/sub`
+    pub parent: Option<String>,
+    /// Documents with this tag or one of its sub-tags
+    pub count: usize,
+    /// Counts by document type
+    pub by_type: BTreeMap<String, usize>,
+    /// Counts by project (documents outside projects aren't counted here)
+    pub by_project: BTreeMap<String, usize>,
+}
+
+/// A document carrying a tag
+#[derive(Debug, Serialize)]
+pub struct TaggedDoc {
+    pub id: String,
+    pub path: String,
+    pub title: String,
+    pub doc_type: String,
+    pub project_id: Option<String>,
+    /// The document's tags that matched (the tag itself or sub-tags)
+    pub tags: Vec<String>,
+}
+
+/// Outcome of a rename or merge
+#[derive(Debug, Serialize)]
+pub struct TagChange {
+    pub files_changed: usize,
+    pub files: Vec<String>,
+    /// Tags rewritten across the vault (frontmatter entries and inline tags)
+    pub replacements: usize,
+    /// The commit recording the change (None if git is unavailable)
+    pub commit: Option<CommitInfo>,
+}
+
+/// Lowercase, without the leading `#` and surrounding slashes.
+pub fn normalize(tag: &str) -> String {
+    tag.trim()
+        .trim_start_matches('#')
+        .trim_matches('/')
+        .to_lowercase()
+}
+
+/// Check a tag given in a request and return it normalised.
+pub fn validate(tag: &str) -> Result<String, String> {
+    let tag = normalize(tag);
+    let valid = !tag.is_empty()
+        && tag.split('/').all(|part| {
+            !part.is_empty()
+                && part.chars().all(is_tag_char)
+                && !part.chars().all(|c| c.is_ascii_digit())
+        });
+    if !valid {
+        return Err(format!("Invalid tag: {}", tag));
+    }
+    Ok(tag)
+}
+
+fn is_tag_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '-'
+}
+
+/// A document's tags: frontmatter `tags` plus inline `#tags` in the body,
+/// normalised and deduplicated.
+pub fn doc_tags(fm: &Mapping, body: &str) -> Vec<String> {
+    let mut tags: BTreeSet<String> = frontmatter_tags(fm)
+        .iter()
+        .map(|tag| normalize(tag))
+        .filter(|tag| !tag.is_empty())
+        .collect();
+
+    for line in lines_outside_code(body) {
+        for range in inline_spans(line) {
+            tags.insert(normalize(&line[range]));
+        }
+    }
+
+    tags.into_iter().collect()
+}
+
+/// Frontmatter `tags` as a list, or a single comma-separated string.
+fn frontmatter_tags(fm: &Mapping) -> Vec<String> {
+    match fm.get(Value::from("tags")) {
+        Some(Value::String(tags)) => tags.split(',').map(|t| t.trim().to_string()).collect(),
+        _ => frontmatter::get_string_seq(fm, "tags"),
+    }
+}
+
+fn lines_outside_code(body: &str) -> impl Iterator<Item = &str> {
+    let mut in_code_block = false;
+    body.lines().filter(move |line| {
+        if links::toggles_code_block(line) {
+            in_code_block = !in_code_block;
+            return false;
+        }
+        !in_code_block
+    })
+}
+
+/// Byte ranges of inline tags (without the `#`) in a line: `#tag` or
+/// `#area/sub` at the start of the line or after whitespace, outside inline
+/// code. Headings (`# Title`) and numbers like `#123` aren't tags.
+fn inline_spans(line: &str) -> Vec<Range<usize>> {
+    let mut spans = Vec::new();
+    let mut in_code = false;
+    let mut prev: Option<char> = None;
+    let mut chars = line.char_indices().peekable();
+
+    while let Some((i, c)) = chars.next() {
+        if c == '`' {
+            in_code = !in_code;
+        } else if c == '#' && !in_code && prev.is_none_or(char::is_whitespace) {
+            let start = i + 1;
+            let mut end = start;
+            while let Some(&(j, next)) = chars.peek() {
+                if !(is_tag_char(next) || next == '/') {
+                    break;
+                }
+                end = j + next.len_utf8();
+                chars.next();
+            }
+
+            // The character before whatever follows is the last one scanned
+            prev = line[..end].chars().next_back();
+
+            let tag = line[start..end].trim_end_matches('/');
+            let end = start + tag.len();
+            let valid = !tag.is_empty()
+                && !tag.starts_with('/')
+                && !tag.contains("//")
+                && !tag.chars().all(|c| c.is_ascii_digit() || c == '/');
+            if valid {
+                spans.push(start..end);
+            }
+            continue;
+        }
+        prev = Some(c);
+    }
+
+    spans
+}
+
+/// `tag` and its ancestors: `a/b/c` → `a`, `a/b`, `a/b/c`
+fn with_ancestors(tag: &str) -> impl Iterator<Item = &str> {
+    tag.match_indices('/')
+        .map(|(i, _)| &tag[..i])
+        .chain(std::iter::once(tag))
+}
+
+/// Whether `doc_tag` is `tag` or nested under it (both normalised).
+pub fn has_tag(doc_tag: &str, tag: &str) -> bool {
+    doc_tag == tag
+        || doc_tag
+            .strip_prefix(tag)
+            .is_some_and(|rest| rest.starts_with('/'))
+}
+
+/// Every tag in use with its counts, by name. `project` and `doc_type`
+/// restrict which documents are counted.
+pub fn summary(project: Option<&str>, doc_type: Option<&str>) -> Vec<TagSummary> {
+    index::read(|idx| {
+        let mut tags: BTreeMap<String, TagSummary> = BTreeMap::new();
+
+        for doc in idx.docs() {
+            let project_id = doc.project_id();
+            if project.is_some_and(|p| project_id.as_deref() != Some(p))
+                || doc_type.is_some_and(|t| doc.doc_type != t)
+            {
+                continue;
+            }
+
+            let names: BTreeSet<&str> = doc.tags.iter().flat_map(|t| with_ancestors(t)).collect();
+            for name in names {
+                let entry = tags.entry(name.to_string()).or_insert_with(|| TagSummary {
+                    name: name.to_string(),
+                    parent: name.rsplit_once('/').map(|(parent, _)| parent.to_string()),
+                    count: 0,
+                    by_type: BTreeMap::new(),
+                    by_project: BTreeMap::new(),
+                });
+                entry.count += 1;
+                *entry.by_type.entry(doc.doc_type.clone()).or_insert(0) += 1;
+                if let Some(project_id) = &project_id {
+                    *entry.by_project.entry(project_id.clone()).or_insert(0) += 1;
+                }
+            }
+        }
+
+        tags.into_values().collect()
+    })
+}
+
+/// Documents with `tag` (or, unless `exact`, one of its sub-tags),
+/// most recently updated first.
+pub fn tagged(tag: &str, exact: bool) -> Result<Vec<TaggedDoc>, String> {
+    let tag = validate(tag)?;
+    let mut docs: Vec<(Option<String>, TaggedDoc)> = index::read(|idx| {
+        idx.docs()
+            .filter_map(|doc| {
+                let tags: Vec<String> = doc
+                    .tags
+                    .iter()
+                    .filter(|t| if exact { **t == tag } else { has_tag(t, &tag) })
+                    .cloned()
+                    .collect();
+                (!tags.is_empty()).then(|| (doc.updated.clone(), tagged_doc(doc, tags)))
+            })
+            .collect()
+    });
+
+    if docs.is_empty() {
+        return Err(format!("Tag not found: {}", tag));
+    }
+
+    docs.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.path.cmp(&b.1.path)));
+    Ok(docs.into_iter().map(|(_, doc)| doc).collect())
+}
+
+fn tagged_doc(doc: &IndexedDoc, tags: Vec<String>) -> TaggedDoc {
+    TaggedDoc {
+        id: doc.id.clone(),
+        path: doc.path.clone(),
+        title: doc.title.clone(),
+        doc_type: doc.doc_type.clone(),
+        project_id: doc.project_id(),
+        tags,
+    }
+}
+
+/// Rename `from` (and its sub-tags) to `to` everywhere. Fails if `to` is
+/// already in use; merge instead.
+pub fn rename(from: &str, to: &str, message: Option<&str>) -> Result<TagChange, String> {
+    let (from, to) = (validate(from)?, validate(to)?);
+    if from == to || has_tag(&to, &from) {
+        return Err(format!("Invalid rename: {} to {}", from, to));
+    }
+    if in_use(&to) {
+        return Err(format!("Tag already exists: {} (merge instead)", to));
+    }
+
+    let default_message = format!("Rename tag #{} to #{}", from, to);
+    apply(&[from], &to, message.unwrap_or(&default_message))
+}
+
+/// Merge every tag in `sources` (and their sub-tags) into `into`.
+pub fn merge(sources: &[String], into: &str, message: Option<&str>) -> Result<TagChange, String> {
+    let into = validate(into)?;
+    let sources = sources
+        .iter()
+        .map(|tag| validate(tag))
+        .collect::<Result<Vec<_>, _>>()?;
+    let sources: Vec<String> = sources.into_iter().filter(|tag| *tag != into).collect();
+    if sources.is_empty() {
+        return Err("Invalid merge: no tags to merge".to_string());
+    }
+    if let Some(tag) = sources.iter().find(|tag| has_tag(&into, tag)) {
+        return Err(format!("Invalid merge: {} is inside {}", into, tag));
+    }
+
+    let default_message = format!(
+        "Merge {} into #{}",
+        sources
+            .iter()
+            .map(|tag| format!("#{}", tag))
+            .collect::<Vec<_>>()
+            .join(", "),
+        into
+    );
+    apply(&sources, &into, message.unwrap_or(&default_message))
+}
+
+fn in_use(tag: &str) -> bool {
+    index::read(|idx| {
+        idx.docs()
+            .any(|doc| doc.tags.iter().any(|t| has_tag(t, tag)))
+    })
+}
+
+/// Rewrite every file carrying one of `sources`, then commit once.
+/// All new contents are worked out before anything is written.
+fn apply(sources: &[String], target: &str, message: &str) -> Result<TagChange, String> {
+    let paths: Vec<String> = index::read(|idx| {
+        let mut paths: Vec<String> = idx
+            .docs()
+            .filter(|doc| {
+                doc.tags
+                    .iter()
+                    .any(|t| sources.iter().any(|s| has_tag(t, s)))
+            })
+            .map(|doc| doc.path.clone())
+            .collect();
+        paths.sort();
+        paths
+    });
+    if paths.is_empty() {
+        return Err(format!("Tag not found: {}", sources.join(", ")));
+    }
+
+    let root = config::data_dir();
+    let mut edits = Vec::new();
+    for path in paths {
+        // Read from disk rather than the index so we never write back stale content
+        let old = fs::read_to_string(root.join(&path)).map_err(|e| e.to_string())?;
+        let (new, count) =
+            retag_content(&old, sources, target).map_err(|err| format!("{} in {}", err, path))?;
+        if count > 0 {
+            edits.push((path, new, count));
+        }
+    }
+
+    for (path, new, _) in &edits {
+        filesystem::atomic_write(&root.join(path), new.as_bytes())?;
+    }
+
+    let paths: Vec<&str> = edits.iter().map(|(path, _, _)| path.as_str()).collect();
+    let commit = match git::commit_paths(&paths, message) {
+        Ok(info) => Some(info),
+        Err(err) => {
+            tracing::warn!("Tags rewritten but not committed: {}", err);
+            None
+        }
+    };
+
+    Ok(TagChange {
+        files_changed: edits.len(),
+        replacements: edits.iter().map(|(_, _, count)| count).sum(),
+        files: edits.into_iter().map(|(path, _, _)| path).collect(),
+        commit,
+    })
+}
+
+/// The new name of `tag` if it is one of `sources` or below one.
+fn retag(tag: &str, sources: &[String], target: &str) -> Option<String> {
+    let tag = normalize(tag);
+    sources
+        .iter()
+        .find(|source| has_tag(&tag, source))
+        .map(|source| format!("{}{}", target, &tag[source.len()..]))
+}
+
+/// Rewrite frontmatter tags (deduplicating) and inline tags outside code.
+/// Returns the new content and the number of tags rewritten.
+fn retag_content(
+    content: &str,
+    sources: &[String],
+    target: &str,
+) -> Result<(String, usize), String> {
+    let (mut fm, body, has_fm) = frontmatter::parse_frontmatter(content);
+    let mut count = 0;
+
+    let mut new_body = String::with_capacity(body.len());
+    let mut in_code_block = false;
+    for line in body.split_inclusive('\n') {
+        if links::toggles_code_block(line) {
+            in_code_block = !in_code_block;
+        }
+        if in_code_block || links::toggles_code_block(line) {
+            new_body.push_str(line);
+            continue;
+        }
+
+        let mut last = 0;
+        for range in inline_spans(line) {
+            if let Some(new) = retag(&line[range.clone()], sources, target) {
+                new_body.push_str(&line[last..range.start]);
+                new_body.push_str(&new);
+                last = range.end;
+                count += 1;
+            }
+        }
+        new_body.push_str(&line[last..]);
+    }
+
+    if !has_fm {
+        return Ok((new_body, count));
+    }
+
+    let tags = frontmatter_tags(&fm);
+    if tags.iter().any(|tag| retag(tag, sources, target).is_some()) {
+        let mut seen = BTreeSet::new();
+        let mut new_tags = Vec::new();
+        for tag in tags {
+            let new = match retag(&tag, sources, target) {
+                Some(new) => {
+                    count += 1;
+                    new
+                }
+                None => tag,
+            };
+            if seen.insert(normalize(&new)) {
+                new_tags.push(Value::from(new));
+            }
+        }
+        fm.insert(Value::from("tags"), Value::Sequence(new_tags));
+    }
+
+    if count == 0 {
+        return Ok((content.to_string(), 0));
+    }
+
+    frontmatter::update_frontmatter(&mut fm);
+    let rebuilt = frontmatter::rewrite(content, &fm, &new_body)?;
+    Ok((rebuilt, count))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_doc_tags() {
+        let fm: Mapping = serde_yaml::from_str("tags: [Infra, ops]").unwrap();
+        let body = "# Heading\n\nSee #area/sub and #todo, not #123 or a#b.\n`#code` ```\n```\n#fenced\n```\n";
+        assert_eq!(
+            doc_tags(&fm, body),
+            vec!["area/sub", "infra", "ops", "todo"]
+        );
+    }
+
+    #[test]
+    fn test_retag_content() {
+        let content = "---\nid: x\ntags:\n- Work\n- job\n- work/meetings\n---\n\nThe #work/meetings note, `#work` and #workshop.\n";
+        let sources = vec!["work".to_string()];
+        let (new, count) = retag_content(content, &sources, "job").unwrap();
+        let (fm, body, _) = frontmatter::parse_frontmatter(&new);
+
+        assert_eq!(count, 3);
+        assert_eq!(
+            frontmatter::get_string_seq(&fm, "tags"),
+            vec!["job", "job/meetings"]
+        );
+        assert_eq!(
+            body.trim(),
+            "The #job/meetings note, `#work` and #workshop."
+        );
+    }
+}
@@ -0,0 +1,328 @@
+use std::collections::BTreeMap;
+use std::fmt::{self, Write};
+use std::fs;
+use std::path::PathBuf;
+
+use chrono::{Days, NaiveDate, Utc};
+use serde::Serialize;
+use serde_yaml::{Mapping, Value};
+
+use crate::config;
+use crate::services::filesystem;
+use crate::services::frontmatter;
+use crate::services::index;
+
+/// Backend-owned fields a template can't preset
+const RESERVED_FIELDS: [&str; 4] = ["id", "type", "created", "updated"];
+
+/// File in the templates folder mapping document types to default template names
+const DEFAULTS_FILE: &str = "defaults.yml";
+
+/// Values for a template's placeholders
+#[derive(Debug, Clone)]
+pub struct TemplateContext {
+    pub title: String,
+    /// The document's date: the day of a daily note, today otherwise
+    pub date: NaiveDate,
+    /// Project name (or id) for project tasks and notes
+    pub project: Option<String>,
+}
+
+/// A template filled in for a new document
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rendered {
+    /// Frontmatter defaults (reserved fields removed)
+    pub frontmatter: Mapping,
+    pub body: String,
+    /// Character offset of `{{cursor}}` in `body`
+    pub cursor: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TemplateInfo {
+    pub name: String,
+    /// Data-relative path
+    pub path: String,
+    /// Document types using this template by default
+    pub default_for: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TemplateWithContent {
+    #[serde(flatten)]
+    pub info: TemplateInfo,
+    pub content: String,
+}
+
+/// User templates live in `data/templates/{name}.md`
+pub fn templates_dir() -> PathBuf {
+    config::data_dir().join("templates")
+}
+
+fn template_path(name: &str) -> Result<PathBuf, String> {
+    let name = name.trim().trim_end_matches(".md");
+    let valid = !name.is_empty() && !name.starts_with('.') && !name.contains(['/', '\\']);
+    if !valid {
+        return Err(format!("Invalid template name: {}", name));
+    }
+    Ok(templates_dir().join(format!("{}.md", name)))
+}
+
+/// A template's frontmatter defaults and body.
+pub fn load(name: &str) -> Result<(Mapping, String), String> {
+    let path = template_path(name)?;
+    if !path.is_file() {
+        return Err(format!("Invalid template: no template named {}", name));
+    }
+
+    let content = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    let (mut fm, body, _) = frontmatter::parse_frontmatter(&content);
+    for field in RESERVED_FIELDS {
+        fm.remove(Value::from(field));
+    }
+
+    Ok((fm, body.trim_start().to_string()))
+}
+
+/// Default template names by document type (note, task, project, daily),
+/// from `data/templates/defaults.yml`.
+pub fn defaults() -> BTreeMap<String, String> {
+    let path = templates_dir().join(DEFAULTS_FILE);
+    let Ok(content) = fs::read_to_string(&path) else {
+        return BTreeMap::new();
+    };
+    serde_yaml::from_str(&content).unwrap_or_else(|err| {
+        tracing::warn!("Ignoring invalid {:?}: {}", path, err);
+        BTreeMap::new()
+    })
+}
+
+/// The template for a new document of `doc_type`: the one named in the request,
+/// else the vault's default for the type. None means the built-in layout.
+pub fn for_new(
+    doc_type: &str,
+    requested: Option<&str>,
+    ctx: &TemplateContext,
+) -> Result<Option<Rendered>, String> {
+    let name = match requested.map(str::trim).filter(|n| !n.is_empty()) {
+        Some(name) => name.to_string(),
+        None => match defaults().remove(doc_type) {
+            Some(name) => name,
+            None => return Ok(None),
+        },
+    };
+
+    let (fm, body) = load(&name)?;
+    Ok(Some(render(&fm, &body, ctx)))
+}
+
+/// Fill in placeholders in the body and in string frontmatter values:
+/// `{{title}}`, `{{project}}`, `{{date}}`, `{{time}}`, `{{weekday}}`,
+/// `{{yesterday}}`, `{{tomorrow}}`, `{{previous_daily}}` and `{{cursor}}`.
+/// Dates take a chrono format after a colon, e.g. `{{date:%B %-d}}`.
+/// Unknown placeholders are left as they are.
+pub fn render(fm: &Mapping, body: &str, ctx: &TemplateContext) -> Rendered {
+    let mut frontmatter = Mapping::new();
+    for (key, value) in fm {
+        let value = match value.as_str() {
+            Some(text) => Value::from(fill(text, ctx).replace("{{cursor}}", "")),
+            None => value.clone(),
+        };
+        frontmatter.insert(key.clone(), value);
+    }
+
+    let filled = fill(body, ctx);
+    let filled = filled.trim_start();
+    let (body, cursor) = match filled.find("{{cursor}}") {
+        Some(at) => (
+            filled
+                .replacen("{{cursor}}", "", 1)
+                .replace("{{cursor}}", ""),
+            Some(filled[..at].chars().count()),
+        ),
+        None => (filled.to_string(), None),
+    };
+
+    Rendered {
+        frontmatter,
+        body,
+        cursor,
+    }
+}
+
+fn fill(text: &str, ctx: &TemplateContext) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find("{{") {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("}}") else {
+            out.push_str(&rest[start..]);
+            return out;
+        };
+
+        let placeholder = &after[..end];
+        match value_of(placeholder.trim(), ctx) {
+            Some(value) => out.push_str(&value),
+            None => out.push_str(&rest[start..start + end + 4]),
+        }
+        rest = &after[end + 2..];
+    }
+
+    out.push_str(rest);
+    out
+}
+
+fn value_of(placeholder: &str, ctx: &TemplateContext) -> Option<String> {
+    let (name, format) = match placeholder.split_once(':') {
+        Some((name, format)) => (name.trim(), Some(format)),
+        None => (placeholder, None),
+    };
+
+    let day = |date: NaiveDate| format_date(date, format.unwrap_or("%Y-%m-%d"));
+    match name {
+        "title" => Some(ctx.title.clone()),
+        "project" => Some(ctx.project.clone().unwrap_or_default()),
+        "date" => day(ctx.date),
+        "weekday" => format_date(ctx.date, format.unwrap_or("%A")),
+        "time" => try_format(Utc::now().format(format.unwrap_or("%H:%M"))),
+        "yesterday" => day(ctx.date.checked_sub_days(Days::new(1))?),
+        "tomorrow" => day(ctx.date.checked_add_days(Days::new(1))?),
+        "previous_daily" => day(previous_daily(ctx.date)),
+        _ => None,
+    }
+}
+
+fn format_date(date: NaiveDate, format: &str) -> Option<String> {
+    try_format(date.format(format))
+}
+
+/// chrono fails to display invalid specifiers and ones the value lacks (`%H`
+/// on a date), which would panic in `to_string`
+fn try_format(formatted: impl fmt::Display) -> Option<String> {
+    let mut out = String::new();
+    write!(&mut out, "{}", formatted).ok()?;
+    Some(out)
+}
+
+/// The most recent daily note before `date` (the day before if there is none).
+fn previous_daily(date: NaiveDate) -> NaiveDate {
+    let earlier = index::read(|idx| {
+        idx.docs()
+            .filter(|doc| doc.doc_type == "daily")
+            .filter_map(|doc| {
+                let stem = doc.path.strip_prefix("daily/")?.strip_suffix(".md")?;
+                NaiveDate::parse_from_str(stem, "%Y-%m-%d").ok()
+            })
+            .filter(|day| *day < date)
+            .max()
+    });
+    earlier.unwrap_or_else(|| date.pred_opt().unwrap_or(date))
+}
+
+/// Display name of a project for `{{project}}`: its title, or the id.
+pub fn project_name(project_id: &str) -> String {
+    index::read(|idx| {
+        idx.get(&format!("projects/{}/index.md", project_id))
+            .map(|doc| doc.title.clone())
+    })
+    .unwrap_or_else(|| project_id.to_string())
+}
+
+/// Templates in `data/templates/`, by name.
+pub fn list() -> Result<Vec<TemplateInfo>, String> {
+    let dir = templates_dir();
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let defaults = defaults();
+    let mut templates = Vec::new();
+    for entry in fs::read_dir(&dir).map_err(|e| e.to_string())? {
+        let path = entry.map_err(|e| e.to_string())?.path();
+        let Some(name) = path.file_name().and_then(|s| s.to_str()) else {
+            continue;
+        };
+        if let Some(name) = name.strip_suffix(".md").filter(|n| !n.starts_with('.')) {
+            templates.push(info(name, &defaults));
+        }
+    }
+
+    templates.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(templates)
+}
+
+fn info(name: &str, defaults: &BTreeMap<String, String>) -> TemplateInfo {
+    TemplateInfo {
+        name: name.to_string(),
+        path: format!("templates/{}.md", name),
+        default_for: defaults
+            .iter()
+            .filter(|(_, template)| template.trim_end_matches(".md") == name)
+            .map(|(doc_type, _)| doc_type.clone())
+            .collect(),
+    }
+}
+
+/// A template's raw markdown.
+pub fn get(name: &str) -> Result<TemplateWithContent, String> {
+    let path = template_path(name)?;
+    if !path.is_file() {
+        return Err(format!("Template not found: {}", name));
+    }
+    let content = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    let name = name.trim().trim_end_matches(".md");
+
+    Ok(TemplateWithContent {
+        info: info(name, &defaults()),
+        content,
+    })
+}
+
+/// Create or replace a template.
+pub fn save(name: &str, content: &str) -> Result<TemplateWithContent, String> {
+    let path = template_path(name)?;
+    fs::create_dir_all(templates_dir()).map_err(|e| e.to_string())?;
+    filesystem::atomic_write(&path, content.as_bytes())?;
+    get(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ctx() -> TemplateContext {
+        TemplateContext {
+            title: "Standup".to_string(),
+            date: NaiveDate::from_ymd_opt(2026, 3, 2).unwrap(),
+            project: Some("Ops".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_render_placeholders() {
+        let fm: Mapping =
+            serde_yaml::from_str("title: '{{title}} {{date}}'\ntags: [daily]").unwrap();
+        let body = "\n# {{title}} ({{project}})\n\n{{weekday}}, {{date:%B %-d}}\nPrevious: [[{{yesterday}}]]\n\n- {{cursor}}\n{{unknown}} {{date:%Q}}";
+        let rendered = render(&fm, body, &ctx());
+
+        assert_eq!(
+            rendered.frontmatter.get("title").and_then(|v| v.as_str()),
+            Some("Standup 2026-03-02")
+        );
+        assert_eq!(
+            rendered.body,
+            "# Standup (Ops)\n\nMonday, March 2\nPrevious: [[2026-03-01]]\n\n- \n{{unknown}} {{date:%Q}}"
+        );
+        let cursor = rendered.cursor.unwrap();
+        assert_eq!(rendered.body.chars().nth(cursor), Some('\n'));
+        assert!(rendered.body[..cursor].ends_with("- "));
+    }
+
+    #[test]
+    fn test_time_specifier_on_date_is_unknown() {
+        let rendered = render(&Mapping::new(), "{{date:%H}} {{weekday:%p}}", &ctx());
+        assert_eq!(rendered.body, "{{date:%H}} {{weekday:%p}}");
+    }
+}
@@ -7,6 +7,7 @@ use notify_debouncer_full::{new_debouncer, DebouncedEvent};
 use tokio::sync::mpsc;
 
 use crate::config;
+use crate::services::index;
 use crate::websocket::{WsMessage, WsState};
 
 /// Start the file watcher in a background task
@@ -108,6 +109,11 @@ fn process_event(event: &DebouncedEvent, ws_state: &WsState) {
         return;
     }
 
+    // Keep the search index current (external edits, renames, deletions)
+    for path in &paths {
+        index::refresh_file(path);
+    }
+
     // Skip archive and .git directories
     if paths.iter().any(|p| {
         let s = p.to_string_lossy();
@@ -116,7 +122,7 @@ fn process_event(event: &DebouncedEvent, ws_state: &WsState) {
         return;
     }
 
-    let path_str = normalize_path(&paths[0]);
+    let path_str = normalize_path(paths[0]);
 
     // Check if this was a recent save by us (within last 2 seconds)
     if let Ok(mut saves) = RECENT_SAVES.lock() {
@@ -63,9 +63,18 @@ export interface TaskWithContent extends Task {
 }
 
 export interface SearchResult {
+  id: string
   path: string
   title: string
-  matches: { line_number: number; line_content: string }[]
+  note_type: string
+  project_id?: string
+  updated?: string
+  score: number
+  matches: {
+    line_number: number
+    line_content: string
+    highlights: { start: number; end: number }[]
+  }[]
 }
 
 export interface GitStatus {